@@ -10,11 +10,14 @@ use std::sync::mpsc;
 use std::collections::HashMap;
 use std::sync::Mutex;
 use std::thread;
+use std::time::{Duration, Instant};
 use serde_derive::{Serialize, Deserialize};
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::configuration::CommandConfig;
 use crate::configuration::CustomCommandConfig;
+use crate::configuration::ConfigGroup;
 use crate::module::command::CommandModule;
 use crate::utils::sha256;
 use crate::configuration::Hosts;
@@ -24,6 +27,7 @@ use crate::file_handler::write_file_metadata;
 use crate::host_manager::HostManager;
 use crate::module::command::UIAction;
 use crate::module::connection::request_response::RequestResponse;
+use crate::module::connection::SshConnectorSettings;
 use crate::module::module_factory::ModuleFactory;
 use crate::utils::*;
 use crate::{
@@ -39,6 +43,9 @@ use crate::module::{
     command::CommandResult,
 };
 
+/// Command ID used for results of `CommandHandler::test_connection`.
+pub const TEST_CONNECTION_COMMAND_ID: &str = "_test-connection";
+
 // Default needs to be implemented because of Qt QObject requirements.
 #[derive(Default)]
 pub struct CommandHandler {
@@ -54,8 +61,14 @@ pub struct CommandHandler {
     preferences: Preferences,
     /// Effective host configurations.
     hosts_config: Hosts,
-    /// Every execution gets an invocation ID. Valid ID numbers begin from 1.
-    invocation_id_counter: u64,
+    /// Time of last execution for enforcing command cooldowns.
+    /// Host name is the first key, command id is the second key.
+    last_executions: HashMap<String, HashMap<String, Instant>>,
+    /// SSH settings for launching external terminals.
+    /// Host name is the first key, connector instance ID is the second key.
+    ssh_settings: HashMap<String, HashMap<String, SshConnectorSettings>>,
+    /// Direct result channels for invocations started with `execute_with_result`. Invocation ID is the key.
+    result_senders: Arc<Mutex<HashMap<u64, mpsc::Sender<CommandResult>>>>,
 
     // Shared resources.
     /// Mainly for getting up-to-date Host-datas.
@@ -91,6 +104,7 @@ impl CommandHandler {
         };
 
         self.custom_commands.clear();
+        self.ssh_settings.clear();
 
         self.request_sender = Some(request_sender);
         self.state_update_sender = Some(state_update_sender);
@@ -99,7 +113,25 @@ impl CommandHandler {
         self.hosts_config = hosts_config.clone();
 
         for (host_id, host_config) in hosts_config.hosts.iter() {
+            for (instance_id, connector_config) in host_config.effective.connectors.iter() {
+                if connector_config.module_id(instance_id) != "ssh" {
+                    continue;
+                }
+
+                match SshConnectorSettings::from_settings(&connector_config.settings) {
+                    Ok(ssh_settings) => {
+                        self.ssh_settings.entry(host_id.clone()).or_default().insert(instance_id.clone(), ssh_settings);
+                    },
+                    Err(error) => log::error!("[{}][{}] {}", host_id, instance_id, error),
+                }
+            }
+
             for (command_id, command_config) in host_config.effective.commands.iter() {
+                if !CommandConfig::is_enabled(&command_config.enabled) {
+                    log::debug!("[{}] Command \"{}\" is disabled", host_id, command_id);
+                    continue;
+                }
+
                 let command_spec = crate::module::ModuleSpecification::command(command_id, &command_config.version);
                 if let Some(command) = self.module_factory.new_command(&command_spec, &command_config.settings) {
                     self.add_command(host_id, command);
@@ -147,8 +179,30 @@ impl CommandHandler {
         command_collection.entry(module_spec.id).or_insert(command);
     }
 
+    /// True if the command can preview its changes with `dry_run` (see `execute`).
+    pub fn supports_dry_run(&self, host_id: &String, command_id: &String, parameters: &[String]) -> bool {
+        let host = self.host_manager.borrow().get_host(host_id);
+        let commands = self.commands.lock().unwrap();
+
+        match commands.get(host_id).and_then(|host_commands| host_commands.get(command_id)) {
+            Some(command) => !matches!(command.get_connector_message_dry_run(host, parameters.to_owned()),
+                                       Err(error) if error.kind == ErrorKind::NotImplemented),
+            None => false,
+        }
+    }
+
     /// Returns invocation ID or 0 on error.
-    pub fn execute(&mut self, host_id: &String, command_id: &String, parameters: &[String]) -> u64 {
+    /// Commands that have a confirmation text are refused unless `confirmed` is true.
+    /// With `dry_run`, only a preview of the command is run. Dry-runs don't need confirmation and are allowed in read-only mode.
+    pub fn execute(&mut self, host_id: &String, command_id: &String, parameters: &[String], confirmed: bool, dry_run: bool) -> u64 {
+        self.execute_with_result(host_id, command_id, parameters, confirmed, dry_run, None)
+    }
+
+    /// Like `execute`, but the final `CommandResult` of this invocation is also sent to `result_sender`,
+    /// so the caller doesn't have to filter all state updates. Errors are sent as error results.
+    /// Results of commands that open a text editor are not sent.
+    pub fn execute_with_result(&mut self, host_id: &String, command_id: &String, parameters: &[String], confirmed: bool, dry_run: bool,
+                               result_sender: Option<mpsc::Sender<CommandResult>>) -> u64 {
 
         let host = self.host_manager.borrow().get_host(host_id);
 
@@ -161,22 +215,55 @@ impl CommandHandler {
 
         let state_update_sender = self.state_update_sender.as_ref().unwrap().clone();
 
-        let messages = match get_command_connector_messages(&host, command, parameters) {
+        if self.preferences.use_read_only_mode && command.is_mutating() && !dry_run {
+            log::warn!("[{}] Refused to execute command \"{}\" in read-only mode", host_id, command_id);
+            self.send_refusal(&host, command, result_sender, "Command changes the target and is not allowed in read-only mode");
+            return 0;
+        }
+
+        if !confirmed && !dry_run && !command.get_display_options().confirmation_text.is_empty() {
+            log::warn!("[{}] Refused to execute command \"{}\" without confirmation", host_id, command_id);
+            self.send_refusal(&host, command, result_sender, "Command requires confirmation before execution");
+            return 0;
+        }
+
+        if let Some(remaining) = self.get_remaining_cooldown(host_id, command_id) {
+            log::warn!("[{}] Refused to execute command \"{}\" during cooldown", host_id, command_id);
+            self.send_refusal(&host, command, result_sender, format!(
+                "Command was executed recently. Try again in {} seconds.", remaining.as_secs().max(1)
+            ));
+            return 0;
+        }
+
+        if let Err(error) = command.get_display_options().validate_user_input(parameters) {
+            log::warn!("[{}] Refused to execute command \"{}\" with invalid parameters: {}", host_id, command_id, error);
+            self.send_refusal(&host, command, result_sender, format!("Invalid parameter: {}", error));
+            return 0;
+        }
+
+        let messages_result = if dry_run {
+            get_command_dry_run_messages(&host, command, parameters)
+        }
+        else {
+            get_command_connector_messages(&host, command, parameters)
+        };
+
+        let messages = match messages_result {
             Ok(messages) => messages,
             Err(error) => {
                 log::error!("Command failed: {}", error);
-                state_update_sender.send(StateUpdateMessage {
-                    host_name: host.name,
-                    display_options: command.get_display_options(),
-                    module_spec: command.get_module_spec(),
-                    command_result: Some(CommandResult::new_error(error)),
-                    ..Default::default()
-                }).unwrap();
+                self.send_refusal(&host, command, result_sender, error);
                 return 0;
             }
         };
 
-        self.invocation_id_counter += 1;
+        let invocation_id = next_invocation_id();
+        if let Some(result_sender) = result_sender {
+            self.result_senders.lock().unwrap().insert(invocation_id, result_sender);
+        }
+        if !dry_run {
+            self.last_executions.entry(host_id.clone()).or_default().insert(command_id.clone(), Instant::now());
+        }
 
         // Notify host state manager about new command, so it can keep track of pending invocations.
         state_update_sender.send(StateUpdateMessage {
@@ -184,13 +271,18 @@ impl CommandHandler {
             display_options: command.get_display_options(),
             module_spec: command.get_module_spec(),
             command_result: Some(CommandResult::pending()),
-            invocation_id: self.invocation_id_counter,
+            invocation_id: invocation_id,
             ..Default::default()
         }).unwrap();
 
-        let request_type = match command.get_display_options().action == UIAction::FollowOutput {
-            true => RequestType::CommandFollowOutput { commands: messages },
-            false => RequestType::Command { commands: messages }
+        let request_type = if dry_run {
+            RequestType::CommandDryRun { commands: messages }
+        }
+        else if command.get_display_options().action == UIAction::FollowOutput {
+            RequestType::CommandFollowOutput { commands: messages }
+        }
+        else {
+            RequestType::Command { commands: messages }
         };
 
         // Send request to ConnectionManager.
@@ -198,43 +290,48 @@ impl CommandHandler {
             connector_spec: command.get_connector_spec(),
             source_id: command.get_module_spec().id,
             host: host.clone(),
-            invocation_id: self.invocation_id_counter,
+            invocation_id: invocation_id,
             request_type: request_type,
             response_sender: self.new_response_sender(),
         }).unwrap();
 
-        self.invocation_id_counter
+        invocation_id
     }
 
     //
     // INTEGRATED COMMANDS
     //
 
-    pub fn download_editable_file(&mut self, host_id: &String, command_id: &String, remote_file_path: &String) -> (u64, String) {
+    /// Refused in read-only mode if the command is mutating, since the file is meant to be edited.
+    pub fn download_editable_file(&mut self, host_id: &String, command_id: &String, remote_file_path: &String) -> Result<(u64, String), LkError> {
         let host = self.host_manager.borrow().get_host(host_id);
         let commands = self.commands.lock().unwrap();
         let command = &commands[host_id][command_id];
 
+        self.check_read_only_mode(&host, command)?;
+
         let connector_messages = get_command_connector_messages(&host, command, &[remote_file_path.clone()]).map_err(|error| {
             log::error!("Command failed: {}", error);
-            return;
-        }).unwrap();
+            self.send_refusal(&host, command, None, &error);
+            error
+        })?;
 
         let (_, local_file_path) = file_handler::convert_to_local_paths(&host, remote_file_path);
-        self.invocation_id_counter += 1;
+        let invocation_id = next_invocation_id();
 
         self.request_sender.as_ref().unwrap().send(ConnectorRequest {
             connector_spec: command.get_connector_spec(),
             source_id: command.get_module_spec().id,
             host: host.clone(),
-            invocation_id: self.invocation_id_counter,
+            invocation_id: invocation_id,
             response_sender: self.new_response_sender(),
             request_type: RequestType::Download {
                 remote_file_path: connector_messages[0].to_owned(),
+                max_size: self.preferences.max_download_size,
             },
         }).unwrap();
 
-        (self.invocation_id_counter, local_file_path)
+        Ok((invocation_id, local_file_path))
     }
 
     pub fn upload_file(&mut self, host_id: &String, command_id: &String, local_file_path: &String) -> u64 {
@@ -243,7 +340,20 @@ impl CommandHandler {
         let command = &commands[host_id][command_id];
 
         let state_update_sender = self.state_update_sender.as_ref().unwrap().clone();
-        self.invocation_id_counter += 1;
+
+        if self.preferences.use_read_only_mode {
+            log::warn!("[{}] Refused to upload file in read-only mode", host_id);
+            state_update_sender.send(StateUpdateMessage {
+                host_name: host.name,
+                display_options: command.get_display_options(),
+                module_spec: command.get_module_spec(),
+                command_result: Some(CommandResult::new_error("Uploading files is not allowed in read-only mode")),
+                ..Default::default()
+            }).unwrap();
+            return 0;
+        }
+
+        let invocation_id = next_invocation_id();
 
         match file_handler::read_file(local_file_path) {
             Ok((mut metadata, contents)) => {
@@ -255,7 +365,7 @@ impl CommandHandler {
                         display_options: command.get_display_options(),
                         module_spec: command.get_module_spec(),
                         command_result: Some(CommandResult::new_error("File is unchanged")),
-                        invocation_id: self.invocation_id_counter,
+                        invocation_id: invocation_id,
                         ..Default::default()
                     }).unwrap();
                 }
@@ -266,7 +376,7 @@ impl CommandHandler {
                         connector_spec: command.get_connector_spec(),
                         source_id: command.get_module_spec().id,
                         host: host.clone(),
-                        invocation_id: self.invocation_id_counter,
+                        invocation_id: invocation_id,
                         response_sender: self.new_response_sender(),
                         request_type: RequestType::Upload {
                             local_file_path: local_file_path.clone(),
@@ -282,13 +392,13 @@ impl CommandHandler {
                     display_options: command.get_display_options(),
                     module_spec: command.get_module_spec(),
                     command_result: Some(CommandResult::new_critical_error(error)),
-                    invocation_id: self.invocation_id_counter,
+                    invocation_id: invocation_id,
                     ..Default::default()
                 }).unwrap();
             }
         }
 
-        self.invocation_id_counter
+        invocation_id
     }
 
     pub fn verify_host_key(&self, host_id: &String, connector_id: &String, key_id: &String) {
@@ -308,42 +418,94 @@ impl CommandHandler {
         }).unwrap();
     }
 
-    pub fn open_remote_terminal_command(&self, host_id: &String, command_id: &String, parameters: &[String]) -> ShellCommand {
+    /// Tests connecting to the host in the background. See `ConnectionManager::test_connection`.
+    /// Returns invocation ID. Result is received as a regular command result.
+    pub fn test_connection(&self, host_id: &String) -> u64 {
         let host = self.host_manager.borrow().get_host(host_id);
-        let mut command = self.remote_ssh_command(&host);
+        let host_config = self.get_effective_host_config(host_id);
+        let module_factory = self.module_factory.clone();
+        let state_update_sender = self.state_update_sender.as_ref().unwrap().clone();
+        let module_spec = crate::module::ModuleSpecification::command(TEST_CONNECTION_COMMAND_ID, "0.0.1");
+        let invocation_id = next_invocation_id();
+
+        state_update_sender.send(StateUpdateMessage {
+            host_name: host.name.clone(),
+            module_spec: module_spec.clone(),
+            command_result: Some(CommandResult::pending()),
+            invocation_id: invocation_id,
+            ..Default::default()
+        }).unwrap();
+
+        thread::spawn(move || {
+            let mut command_result = ConnectionManager::test_connection(&module_factory, &host, &host_config);
+            command_result.command_id = module_spec.id.clone();
+
+            state_update_sender.send(StateUpdateMessage {
+                host_name: host.name.clone(),
+                module_spec: module_spec,
+                command_result: Some(command_result),
+                invocation_id: invocation_id,
+                ..Default::default()
+            }).unwrap_or_else(|error| log::error!("[{}] Couldn't send connection test result: {}", host.name, error));
+        });
+
+        invocation_id
+    }
+
+    /// Refused in read-only mode if the command is mutating.
+    pub fn open_remote_terminal_command(&self, host_id: &String, command_id: &String, parameters: &[String]) -> Result<ShellCommand, LkError> {
+        let host = self.host_manager.borrow().get_host(host_id);
+        let mut command = self.remote_ssh_command(&host, command_id);
 
         let commands = self.commands.lock().unwrap();
         let command_module = &commands[host_id][command_id];
 
+        self.check_read_only_mode(&host, command_module)?;
+
         let connector_messages = get_command_connector_messages(&host, command_module, parameters).unwrap_or_else(|error| {
             log::error!("Command failed: {}", error);
             Vec::new()
         });
 
         command.arguments(connector_messages);
-        ::log::debug!("Opening terminal with command: {}", command.to_string());
-        command
+        ::log::debug!("Opening terminal with command: {}", secret::redact(&command.to_string()));
+        Ok(command)
     }
 
-    // TODO: this will block the UI thread. Improve!
+    /// Launches the terminal in a background thread. Errors are reported through the state update channel.
     pub fn open_external_terminal(&self, host_id: &String, command_id: &String, parameters: Vec<String>) {
-        let command_args = self.open_remote_terminal_command(host_id, command_id, &parameters);
+        let command_args = match self.open_remote_terminal_command(host_id, command_id, &parameters) {
+            Ok(command_args) => command_args,
+            Err(_) => return,
+        };
 
-        log::debug!("Starting local process: {} {}", self.preferences.terminal, command_args.to_string());
-        let result = ShellCommand::new()
-            .arguments(vec![self.preferences.terminal.clone()])
-            .arguments(self.preferences.terminal_args.clone())
-            .arguments(command_args.to_vec())
-            .execute();
+        log::debug!("Starting local process: {} {}", self.preferences.terminal, secret::redact(&command_args.to_string()));
+        let mut local_command = ShellCommand::new();
+        local_command.arguments(vec![self.preferences.terminal.clone()])
+                     .arguments(self.preferences.terminal_args.clone())
+                     .arguments(command_args.to_vec());
 
-        if let Err(error) = result {
-            log::error!("Couldn't start terminal: {}", error);
-        }
+        let commands = self.commands.lock().unwrap();
+        let command = &commands[host_id][command_id];
+
+        Self::start_local_process(
+            local_command,
+            host_id.clone(),
+            command,
+            self.state_update_sender.as_ref().unwrap().clone(),
+        );
     }
 
-    pub fn open_remote_text_editor(&self, host_id: &String, remote_file_path: &str) -> ShellCommand {
+    /// Refused in read-only mode if the command is mutating.
+    pub fn open_remote_text_editor(&self, host_id: &String, command_id: &String, remote_file_path: &str) -> Result<ShellCommand, LkError> {
         let host = self.host_manager.borrow().get_host(host_id);
-        let mut command = self.remote_ssh_command(&host);
+
+        {
+            let commands = self.commands.lock().unwrap();
+            self.check_read_only_mode(&host, &commands[host_id][command_id])?;
+        }
+
+        let mut command = self.remote_ssh_command(&host, command_id);
 
         if self.preferences.sudo_remote_editor {
             command.argument("sudo");
@@ -351,20 +513,22 @@ impl CommandHandler {
 
         command.argument(self.preferences.remote_text_editor.clone());
         command.argument(remote_file_path);
-        command
+        Ok(command)
     }
 
-    // TODO: this will block the UI thread? Improve!
-    /// Returns local file path where file was downloaded.
-    pub fn open_external_text_editor(&self, host_id: &String, command_id: &String, remote_file_path: &String) -> String {
+    /// Returns local file path where file was downloaded. Refused in read-only mode if the command is mutating.
+    pub fn open_external_text_editor(&self, host_id: &String, command_id: &String, remote_file_path: &String) -> Result<String, LkError> {
         let host = self.host_manager.borrow().get_host(host_id);
         let commands = self.commands.lock().unwrap();
         let command = &commands[host_id][command_id];
 
+        self.check_read_only_mode(&host, command)?;
+
         let connector_messages = get_command_connector_messages(&host, command, &[remote_file_path.clone()]).map_err(|error| {
             log::error!("Command failed: {}", error);
-            return;
-        }).unwrap();
+            self.send_refusal(&host, command, None, &error);
+            error
+        })?;
 
         self.request_sender.as_ref().unwrap().send(ConnectorRequest {
             connector_spec: command.get_connector_spec(),
@@ -375,13 +539,46 @@ impl CommandHandler {
             response_sender: self.new_response_sender(),
             request_type: RequestType::Download {
                 remote_file_path: connector_messages[0].to_owned(),
+                max_size: self.preferences.max_download_size,
             },
         }).unwrap();
 
-        file_handler::convert_to_local_paths(&host, remote_file_path).1
+        Ok(file_handler::convert_to_local_paths(&host, remote_file_path).1)
+    }
+
+    /// Mutating commands are refused in read-only mode. The refusal is also sent as a command result.
+    fn check_read_only_mode(&self, host: &Host, command: &Command) -> Result<(), LkError> {
+        if self.preferences.use_read_only_mode && command.is_mutating() {
+            let command_id = command.get_module_spec().id;
+            log::warn!("[{}] Refused to open command \"{}\" in read-only mode", host.name, command_id);
+
+            let message = "Command changes the target and is not allowed in read-only mode";
+            self.send_refusal(host, command, None, message);
+            Err(LkError::other(message))
+        }
+        else {
+            Ok(())
+        }
     }
 
 
+    /// For commands that are refused or fail before anything is sent to the host.
+    fn send_refusal<Stringable: ToString>(&self, host: &Host, command: &Command, result_sender: Option<mpsc::Sender<CommandResult>>, error: Stringable) {
+        let command_result = CommandResult::new_error(error);
+
+        if let Some(result_sender) = result_sender {
+            result_sender.send(command_result.clone()).unwrap_or_default();
+        }
+
+        self.state_update_sender.as_ref().unwrap().send(StateUpdateMessage {
+            host_name: host.name.clone(),
+            display_options: command.get_display_options(),
+            module_spec: command.get_module_spec(),
+            command_result: Some(command_result),
+            ..Default::default()
+        }).unwrap();
+    }
+
     //
     // RESPONSE HANDLING
     //
@@ -389,6 +586,7 @@ impl CommandHandler {
     pub fn start_processing_responses(&mut self) {
         let thread = Self::_start_processing_responses(
             self.commands.clone(),
+            self.result_senders.clone(),
             self.response_receiver.take().unwrap(),
             self.preferences.clone(),
             self.state_update_sender.as_ref().unwrap().clone()
@@ -399,6 +597,7 @@ impl CommandHandler {
 
     fn _start_processing_responses(
         commands: Arc<Mutex<HashMap<String, HashMap<String, Command>>>>,
+        result_senders: Arc<Mutex<HashMap<u64, mpsc::Sender<CommandResult>>>>,
         receiver: mpsc::Receiver<RequestResponse>,
         preferences: Preferences,
         state_update_sender: mpsc::Sender<StateUpdateMessage>) -> thread::JoinHandle<()> {
@@ -424,6 +623,20 @@ impl CommandHandler {
                 let command = &commands[&response.host.name][&response.source_id];
                 let new_state_update_sender = state_update_sender.clone();
 
+                // Partial responses of followed output keep the channel for the final result.
+                let is_final = response.responses.iter().all(|result| !matches!(result, Ok(message) if message.is_partial));
+                let result_sender = if is_final {
+                    result_senders.lock().unwrap().remove(&response.invocation_id)
+                }
+                else {
+                    None
+                };
+
+                if let RequestType::CommandDryRun { .. } = response.request_type {
+                    Self::process_dry_run_response(command, new_state_update_sender, result_sender, response);
+                    continue;
+                }
+
                 match command.get_display_options().action {
                     UIAction::None |
                     UIAction::FollowOutput |
@@ -431,12 +644,12 @@ impl CommandHandler {
                     UIAction::TextDialog |
                     UIAction::LogView |
                     UIAction::LogViewWithTimeControls =>
-                        Self::process_command_response(command, new_state_update_sender, response),
+                        Self::process_command_response(command, new_state_update_sender, result_sender, response),
                     UIAction::TextEditor => {
                         match response.request_type.clone() {
-                            RequestType::Download { .. } => {
+                            RequestType::Download { max_size, .. } => {
                                 if preferences.text_editor == crate::configuration::INTERNAL {
-                                    Self::process_download_for_internal_editor(command, new_state_update_sender, response);
+                                    Self::process_download_for_internal_editor(command, max_size, new_state_update_sender, response);
                                 }
                                 else {
                                     Self::process_download_for_external_editor(command, &preferences.text_editor, new_state_update_sender, response);
@@ -454,13 +667,44 @@ impl CommandHandler {
         })
     }
 
-    fn process_command_response(
+    fn process_dry_run_response(
         command: &Command,
         state_update_sender: mpsc::Sender<StateUpdateMessage>,
+        result_sender: Option<mpsc::Sender<CommandResult>>,
         response: RequestResponse) {
 
+        let (messages, errors): (Vec<_>, Vec<_>) = response.responses.into_iter().partition(Result::is_ok);
+        let output = messages.into_iter().map(|message| message.unwrap().message).collect::<Vec<_>>().join("\n");
+        let errors = errors.into_iter().map(Result::unwrap_err).collect::<Vec<_>>();
+
+        log::debug!("[{}][{}] Dry-run result received", response.host.name, command.get_module_spec().id);
+
+        let mut command_result = CommandResult::new_info(format!("Dry-run, nothing was changed:\n{}", output));
+        command_result.command_id = command.get_module_spec().id;
+
+        if let Some(result_sender) = result_sender {
+            result_sender.send(command_result.clone()).unwrap_or_default();
+        }
+
+        state_update_sender.send(StateUpdateMessage {
+            host_name: response.host.name,
+            display_options: command.get_display_options(),
+            module_spec: command.get_module_spec(),
+            command_result: Some(command_result),
+            errors: errors,
+            invocation_id: response.invocation_id,
+            ..Default::default()
+        }).unwrap();
+    }
+
+    fn process_command_response(
+        command: &Command,
+        state_update_sender: mpsc::Sender<StateUpdateMessage>,
+        result_sender: Option<mpsc::Sender<CommandResult>>,
+        mut response: RequestResponse) {
+
         let command_id = &command.get_module_spec().id;
-        let (messages, errors): (Vec<_>, Vec<_>) =  response.responses.into_iter().partition(Result::is_ok);
+        let (messages, errors): (Vec<_>, Vec<_>) = std::mem::take(&mut response.responses).into_iter().partition(Result::is_ok);
         let messages = messages.into_iter().map(Result::unwrap).collect::<Vec<_>>();
         let mut errors = errors.into_iter().map(Result::unwrap_err).collect::<Vec<_>>();
 
@@ -487,6 +731,12 @@ impl CommandHandler {
             Ok(mut command_result) => {
                 command_result.command_id = command_id.clone();
 
+                // Modules can set the return code themselves. Otherwise, the first failing exit code is used.
+                if command_result.return_code.is_none() && !messages.is_empty() && messages.iter().all(|message| !message.is_partial) {
+                    let return_code = messages.iter().map(|message| message.return_code).find(|return_code| *return_code != 0).unwrap_or(0);
+                    command_result.return_code = Some(return_code);
+                }
+
                 let log_message = if command_result.message.len() > 5000 {
                     format!("{}...(long message cut)...", &command_result.message[..5000])
                 }
@@ -494,7 +744,7 @@ impl CommandHandler {
                     command_result.message.clone()
                 };
 
-                log::debug!("[{}][{}] Command result received: {}", response.host.name, command_id, log_message);
+                log::debug!("[{}][{}] Command result received: {}", response.host.name, command_id, secret::redact(&log_message));
                 Some(command_result)
             },
             Err(error) => {
@@ -504,7 +754,19 @@ impl CommandHandler {
         };
 
         for error in errors.iter() {
-            log::error!("[{}][{}] Error: {}", response.host.name, error.source_id, error.message);
+            match error.request_index.and_then(|request_index| response.get_request_message(request_index)) {
+                Some(request_message) => log::error!("[{}][{}] Error in \"{}\": {}",
+                    response.host.name, error.source_id, secret::redact(request_message), secret::redact(&error.message)),
+                None => log::error!("[{}][{}] Error: {}", response.host.name, error.source_id, secret::redact(&error.message)),
+            }
+        }
+
+        if let Some(result_sender) = result_sender {
+            let direct_result = match &new_command_result {
+                Some(command_result) => command_result.clone(),
+                None => CommandResult::new_error(errors.iter().map(|error| error.message.clone()).collect::<Vec<_>>().join("\n")),
+            };
+            result_sender.send(direct_result).unwrap_or_default();
         }
 
         state_update_sender.send(StateUpdateMessage {
@@ -519,13 +781,24 @@ impl CommandHandler {
     }
 
 
-    fn process_download_for_internal_editor(command: &Command, state_update_sender: mpsc::Sender<StateUpdateMessage>, response: RequestResponse) {
+    fn process_download_for_internal_editor(
+        command: &Command,
+        max_size: u64,
+        state_update_sender: mpsc::Sender<StateUpdateMessage>,
+        response: RequestResponse
+    ) {
         let message_result = &response.responses[0];
 
         let command_result = match message_result {
             Ok(response_message) => {
-                let (_, contents) = file_handler::read_file(&response_message.message).unwrap();
-                CommandResult::new_hidden(String::from_utf8(contents).unwrap())
+                match file_handler::read_file_with_limit(&response_message.message, max_size) {
+                    Ok((_, contents)) => CommandResult::new_hidden(String::from_utf8_lossy(&contents).to_string()),
+                    Err(error) => {
+                        let error_message = format!("Error reading downloaded file: {}", error);
+                        log::error!("{}", error_message);
+                        CommandResult::new_critical_error(error_message)
+                    }
+                }
             },
             Err(error) => {
                 let error_message = format!("Error downloading file: {}", error);
@@ -557,15 +830,13 @@ impl CommandHandler {
                 let local_file = response_message.message.clone();
                 log::debug!("Starting local process: {} {}", text_editor, local_file);
 
-                // Blocks until finished.
-                let result = ShellCommand::new()
-                    .arguments(vec![text_editor, &local_file])
-                    .execute();
-
-                if let Err(error) = result {
-                    log::error!("Couldn't start text editor: {}", error);
-                    return;
-                }
+                // Editor is run in a separate thread so response processing isn't blocked until it exits.
+                Self::start_local_process(
+                    ShellCommand::new_from(vec![text_editor, &local_file]),
+                    response.host.name.clone(),
+                    command,
+                    state_update_sender,
+                );
             },
             Err(error) => {
                 let error_message = format!("Error downloading file: {}", error);
@@ -627,6 +898,60 @@ impl CommandHandler {
     // HELPER FUNCTIONS
     //
 
+    /// Runs a local process (e.g. terminal or text editor) in a new thread without blocking the caller.
+    /// Failures are sent to HostManager as errors of the related command.
+    fn start_local_process(
+        local_command: ShellCommand,
+        host_name: String,
+        command: &Command,
+        state_update_sender: mpsc::Sender<StateUpdateMessage>) {
+
+        let display_options = command.get_display_options();
+        let module_spec = command.get_module_spec();
+
+        thread::spawn(move || {
+            let error_message = match local_command.execute() {
+                Ok(output) => {
+                    if output.status.success() {
+                        return;
+                    }
+                    format!("Local process exited with {}: {}", output.status, String::from_utf8_lossy(&output.stderr))
+                },
+                Err(error) => format!("Couldn't start local process: {}", error),
+            };
+
+            log::error!("[{}][{}] {}", host_name, module_spec.id, error_message);
+
+            let result = state_update_sender.send(StateUpdateMessage {
+                host_name: host_name,
+                errors: vec![LkError::other(error_message).set_source(&module_spec.id)],
+                display_options: display_options,
+                module_spec: module_spec,
+                ..Default::default()
+            });
+
+            if let Err(error) = result {
+                log::error!("Couldn't send state update: {}", error);
+            }
+        });
+    }
+
+    /// Returns the remaining cooldown time if command was executed on the host too recently.
+    fn get_remaining_cooldown(&self, host_id: &String, command_id: &String) -> Option<Duration> {
+        let cooldown = self.hosts_config.hosts.get(host_id)
+            .and_then(|host_config| host_config.effective.commands.get(command_id))
+            .and_then(|command_config| command_config.cooldown_seconds)
+            .map(Duration::from_secs)?;
+
+        let last_execution = self.last_executions.get(host_id)?.get(command_id)?;
+        cooldown.checked_sub(last_execution.elapsed()).filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Returns the effective (merged) configuration of the host.
+    pub fn get_effective_host_config(&self, host_id: &String) -> ConfigGroup {
+        self.hosts_config.hosts.get(host_id).map(|host_config| host_config.effective.clone()).unwrap_or_default()
+    }
+
     pub fn get_custom_commands_for_host(&self, host_id: &String) -> HashMap<String, CustomCommandConfig> {
         match self.custom_commands.get(host_id) {
             Some(commands) => commands.clone(),
@@ -635,11 +960,16 @@ impl CommandHandler {
     }
 
     // Return value contains host's commands. `parameters` is not set since provided by data point later on.
+    /// Returns commands for the host. Commands whose host-level tag dependencies aren't currently met are left out.
     pub fn get_commands_for_host(&self, host_id: String) -> HashMap<String, CommandButtonData> {
         if let Some(command_collection) = self.commands.lock().unwrap().get(&host_id) {
-            command_collection.iter().map(|(command_id, command)| {
-                (command_id.clone(), CommandButtonData::new(command_id.clone(), command.get_display_options()))
-            }).collect()
+            let host_tags = self.host_manager.borrow().get_host_tags(&host_id);
+
+            command_collection.iter()
+                .map(|(command_id, command)| (command_id, command.get_display_options()))
+                .filter(|(_, display_options)| display_options.host_tags_match(&host_tags))
+                .map(|(command_id, display_options)| (command_id.clone(), CommandButtonData::new(command_id.clone(), display_options)))
+                .collect()
         }
         else {
             HashMap::new()
@@ -679,8 +1009,17 @@ impl CommandHandler {
         }
     }
 
-    fn remote_ssh_command(&self, host: &Host) -> ShellCommand {
-        let ssh_settings = self.hosts_config.hosts[&host.name].effective.connectors["ssh"].settings.clone();
+    /// Uses the same SSH connector instance as the command would (see `CommandConfig::connector`).
+    fn remote_ssh_command(&self, host: &Host, command_id: &String) -> ShellCommand {
+        let instance_id = self.hosts_config.hosts.get(&host.name)
+            .and_then(|host_config| host_config.effective.commands.get(command_id))
+            .and_then(|command_config| command_config.connector.clone())
+            .unwrap_or(String::from("ssh"));
+
+        let ssh_settings = self.ssh_settings.get(&host.name)
+            .and_then(|host_ssh_settings| host_ssh_settings.get(&instance_id))
+            .cloned()
+            .unwrap_or_default();
 
         let remote_address = if !host.fqdn.is_empty() {
             host.fqdn.clone()
@@ -693,15 +1032,19 @@ impl CommandHandler {
         command.arguments(vec![
             String::from("ssh"),
             String::from("-t"),
-            String::from("-p"), ssh_settings.get("port").unwrap_or(&String::from("22")).clone(),
+            String::from("-p"), ssh_settings.port.to_string(),
+            String::from("-l"), ssh_settings.username,
         ]);
 
-        if let Some(username) = ssh_settings.get("username") {
-            command.arguments(vec![String::from("-l"), username.clone()]);
+        if let Some(private_key_path) = ssh_settings.private_key_path {
+            command.arguments(vec![String::from("-i"), private_key_path]);
         }
 
-        if let Some(private_key_path) = ssh_settings.get("private_key_path") {
-            command.arguments(vec![String::from("-i"), private_key_path.clone()]);
+        if let Some(proxy_command) = ssh_settings.proxy_command {
+            command.arguments(vec![String::from("-o"), format!("ProxyCommand={}", proxy_command)]);
+        }
+        else if let Some(socks_proxy) = ssh_settings.socks_proxy {
+            command.arguments(vec![String::from("-o"), format!("ProxyCommand=nc -X 5 -x {} %h %p", socks_proxy)]);
         }
 
         command.argument(remote_address);
@@ -709,6 +1052,20 @@ impl CommandHandler {
     }
 }
 
+fn get_command_dry_run_messages(host: &Host, command: &Command, parameters: &[String]) -> Result<Vec<String>, LkError> {
+    match command.get_connector_message_dry_run(host.clone(), parameters.to_owned()) {
+        Ok(message) => Ok(vec![message]),
+        Err(error) => {
+            if error.kind == ErrorKind::NotImplemented {
+                Err(LkError::other("Dry-run is not supported by this command").set_source(command.get_module_spec().id))
+            }
+            else {
+                Err(error.set_source(command.get_module_spec().id))
+            }
+        }
+    }
+}
+
 fn get_command_connector_messages(host: &Host, command: &Command, parameters: &[String]) -> Result<Vec<String>, LkError> {
     let mut all_messages: Vec<String> = Vec::new();
 
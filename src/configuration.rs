@@ -3,17 +3,22 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::prelude::PermissionsExt;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{collections::HashMap, fs, io};
 
 use serde_derive::{Deserialize, Serialize};
 use serde_yaml;
 
+use crate::error::LkError;
 use crate::file_handler;
 use crate::host::HostSetting;
+use crate::module::connection::{DockerApiSettings, SshConnectorSettings};
+use crate::utils::UnitSystem;
 
 const MAIN_CONFIG_FILE: &str = "config.yml";
 const HOSTS_FILE: &str = "hosts.yml";
@@ -24,6 +29,9 @@ const DEFAULT_HOSTS_CONFIG: &str = include_str!("../hosts.example.yml");
 pub const INTERNAL: &str = "internal";
 pub const CURRENT_SCHEMA_VERSION: u16 = 2;
 
+/// Set when the configuration directory was detected to be read-only while reading the configuration.
+static READ_ONLY_CONFIG_DIR: AtomicBool = AtomicBool::new(false);
+
 #[derive(Serialize, Debug, Deserialize, Default, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct Configuration {
@@ -49,6 +57,12 @@ impl Default for Preferences {
 #[serde(deny_unknown_fields)]
 pub struct Groups {
     pub groups: BTreeMap<String, ConfigGroup>,
+    /// Additional group files. Relative paths are resolved against the configuration directory.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub includes: Vec<String>,
+    /// Groups that were read from included files. These are not written to the base file.
+    #[serde(skip)]
+    pub included_groups: BTreeSet<String>,
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
@@ -57,14 +71,33 @@ pub struct Hosts {
     pub hosts: BTreeMap<String, HostSettings>,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub certificate_monitors: Vec<String>,
+    /// Additional host files. Relative paths are resolved against the configuration directory.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub includes: Vec<String>,
+    /// Hosts that were read from included files. These are not written to the base file.
+    #[serde(skip)]
+    pub included_hosts: BTreeSet<String>,
 }
 
 #[derive(Serialize, Debug, Deserialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct Preferences {
+    /// Set automatically when running inside a Flatpak sandbox. Not related to `use_read_only_mode`.
     #[serde(default)]
     pub use_sandbox_mode: bool,
+    /// Only monitors and read-only commands are allowed to run. Commands that change something on the target are refused.
+    #[serde(default)]
+    pub use_read_only_mode: bool,
     pub refresh_hosts_on_start: bool,
+    /// Open connections to all hosts concurrently on start so that the first refresh is faster.
+    #[serde(default)]
+    pub warm_up_connections: bool,
+    /// Interval in seconds for refreshing monitors automatically. 0 disables automatic refresh.
+    #[serde(default)]
+    pub refresh_interval: u64,
+    /// Interval in seconds for critical monitors. 0 means `refresh_interval` is used.
+    #[serde(default)]
+    pub refresh_interval_critical: u64,
     pub use_remote_editor: bool,
     pub sudo_remote_editor: bool,
     pub remote_text_editor: String,
@@ -79,6 +112,37 @@ pub struct Preferences {
     pub show_monitor_notifications: bool,
     #[serde(default)]
     pub show_charts: bool,
+    /// Maximum size in bytes for downloaded files. Downloads exceeding this are aborted.
+    #[serde(default = "Preferences::default_max_download_size")]
+    pub max_download_size: u64,
+    /// Directory for mutable runtime data (metrics, host logs, known hosts). Uses the XDG data directory if not set.
+    #[serde(default)]
+    pub data_dir: Option<String>,
+    /// Directory for cached data. Uses the XDG cache directory if not set.
+    #[serde(default)]
+    pub cache_dir: Option<String>,
+    /// Directory for downloaded files. Uses the cache directory if not set.
+    #[serde(default)]
+    pub download_dir: Option<String>,
+    /// Downloaded files older than this are removed on startup. 0 disables the cleanup.
+    #[serde(default = "Preferences::default_download_retention_days")]
+    pub download_retention_days: u32,
+    /// Log lines of each host are also written to a separate file in the data directory.
+    #[serde(default)]
+    pub per_host_logs: bool,
+    /// Units used by monitors when displaying byte counts and temperatures.
+    #[serde(default)]
+    pub unit_system: UnitSystem,
+}
+
+impl Preferences {
+    pub fn default_max_download_size() -> u64 {
+        50 * 1024 * 1024
+    }
+
+    pub fn default_download_retention_days() -> u32 {
+        7
+    }
 }
 
 #[derive(Serialize, Debug, Deserialize, Clone)]
@@ -96,6 +160,9 @@ pub struct DisplayOptions {
     pub show_status_bar: bool,
     #[serde(default, skip_serializing_if = "Configuration::always")]
     pub chart_categories: Vec<ChartCategory>,
+    /// Tag name to style. User-defined styles are merged over the defaults.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub tag_styles: HashMap<String, TagStyle>,
 }
 
 impl Default for DisplayOptions {
@@ -125,6 +192,19 @@ pub struct Category {
     pub monitor_order: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub collapsible_commands: Option<Vec<String>>,
+    /// Overrides global tag styles for this category.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub tag_styles: HashMap<String, TagStyle>,
+}
+
+/// Styling for DataPoint tags, e.g. "masked" or "Current".
+#[derive(Serialize, Debug, Deserialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TagStyle {
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub icon: Option<String>,
 }
 
 #[derive(Serialize, Debug, Deserialize, Default, Clone)]
@@ -144,6 +224,9 @@ pub struct HostSettings {
     pub address: String,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub fqdn: String,
+    /// Free-text notes about the host, e.g. "Primary database, handle with care".
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub description: String,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub overrides: ConfigGroup,
     /// Effective configuration after merging everything. Will not be stored in config file, but is available in runtime.
@@ -177,10 +260,28 @@ pub struct ConfigGroup {
     pub connectors: BTreeMap<String, ConnectorConfig>,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub host_settings: Vec<HostSetting>,
+    /// Overrides for category display options. Category name is the key.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub categories: BTreeMap<String, CategoryConfig>,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub config_helper: ConfigHelperData,
 }
 
+/// Group- or host-specific overrides for `Category`. Unset values are inherited.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryConfig {
+    /// Overrides the position of the category, e.g. to show storage first on database hosts.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub priority: Option<u16>,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub command_order: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub monitor_order: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub collapsible_commands: Option<Vec<String>>,
+}
+
 #[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
 pub struct ConfigHelperData {
     pub ignored_commands: Vec<String>,
@@ -196,6 +297,211 @@ impl HostSettings {
     pub fn is_default_address(address: &String) -> bool {
         address == "0.0.0.0"
     }
+
+    /// Setting of the effective SSH connector, e.g. port or username. Empty if not set.
+    fn get_ssh_setting(&self, setting: &str) -> String {
+        self.effective.connectors.get("ssh")
+            .and_then(|connector| connector.settings.get(setting))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl Hosts {
+    /// Returns IDs of hosts matching the selector. Selector can be an exact host ID, a glob pattern
+    /// (e.g. `web-*`, supports `*` and `?`) or a group name prefixed with `@` (e.g. `@prod`).
+    pub fn select_hosts(&self, selector: &str) -> Vec<String> {
+        if let Some(group) = selector.strip_prefix('@') {
+            return self.hosts.iter()
+                .filter(|(_, host_settings)| host_settings.groups.iter().any(|host_group| host_group == group))
+                .map(|(host_id, _)| host_id.clone())
+                .collect();
+        }
+
+        let pattern = selector.chars().map(|character| match character {
+            '*' => String::from(".*"),
+            '?' => String::from("."),
+            _ => regex::escape(&character.to_string()),
+        }).collect::<String>();
+
+        match regex::Regex::new(&format!("^{}$", pattern)) {
+            Ok(regex) => self.hosts.keys().filter(|host_id| regex.is_match(host_id)).cloned().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Exports hosts as CSV with columns name, address, fqdn and groups. Groups are separated with semicolons.
+    pub fn to_csv(&self) -> String {
+        fn quote(field: &str) -> String {
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            }
+            else {
+                field.to_string()
+            }
+        }
+
+        let mut output = String::from("name,address,fqdn,groups\n");
+        for (host_id, host_settings) in self.hosts.iter() {
+            output.push_str(&format!("{},{},{},{}\n",
+                quote(host_id),
+                quote(&host_settings.address),
+                quote(&host_settings.fqdn),
+                quote(&host_settings.groups.join(";")),
+            ));
+        }
+        output
+    }
+
+    /// Exports hosts as an Ansible inventory in INI format. Hosts are listed under their groups.
+    pub fn to_ansible_inventory(&self) -> String {
+        let mut groups = BTreeMap::<String, Vec<String>>::new();
+
+        for (host_id, host_settings) in self.hosts.iter() {
+            let mut variables = Vec::new();
+            if !host_settings.fqdn.is_empty() {
+                variables.push(format!("ansible_host={}", host_settings.fqdn));
+            }
+            else if !HostSettings::is_default_address(&host_settings.address) {
+                variables.push(format!("ansible_host={}", host_settings.address));
+            }
+
+            let port = host_settings.get_ssh_setting("port");
+            if !port.is_empty() {
+                variables.push(format!("ansible_port={}", port));
+            }
+            let username = host_settings.get_ssh_setting("username");
+            if !username.is_empty() {
+                variables.push(format!("ansible_user={}", username));
+            }
+
+            let line = std::iter::once(host_id.clone()).chain(variables).collect::<Vec<_>>().join(" ");
+
+            if host_settings.groups.is_empty() {
+                groups.entry(String::from("ungrouped")).or_default().push(line);
+            }
+            for group in host_settings.groups.iter() {
+                // Ansible group names can't contain dashes.
+                groups.entry(group.replace('-', "_")).or_default().push(line.clone());
+            }
+        }
+
+        groups.iter()
+            .map(|(group, lines)| format!("[{}]\n{}\n", group, lines.join("\n")))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Parses hosts from an OpenSSH client configuration. Only literal host blocks are used; wildcard patterns are skipped.
+    /// Imported hosts get the given groups.
+    pub fn parse_ssh_config(contents: &str, groups: &[String]) -> Vec<(String, HostSettings)> {
+        let mut hosts = Vec::new();
+        let mut current: Vec<(String, HostSettings)> = Vec::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // Keywords are case-insensitive and the value can also be separated with "=".
+            let (keyword, value) = match line.split_once(|character: char| character.is_whitespace() || character == '=') {
+                Some((keyword, value)) => (keyword.to_lowercase(), value.trim_start_matches(|character: char| character.is_whitespace() || character == '=').trim()),
+                None => continue,
+            };
+
+            match keyword.as_str() {
+                "host" | "match" => {
+                    hosts.append(&mut current);
+                    if keyword == "host" {
+                        current = value.split_whitespace()
+                            .filter(|pattern| !pattern.contains(['*', '?', '!']))
+                            .map(|name| (name.to_string(), HostSettings {
+                                groups: groups.to_vec(),
+                                address: HostSettings::default_address(),
+                                fqdn: name.to_string(),
+                                ..Default::default()
+                            }))
+                            .collect();
+                    }
+                },
+                "hostname" => {
+                    for (_, host_settings) in current.iter_mut() {
+                        if value.parse::<std::net::IpAddr>().is_ok() {
+                            host_settings.address = value.to_string();
+                            host_settings.fqdn.clear();
+                        }
+                        else {
+                            host_settings.fqdn = value.to_string();
+                        }
+                    }
+                },
+                "port" | "user" => {
+                    let setting = if keyword == "port" { "port" } else { "username" };
+                    for (_, host_settings) in current.iter_mut() {
+                        host_settings.overrides.connectors.entry(String::from("ssh")).or_default()
+                            .settings.insert(setting.to_string(), value.to_string());
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        hosts.append(&mut current);
+        hosts
+    }
+
+    /// Parses hosts from CSV in the format produced by `to_csv`.
+    pub fn parse_csv(contents: &str) -> Result<Vec<(String, HostSettings)>, String> {
+        fn split_line(line: &str) -> Vec<String> {
+            let mut fields = vec![String::new()];
+            let mut in_quotes = false;
+            let mut characters = line.chars().peekable();
+
+            while let Some(character) = characters.next() {
+                match character {
+                    '"' if in_quotes && characters.peek() == Some(&'"') => {
+                        characters.next();
+                        fields.last_mut().unwrap().push('"');
+                    },
+                    '"' => in_quotes = !in_quotes,
+                    ',' if !in_quotes => fields.push(String::new()),
+                    _ => fields.last_mut().unwrap().push(character),
+                }
+            }
+            fields
+        }
+
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+        let header = lines.next().map(split_line).unwrap_or_default();
+        let column = |name: &str| header.iter().position(|column| column.trim() == name);
+
+        let name_column = column("name").ok_or("Missing column \"name\"")?;
+        let address_column = column("address");
+        let fqdn_column = column("fqdn");
+        let groups_column = column("groups");
+
+        let mut hosts = Vec::new();
+        for line in lines {
+            let fields = split_line(line);
+            let field = |index: Option<usize>| index.and_then(|index| fields.get(index)).map(|field| field.trim().to_string()).unwrap_or_default();
+
+            let name = field(Some(name_column));
+            if name.is_empty() {
+                return Err(format!("Missing host name on line \"{}\"", line));
+            }
+
+            let address = field(address_column);
+            hosts.push((name, HostSettings {
+                groups: field(groups_column).split(';').filter(|group| !group.is_empty()).map(String::from).collect(),
+                address: if address.is_empty() { HostSettings::default_address() } else { address },
+                fqdn: field(fqdn_column),
+                ..Default::default()
+            }));
+        }
+
+        Ok(hosts)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
@@ -207,6 +513,12 @@ pub struct MonitorConfig {
     pub enabled: Option<bool>,
     #[serde(skip_serializing_if = "Configuration::is_default")]
     pub is_critical: Option<bool>,
+    /// Refresh interval in seconds. Overrides the global `refresh_interval`. 0 disables automatic refresh.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub interval: Option<u64>,
+    /// Connector instance to use instead of the default one. See `ConnectorConfig::module`.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub connector: Option<String>,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub settings: HashMap<String, String>,
 }
@@ -231,6 +543,8 @@ impl Default for MonitorConfig {
             version: MonitorConfig::default_version(),
             enabled: MonitorConfig::default_enabled(),
             is_critical: None,
+            interval: None,
+            connector: None,
             settings: HashMap::new(),
         }
     }
@@ -241,6 +555,15 @@ impl Default for MonitorConfig {
 pub struct CommandConfig {
     #[serde(default = "CommandConfig::default_version", skip_serializing_if = "Configuration::version_is_latest")]
     pub version: String,
+    /// Disabled commands are not added for the host. Useful for hiding dangerous commands on specific hosts.
+    #[serde(default = "CommandConfig::default_enabled", skip_serializing_if = "CommandConfig::is_enabled")]
+    pub enabled: Option<bool>,
+    /// Minimum time between executions of the command on the same host. No limit if not set.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub cooldown_seconds: Option<u64>,
+    /// Connector instance to use instead of the default one. See `ConnectorConfig::module`.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub connector: Option<String>,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub settings: HashMap<String, String>,
 }
@@ -249,12 +572,23 @@ impl CommandConfig {
     pub fn default_version() -> String {
         String::from("latest")
     }
+
+    pub fn default_enabled() -> Option<bool> {
+        Some(true)
+    }
+
+    pub fn is_enabled(enabled: &Option<bool>) -> bool {
+        (*enabled).unwrap_or(true)
+    }
 }
 
 impl Default for CommandConfig {
     fn default() -> Self {
         CommandConfig {
             version: CommandConfig::default_version(),
+            enabled: CommandConfig::default_enabled(),
+            cooldown_seconds: None,
+            connector: None,
             settings: HashMap::new(),
         }
     }
@@ -271,10 +605,20 @@ pub struct CustomCommandConfig {
 #[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct ConnectorConfig {
+    /// Connector module ID. Only needed when the connector is configured under an instance name (e.g. "ssh-mgmt")
+    /// so that a host can have multiple connectors of the same type. By default, the key is the module ID.
+    #[serde(default, skip_serializing_if = "Configuration::is_default")]
+    pub module: Option<String>,
     #[serde(default, skip_serializing_if = "Configuration::is_default")]
     pub settings: HashMap<String, String>,
 }
 
+impl ConnectorConfig {
+    pub fn module_id(&self, instance_id: &str) -> String {
+        self.module.clone().unwrap_or(instance_id.to_string())
+    }
+}
+
 impl Configuration {
     #![cfg_attr(any(), rustfmt::skip)]
     pub fn read(config_dir: &str) -> io::Result<(Configuration, Hosts, Groups)> {
@@ -290,11 +634,27 @@ impl Configuration {
         let groups_file_path = config_dir.join(GROUPS_FILE);
         let old_templates_file_path = config_dir.join("templates.yml");
 
+        let read_only = config_dir.is_dir() && !Self::is_dir_writable(&config_dir);
+        READ_ONLY_CONFIG_DIR.store(read_only, Ordering::SeqCst);
+
+        if read_only {
+            log::warn!("Configuration directory {} is read-only. Configuration changes can't be saved.", config_dir.display());
+        }
+
         // If main configuration is missing, this is probably the first run, so create initial configurations.
         if fs::metadata(&main_config_file_path).is_err() {
+            if read_only {
+                let message = format!("Main configuration file {} is missing and the configuration directory is read-only", main_config_file_path.display());
+                return Err(io::Error::new(io::ErrorKind::PermissionDenied, message));
+            }
             Self::write_initial_config(&config_dir)?;
         }
         else if fs::metadata(config_dir.join("templates.yml")).is_ok() {
+            if read_only {
+                let message = String::from("Old templates.yml configuration file found but the configuration directory is read-only");
+                return Err(io::Error::new(io::ErrorKind::PermissionDenied, message));
+            }
+
             log::warn!("Old templates.yml configuration file found. Renaming old configuration files and reinitializing.");
 
             // This is the old groups.yml file. Rename old files with .old suffix and do a new init.
@@ -306,8 +666,10 @@ impl Configuration {
         }
 
         // Make sure directory is protected from reading by others.
-        if let Err(error) = fs::set_permissions(&config_dir, fs::Permissions::from_mode(0o700)) {
-            log::error!("Error while setting config directory permissions: {}", error);
+        if !read_only {
+            if let Err(error) = fs::set_permissions(&config_dir, fs::Permissions::from_mode(0o700)) {
+                log::error!("Error while setting config directory permissions: {}", error);
+            }
         }
 
         log::info!("Reading main configuration from {}", main_config_file_path.display());
@@ -321,6 +683,7 @@ impl Configuration {
 
         // Exceptions. Allow some to be configurable.
         actual_display_options.show_status_bar = main_config.display_options.show_status_bar;
+        actual_display_options.tag_styles.extend(main_config.display_options.tag_styles.clone());
         main_config.display_options = actual_display_options;
 
         log::info!("Reading host configuration from {}", hosts_file_path.display());
@@ -330,8 +693,11 @@ impl Configuration {
 
         log::info!("Reading group configuration from {}", groups_file_path.display());
         let groups_contents = fs::read_to_string(groups_file_path)?;
-        let all_groups = serde_yaml::from_str::<Groups>(groups_contents.as_str())
-                                    .map_err(|error| io::Error::new(io::ErrorKind::Other, error.to_string()))?;
+        let mut all_groups = serde_yaml::from_str::<Groups>(groups_contents.as_str())
+                                        .map_err(|error| io::Error::new(io::ErrorKind::Other, error.to_string()))?;
+
+        Self::read_host_includes(&config_dir, &mut hosts)?;
+        Self::read_group_includes(&config_dir, &mut all_groups)?;
 
         // Check there are no invalid group references.
         let invalid_groups = hosts.hosts.values()
@@ -345,9 +711,23 @@ impl Configuration {
         }
 
         // Merge config groups to form the final, effective config.
-        for (_, host_config) in hosts.hosts.iter_mut() {
+        for (host_id, host_config) in hosts.hosts.iter_mut() {
             host_config.effective = Self::get_effective_group_config(host_config, &all_groups.groups);
 
+            // Invalid connector settings are reported here instead of silently falling back to defaults later.
+            for (instance_id, connector_config) in host_config.effective.connectors.iter() {
+                let validation_result = match connector_config.module_id(instance_id).as_str() {
+                    "ssh" => SshConnectorSettings::from_settings(&connector_config.settings).map(|_| ()),
+                    "docker" => DockerApiSettings::from_settings(&connector_config.settings).map(|_| ()),
+                    _ => continue,
+                };
+
+                if let Err(error) = validation_result {
+                    let error_message = format!("Invalid configuration for host {} (connector {}): {}", host_id, instance_id, error);
+                    return Err(io::Error::new(io::ErrorKind::Other, error_message));
+                }
+            }
+
             // Old, deprecated host overrides.
             let old_overrides = ConfigGroup {
                 commands: host_config.commands.clone(),
@@ -355,6 +735,7 @@ impl Configuration {
                 connectors: host_config.connectors.clone(),
                 custom_commands: Vec::new(),
                 host_settings: host_config.settings.clone(),
+                categories: BTreeMap::new(),
                 config_helper: Default::default(),
             };
 
@@ -371,7 +752,65 @@ impl Configuration {
         Ok((main_config, hosts, all_groups))
     }
 
+    fn read_host_includes(config_dir: &Path, hosts: &mut Hosts) -> io::Result<()> {
+        for include in hosts.includes.clone() {
+            let included = Self::read_include::<Hosts>(config_dir, &include)?;
+            if !included.includes.is_empty() || !included.certificate_monitors.is_empty() {
+                return Err(Self::invalid_config_error(format!("Included file {} can only contain hosts", include)));
+            }
+
+            for (host_id, host_config) in included.hosts {
+                if hosts.hosts.contains_key(&host_id) {
+                    return Err(Self::invalid_config_error(format!("Duplicate host \"{}\" in included file {}", host_id, include)));
+                }
+
+                hosts.included_hosts.insert(host_id.clone());
+                hosts.hosts.insert(host_id, host_config);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_group_includes(config_dir: &Path, groups: &mut Groups) -> io::Result<()> {
+        for include in groups.includes.clone() {
+            let included = Self::read_include::<Groups>(config_dir, &include)?;
+            if !included.includes.is_empty() {
+                return Err(Self::invalid_config_error(format!("Included file {} can only contain groups", include)));
+            }
+
+            for (group_id, group_config) in included.groups {
+                if groups.groups.contains_key(&group_id) {
+                    return Err(Self::invalid_config_error(format!("Duplicate group \"{}\" in included file {}", group_id, include)));
+                }
+
+                groups.included_groups.insert(group_id.clone());
+                groups.groups.insert(group_id, group_config);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_include<T: serde::de::DeserializeOwned>(config_dir: &Path, include: &String) -> io::Result<T> {
+        // Absolute paths replace the configuration directory.
+        let include_path = config_dir.join(include);
+        log::info!("Reading included configuration from {}", include_path.display());
+
+        let contents = fs::read_to_string(&include_path)
+            .map_err(|error| io::Error::new(error.kind(), format!("Failed to read included file {}: {}", include_path.display(), error)))?;
+
+        serde_yaml::from_str::<T>(contents.as_str())
+            .map_err(|error| Self::invalid_config_error(format!("Invalid included file {}: {}", include_path.display(), error)))
+    }
+
+    fn invalid_config_error(message: String) -> io::Error {
+        io::Error::new(io::ErrorKind::Other, LkError::new(crate::error::ErrorKind::InvalidConfig, message))
+    }
+
     /// Merge config groups to form the final, effective config.
+    /// Groups are merged in the order they are listed and host-level overrides last, so e.g. a connector `port`
+    /// set for the host always wins over the one set in a group.
     pub fn get_effective_group_config(host_config: &HostSettings, all_groups: &BTreeMap<String, ConfigGroup>) -> ConfigGroup {
         let mut effective_config = ConfigGroup::default();
 
@@ -387,6 +826,7 @@ impl Configuration {
             connectors: host_config.connectors.clone(),
             custom_commands: Vec::new(),
             host_settings: host_config.settings.clone(),
+            categories: BTreeMap::new(),
             config_helper: Default::default(),
         };
 
@@ -403,8 +843,18 @@ impl Configuration {
         second_config.monitors.iter().for_each(|(monitor_id, new_config)| {
             let mut merged_config = first_config.monitors.get(monitor_id).cloned().unwrap_or_default();
             merged_config.settings.extend(new_config.settings.clone());
+            // Overrides without an explicit version shouldn't reset a version pinned earlier.
+            if !Configuration::version_is_latest(&new_config.version) {
+                merged_config.version = new_config.version.clone();
+            }
             merged_config.enabled = new_config.enabled.clone();
             merged_config.is_critical = new_config.is_critical;
+            if new_config.interval.is_some() {
+                merged_config.interval = new_config.interval;
+            }
+            if new_config.connector.is_some() {
+                merged_config.connector = new_config.connector.clone();
+            }
             result.monitors.insert(monitor_id.clone(), merged_config);
         });
 
@@ -412,12 +862,22 @@ impl Configuration {
             let mut merged_config = first_config.commands.get(command_id).cloned().unwrap_or_default();
             merged_config.settings.extend(new_config.settings.clone());
             merged_config.version = new_config.version.clone();
+            merged_config.enabled = new_config.enabled.clone();
+            if new_config.cooldown_seconds.is_some() {
+                merged_config.cooldown_seconds = new_config.cooldown_seconds;
+            }
+            if new_config.connector.is_some() {
+                merged_config.connector = new_config.connector.clone();
+            }
             result.commands.insert(command_id.clone(), merged_config);
         });
 
         second_config.connectors.iter().for_each(|(connector_id, new_config)| {
             let mut merged_config = first_config.connectors.get(connector_id).cloned().unwrap_or_default();
             merged_config.settings.extend(new_config.settings.clone());
+            if new_config.module.is_some() {
+                merged_config.module = new_config.module.clone();
+            }
             result.connectors.insert(connector_id.clone(), merged_config);
         });
 
@@ -429,9 +889,59 @@ impl Configuration {
             result.host_settings = second_config.host_settings.clone();
         }
 
+        second_config.categories.iter().for_each(|(category_name, new_config)| {
+            let mut merged_config = first_config.categories.get(category_name).cloned().unwrap_or_default();
+            if new_config.priority.is_some() {
+                merged_config.priority = new_config.priority;
+            }
+            if new_config.command_order.is_some() {
+                merged_config.command_order = new_config.command_order.clone();
+            }
+            if new_config.monitor_order.is_some() {
+                merged_config.monitor_order = new_config.monitor_order.clone();
+            }
+            if new_config.collapsible_commands.is_some() {
+                merged_config.collapsible_commands = new_config.collapsible_commands.clone();
+            }
+            result.categories.insert(category_name.clone(), merged_config);
+        });
+
         result
     }
 
+    /// Returns category display options with overrides from the effective host config applied.
+    pub fn get_effective_categories(display_options: &DisplayOptions, effective_config: &ConfigGroup) -> HashMap<String, Category> {
+        let mut categories = display_options.categories.clone();
+
+        for (category_name, category_config) in effective_config.categories.iter() {
+            let category = categories.entry(category_name.clone()).or_default();
+            if let Some(priority) = category_config.priority {
+                category.priority = priority;
+            }
+            if category_config.command_order.is_some() {
+                category.command_order = category_config.command_order.clone();
+            }
+            if category_config.monitor_order.is_some() {
+                category.monitor_order = category_config.monitor_order.clone();
+            }
+            if category_config.collapsible_commands.is_some() {
+                category.collapsible_commands = category_config.collapsible_commands.clone();
+            }
+        }
+
+        categories
+    }
+
+    /// Category names sorted by effective priority. Categories without display options aren't included.
+    pub fn get_category_order(categories: &HashMap<String, Category>) -> Vec<String> {
+        let mut priorities = categories.iter()
+                                       .map(|(category, options)| (category.clone(), options.priority))
+                                       .collect::<Vec<_>>();
+
+        priorities.sort_by(|left, right| left.1.cmp(&right.1).then_with(|| left.0.cmp(&right.0)));
+        priorities.into_iter().map(|(category, _)| category).collect()
+    }
+
     pub fn write_initial_config(config_dir: &PathBuf) -> io::Result<()> {
         let main_config_file_path = config_dir.join(MAIN_CONFIG_FILE);
         let hosts_file_path = config_dir.join(HOSTS_FILE);
@@ -513,8 +1023,9 @@ impl Configuration {
         Ok(())
     }
 
-    /// Writes the hosts.yml configuration file.
-    pub fn write_hosts_config(config_dir: &String, hosts: &Hosts) -> io::Result<()> {
+    /// Reads only the hosts.yml file itself, without included files and without merging groups.
+    /// Meant for changing single hosts with `write_hosts_config` so that nothing else gets rewritten.
+    pub fn read_hosts_config(config_dir: &String) -> io::Result<Hosts> {
         let config_dir = if config_dir.is_empty() {
             file_handler::get_config_dir().unwrap()
         }
@@ -523,36 +1034,50 @@ impl Configuration {
         };
 
         let hosts_file_path = config_dir.join(HOSTS_FILE);
-        let hosts_config_file = fs::OpenOptions::new().write(true).truncate(true).open(hosts_file_path.clone());
-        match hosts_config_file {
-            Ok(mut file) => {
-                let mut sanitized_hosts = hosts.clone();
-                sanitized_hosts
-                    .hosts
-                    .values_mut()
-                    .for_each(|host| host.effective = ConfigGroup::default());
+        let hosts_contents = fs::read_to_string(hosts_file_path)?;
+        serde_yaml::from_str::<Hosts>(hosts_contents.as_str())
+                   .map_err(|error| io::Error::new(io::ErrorKind::Other, error.to_string()))
+    }
 
-                let hosts_config = serde_yaml::to_string(&sanitized_hosts).unwrap();
+    /// Writes the hosts.yml configuration file.
+    pub fn write_hosts_config(config_dir: &String, hosts: &Hosts) -> io::Result<()> {
+        Self::check_writable()?;
 
-                if let Err(error) = file.write_all(hosts_config.as_bytes()) {
-                    let message = format!("Failed to write host configuration file {}: {}", hosts_file_path.to_string_lossy(), error);
-                    return Err(io::Error::new(io::ErrorKind::Other, message));
-                }
-                else {
-                    log::info!("Updated host configuration file {}", hosts_file_path.to_string_lossy());
-                }
-            }
-            Err(error) => {
-                let message = format!("Failed to open host configuration file {}: {}", hosts_file_path.to_string_lossy(), error);
-                return Err(io::Error::new(io::ErrorKind::Other, message));
-            }
+        let config_dir = if config_dir.is_empty() {
+            file_handler::get_config_dir().unwrap()
         }
+        else {
+            Path::new(config_dir).to_path_buf()
+        };
+
+        let mut sanitized_hosts = hosts.clone();
+        sanitized_hosts
+            .hosts
+            .values_mut()
+            .for_each(|host| host.effective = ConfigGroup::default());
+
+        // Included files are left untouched.
+        sanitized_hosts.hosts.retain(|host_id, _| !hosts.included_hosts.contains(host_id));
 
+        let hosts_file_path = config_dir.join(HOSTS_FILE);
+        let hosts_config = Self::to_yaml_preserving_order(&hosts_file_path, &sanitized_hosts).map_err(|error| {
+            let message = format!("Failed to serialize host configuration: {}", error);
+            io::Error::new(io::ErrorKind::Other, message)
+        })?;
+
+        Self::write_config_file(&hosts_file_path, &hosts_config).map_err(|error| {
+            let message = format!("Failed to write host configuration file {}: {}", hosts_file_path.to_string_lossy(), error);
+            io::Error::new(io::ErrorKind::Other, message)
+        })?;
+
+        log::info!("Updated host configuration file {}", hosts_file_path.to_string_lossy());
         Ok(())
     }
 
     /// Writes the groups.yml configuration file.
     pub fn write_groups_config(config_dir: &String, groups: &Groups) -> io::Result<()> {
+        Self::check_writable()?;
+
         let config_dir = if config_dir.is_empty() {
             file_handler::get_config_dir().unwrap()
         }
@@ -560,38 +1085,29 @@ impl Configuration {
             Path::new(config_dir).to_path_buf()
         };
 
+        // Included files are left untouched.
+        let mut base_groups = groups.clone();
+        base_groups.groups.retain(|group_id, _| !groups.included_groups.contains(group_id));
+
         let groups_file_path = config_dir.join(GROUPS_FILE);
-        let groups_config_file = fs::OpenOptions::new().write(true).truncate(true).open(groups_file_path.clone());
-        match groups_config_file {
-            Ok(mut file) => {
-                let groups_config = serde_yaml::to_string(groups).unwrap();
-                if let Err(error) = file.write_all(groups_config.as_bytes()) {
-                    let message = format!(
-                        "Failed to write group configuration file {}: {}",
-                        groups_file_path.to_string_lossy(),
-                        error
-                    );
-                    return Err(io::Error::new(io::ErrorKind::Other, message));
-                }
-                else {
-                    log::info!("Updated group configuration file {}", groups_file_path.to_string_lossy());
-                }
-            }
-            Err(error) => {
-                let message = format!(
-                    "Failed to open group configuration file {}: {}",
-                    groups_file_path.to_string_lossy(),
-                    error
-                );
-                return Err(io::Error::new(io::ErrorKind::Other, message));
-            }
-        }
+        let groups_config = Self::to_yaml_preserving_order(&groups_file_path, &base_groups).map_err(|error| {
+            let message = format!("Failed to serialize group configuration: {}", error);
+            io::Error::new(io::ErrorKind::Other, message)
+        })?;
 
+        Self::write_config_file(&groups_file_path, &groups_config).map_err(|error| {
+            let message = format!("Failed to write group configuration file {}: {}", groups_file_path.to_string_lossy(), error);
+            io::Error::new(io::ErrorKind::Other, message)
+        })?;
+
+        log::info!("Updated group configuration file {}", groups_file_path.to_string_lossy());
         Ok(())
     }
 
     /// Writes the config.yml configuration file.
     pub fn write_main_config(config_dir: &String, config: &Configuration) -> io::Result<()> {
+        Self::check_writable()?;
+
         let config_dir = if config_dir.is_empty() {
             file_handler::get_config_dir().unwrap()
         }
@@ -599,46 +1115,128 @@ impl Configuration {
             Path::new(config_dir).to_path_buf()
         };
 
+        // Display options are currently not really user-configurable.
+        let mut actual_display_options = get_default_main_config().display_options;
+        // Exceptions. Allow some to be configurable.
+        actual_display_options.show_status_bar = config.display_options.show_status_bar;
+        actual_display_options.tag_styles = config.display_options.tag_styles.clone();
+
+        let config_without_display_options = Configuration {
+            preferences: config.preferences.clone(),
+            cache_settings: config.cache_settings.clone(),
+            display_options: actual_display_options,
+            schema_version: config.schema_version.clone(),
+        };
+
         let main_config_file_path = config_dir.join(MAIN_CONFIG_FILE);
-        let main_config_file = fs::OpenOptions::new().write(true).truncate(true).open(main_config_file_path.clone());
-        match main_config_file {
-            Ok(mut file) => {
-                // Display options are currently not really user-configurable.
-                let mut actual_display_options = get_default_main_config().display_options;
-                // Exceptions. Allow some to be configurable.
-                actual_display_options.show_status_bar = config.display_options.show_status_bar;
-
-                let config_without_display_options = Configuration {
-                    preferences: config.preferences.clone(),
-                    cache_settings: config.cache_settings.clone(),
-                    display_options: actual_display_options,
-                    schema_version: config.schema_version.clone(),
-                };
+        let main_config = Self::to_yaml_preserving_order(&main_config_file_path, &config_without_display_options).map_err(|error| {
+            let message = format!("Failed to serialize main configuration: {}", error);
+            io::Error::new(io::ErrorKind::Other, message)
+        })?;
 
-                let main_config = serde_yaml::to_string(&config_without_display_options).unwrap();
-                if let Err(error) = file.write_all(main_config.as_bytes()) {
-                    let message = format!(
-                        "Failed to write main configuration file {}: {}",
-                        main_config_file_path.to_string_lossy(),
-                        error
-                    );
-                    return Err(io::Error::new(io::ErrorKind::Other, message));
-                }
-                else {
-                    log::info!("Updated main configuration file {}", main_config_file_path.to_string_lossy());
+        Self::write_config_file(&main_config_file_path, &main_config).map_err(|error| {
+            let message = format!("Failed to write main configuration file {}: {}", main_config_file_path.to_string_lossy(), error);
+            io::Error::new(io::ErrorKind::Other, message)
+        })?;
+
+        log::info!("Updated main configuration file {}", main_config_file_path.to_string_lossy());
+        Ok(())
+    }
+
+    /// Serializes configuration so that mapping keys (e.g. hosts) keep their order from the existing file. New keys go last.
+    /// Comment lines at the beginning of the existing file are kept. Other comments are currently lost.
+    fn to_yaml_preserving_order<T: serde::Serialize>(file_path: &Path, config: &T) -> Result<String, serde_yaml::Error> {
+        let mut new_value = serde_yaml::to_value(config)?;
+        let existing_contents = fs::read_to_string(file_path).unwrap_or_default();
+
+        if let Ok(existing_value) = serde_yaml::from_str::<serde_yaml::Value>(&existing_contents) {
+            Self::reorder_keys(&mut new_value, &existing_value);
+        }
+
+        let header = existing_contents.lines()
+            .take_while(|line| line.starts_with('#'))
+            .map(|line| format!("{}\n", line))
+            .collect::<String>();
+
+        Ok(header + &serde_yaml::to_string(&new_value)?)
+    }
+
+    /// Recursively orders mapping keys of `value` to match `reference`.
+    fn reorder_keys(value: &mut serde_yaml::Value, reference: &serde_yaml::Value) {
+        if let (serde_yaml::Value::Mapping(mapping), serde_yaml::Value::Mapping(reference_mapping)) = (value, reference) {
+            let mut reordered = serde_yaml::Mapping::new();
+            for key in reference_mapping.keys() {
+                if let Some(child) = mapping.shift_remove(key) {
+                    reordered.insert(key.clone(), child);
                 }
             }
-            Err(error) => {
-                let message = format!(
-                    "Failed to open main configuration file {}: {}",
-                    main_config_file_path.to_string_lossy(),
-                    error
-                );
-                return Err(io::Error::new(io::ErrorKind::Other, message));
+
+            reordered.extend(std::mem::take(mapping));
+
+            for (key, child) in reordered.iter_mut() {
+                if let Some(reference_child) = reference_mapping.get(key) {
+                    Self::reorder_keys(child, reference_child);
+                }
             }
+
+            *mapping = reordered;
+        }
+    }
+
+    /// True if the configuration directory was detected to be read-only, e.g. when it's located in the Nix store
+    /// or in a container image. Configuration changes can't be saved in that case.
+    pub fn is_read_only() -> bool {
+        READ_ONLY_CONFIG_DIR.load(Ordering::SeqCst)
+    }
+
+    fn check_writable() -> io::Result<()> {
+        if Self::is_read_only() {
+            Err(io::Error::new(io::ErrorKind::PermissionDenied, "Configuration directory is read-only. Changes can't be saved."))
         }
+        else {
+            Ok(())
+        }
+    }
 
-        Ok(())
+    /// Checks writability by actually creating a file since permission bits don't tell about read-only mounts.
+    fn is_dir_writable(dir: &Path) -> bool {
+        let test_file_path = dir.join(".write-test");
+        match fs::OpenOptions::new().write(true).create_new(true).open(&test_file_path) {
+            Ok(_) => {
+                fs::remove_file(&test_file_path).unwrap_or_default();
+                true
+            },
+            // Leftover from an earlier run.
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => fs::remove_file(&test_file_path).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    /// Writes to a temporary file in the same directory and then renames it over the target,
+    /// so the original file is never left half-written.
+    fn write_config_file(file_path: &Path, contents: &str) -> io::Result<()> {
+        let mut temporary_file_name = file_path.file_name().unwrap_or_default().to_os_string();
+        temporary_file_name.push(".tmp");
+        let temporary_file_path = file_path.with_file_name(temporary_file_name);
+
+        let result = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(&temporary_file_path)
+            .and_then(|mut file| {
+                file.write_all(contents.as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|_| {
+                // Keep the permissions of the original file.
+                if let Ok(metadata) = fs::metadata(file_path) {
+                    fs::set_permissions(&temporary_file_path, metadata.permissions())?;
+                }
+                fs::rename(&temporary_file_path, file_path)
+            });
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary_file_path);
+        }
+
+        result
     }
 
     /// Helps keep the configuration up-to-date.
@@ -703,3 +1301,32 @@ pub fn get_default_config_groups() -> Groups {
 pub fn get_default_main_config() -> Configuration {
     serde_yaml::from_str(DEFAULT_MAIN_CONFIG).unwrap()
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ssh_connector(port: &str) -> ConnectorConfig {
+        ConnectorConfig {
+            module: None,
+            settings: HashMap::from([(String::from("port"), String::from(port))]),
+        }
+    }
+
+    #[test]
+    fn test_host_connector_settings_override_group() {
+        let mut group = ConfigGroup::default();
+        group.connectors.insert(String::from("ssh"), ssh_connector("22"));
+        let all_groups = BTreeMap::from([(String::from("linux"), group)]);
+
+        let mut host_config = HostSettings {
+            groups: vec![String::from("linux")],
+            ..Default::default()
+        };
+        host_config.overrides.connectors.insert(String::from("ssh"), ssh_connector("2222"));
+
+        let effective = Configuration::get_effective_group_config(&host_config, &all_groups);
+        assert_eq!(effective.connectors["ssh"].settings["port"], "2222");
+    }
+}
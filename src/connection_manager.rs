@@ -11,33 +11,75 @@ use std::{
     sync::Arc,
     sync::Mutex,
     thread,
+    time::{Duration, Instant},
 };
 
 use serde_derive::{Deserialize, Serialize};
 
-use crate::error::LkError;
+use crate::error::{ErrorKind, LkError};
 use crate::module::monitoring::DataPoint;
 use crate::monitor_manager::CERT_MONITOR_HOST_ID;
 use crate::Host;
 use crate::configuration::{ConfigGroup, HostSettings, Hosts};
 use crate::file_handler::{self, FileMetadata};
 use crate::module::{ModuleFactory, ModuleSpecification, ModuleType};
+use crate::module::command::CommandResult;
 use crate::module::connection::*;
+use crate::utils::{secret, sha256, ShellCommand};
 
 use self::request_response::RequestResponse;
 
 
-type ConnectorStates = HashMap<ModuleSpecification, Connector>;
+type HostConnectionStats = Arc<Mutex<HashMap<String, ConnectionStats>>>;
+/// Key is host name/id, connector instance ID and the monitor's cache key. Value contains the time of caching
+/// and the response to the first message of the request.
+type ResponseCache = Arc<Mutex<HashMap<(String, String, String), (Instant, ResponseMessage)>>>;
 
 
 const MAX_WORKER_THREADS: usize = 4;
+/// How long monitor responses with a cache key can be reused. Should be shorter than any sensible refresh interval.
+const RESPONSE_CACHE_TTL: Duration = Duration::from_secs(10);
 
 
+/// Stateful connectors of a host.
+#[derive(Default)]
+struct ConnectorStates {
+    /// Key is the connector instance ID, which is the module ID unless configured otherwise.
+    /// Shared so that connections can be opened without holding the lock (see `warm_up`).
+    connectors: HashMap<String, Arc<Connector>>,
+    /// Monitors and commands that use some other connector instance than the default one.
+    /// Key is the module type and ID of the monitor or command.
+    selected_instances: HashMap<(ModuleType, String), String>,
+}
+
+impl ConnectorStates {
+    fn add(&mut self, source_spec: &ModuleSpecification, connector_spec: &ModuleSpecification, instance_id: &str, connector: Connector) {
+        if instance_id != connector_spec.id {
+            self.selected_instances.insert((source_spec.module_type.clone(), source_spec.id.clone()), instance_id.to_string());
+        }
+        self.connectors.entry(instance_id.to_string()).or_insert(Arc::new(connector));
+    }
+
+    /// Monitor requests come from monitors and everything else from commands.
+    fn get_instance_id(&self, request: &ConnectorRequest, connector_spec: &ModuleSpecification) -> String {
+        let source_type = match request.request_type {
+            RequestType::MonitorCommand { .. } => ModuleType::Monitor,
+            _ => ModuleType::Command,
+        };
+
+        self.selected_instances.get(&(source_type, request.source_id.clone())).cloned()
+            .unwrap_or(connector_spec.id.clone())
+    }
+}
+
 // Default needs to be implemented because of Qt QObject requirements.
 #[derive(Default)]
 pub struct ConnectionManager {
     /// Key is host name/id.
     stateful_connectors: Arc<Mutex<HashMap<String, ConnectorStates>>>,
+    /// Key is host name/id.
+    stats: HostConnectionStats,
+    response_cache: ResponseCache,
     module_factory: Arc<ModuleFactory>,
     /// Only meant for tracking config changes in re-configuration.
     current_config: HashMap<String, ConfigGroup>,
@@ -51,6 +93,8 @@ impl ConnectionManager {
     pub fn new(module_factory: Arc<ModuleFactory>) -> Self {
         ConnectionManager {
             stateful_connectors: Arc::new(Mutex::new(HashMap::new())),
+            stats: Arc::new(Mutex::new(HashMap::new())),
+            response_cache: Arc::new(Mutex::new(HashMap::new())),
             module_factory: module_factory,
             ..Default::default()
         }
@@ -63,11 +107,11 @@ impl ConnectionManager {
 
         let new_host_configs = if stateful_connectors.is_empty() {
             // For certificate monitoring.
-            let cert_monitor_connectors = stateful_connectors.entry(CERT_MONITOR_HOST_ID.to_string()).or_insert(HashMap::new());
+            let cert_monitor_connectors = stateful_connectors.entry(CERT_MONITOR_HOST_ID.to_string()).or_default();
             let mut settings = HashMap::new();
             settings.insert("verify_certificate".to_string(), "true".to_string());
             let cert_monitor_connector = Tcp::new_connection_module(&settings);
-            cert_monitor_connectors.insert(cert_monitor_connector.get_module_spec(), cert_monitor_connector);
+            cert_monitor_connectors.connectors.insert(cert_monitor_connector.get_module_spec().id, Arc::new(cert_monitor_connector));
 
             // All hosts.
             hosts_config.hosts.clone()
@@ -76,7 +120,8 @@ impl ConnectionManager {
             // Re-add hosts that had their config changed.
             for (host_id, new_host_config) in hosts_config.hosts.iter() {
                 if let Some(current_host_config) = self.current_config.get(host_id) {
-                    if current_host_config.connectors != new_host_config.effective.connectors {
+                    if current_host_config.connectors != new_host_config.effective.connectors ||
+                       Self::get_selected_instances(current_host_config) != Self::get_selected_instances(&new_host_config.effective) {
                         stateful_connectors.remove(host_id);
                     }
                 }
@@ -94,7 +139,7 @@ impl ConnectionManager {
 
         // For regular host monitoring.
         for (host_id, host_config) in new_host_configs {
-            let host_connectors = stateful_connectors.entry(host_id.clone()).or_insert(HashMap::new());
+            let host_connectors = stateful_connectors.entry(host_id.clone()).or_default();
 
             for (monitor_id, monitor_config) in host_config.effective.monitors.iter() {
                 let monitor_spec = ModuleSpecification::monitor(monitor_id.as_str(), monitor_config.version.as_str());
@@ -106,18 +151,17 @@ impl ConnectionManager {
                 if let Some(mut connector_spec) = monitor.get_connector_spec() {
                     connector_spec.module_type = ModuleType::Connector;
 
-                    let connector_settings = match host_config.effective.connectors.get(&connector_spec.id) {
-                        Some(config) => config.settings.clone(),
-                        None => HashMap::new(),
-                    };
-
-                    let connector = match self.module_factory.new_connector(&connector_spec, &connector_settings) {
+                    let instance_id = monitor_config.connector.clone().unwrap_or(connector_spec.id.clone());
+                    let connector = match self.new_connector(&host_config, &connector_spec, &instance_id) {
                         Some(connector) => connector,
-                        None => continue,
+                        None => {
+                            log::error!("[{}] No usable connector \"{}\" for monitor \"{}\"", host_id, instance_id, monitor_id);
+                            continue;
+                        }
                     };
 
                     if !connector.get_metadata_self().is_stateless {
-                        host_connectors.entry(connector_spec).or_insert_with(|| connector);
+                        host_connectors.add(&monitor_spec, &connector_spec, &instance_id, connector);
                     }
                 }
             }
@@ -130,18 +174,17 @@ impl ConnectionManager {
                 };
 
                 if let Some(connector_spec) = command.get_connector_spec() {
-                    let connector_settings = match host_config.effective.connectors.get(&connector_spec.id) {
-                        Some(config) => config.settings.clone(),
-                        None => HashMap::new(),
-                    };
-
-                    let connector = match self.module_factory.new_connector(&connector_spec, &connector_settings) {
+                    let instance_id = command_config.connector.clone().unwrap_or(connector_spec.id.clone());
+                    let connector = match self.new_connector(&host_config, &connector_spec, &instance_id) {
                         Some(connector) => connector,
-                        None => continue,
+                        None => {
+                            log::error!("[{}] No usable connector \"{}\" for command \"{}\"", host_id, instance_id, command_id);
+                            continue;
+                        }
                     };
 
                     if !connector.get_metadata_self().is_stateless {
-                        host_connectors.entry(connector_spec).or_insert_with(|| connector);
+                        host_connectors.add(&command_spec, &connector_spec, &instance_id, connector);
                     }
                 }
             }
@@ -156,6 +199,120 @@ impl ConnectionManager {
         self.request_sender_prototype = Some(sender);
     }
 
+    /// Connector instances explicitly selected by monitors and commands.
+    fn get_selected_instances(config: &ConfigGroup) -> Vec<(String, String)> {
+        let monitor_instances = config.monitors.iter()
+            .filter_map(|(id, config)| config.connector.clone().map(|connector| (id.clone(), connector)));
+        let command_instances = config.commands.iter()
+            .filter_map(|(id, config)| config.connector.clone().map(|connector| (id.clone(), connector)));
+        monitor_instances.chain(command_instances).collect()
+    }
+
+    /// Settings come from the connector instance. Instance has to be of the type the module expects.
+    fn new_connector(&self, host_config: &HostSettings, connector_spec: &ModuleSpecification, instance_id: &str) -> Option<Connector> {
+        let connector_settings = match host_config.effective.connectors.get(instance_id) {
+            Some(config) => {
+                if config.module_id(instance_id) != connector_spec.id {
+                    log::error!("Connector \"{}\" is not a {} connector", instance_id, connector_spec.id);
+                    return None;
+                }
+                config.settings.clone()
+            },
+            None => HashMap::new(),
+        };
+
+        self.module_factory.new_connector(connector_spec, &connector_settings)
+    }
+
+    /// Opens connections to the hosts concurrently in the background so that the first monitor refresh doesn't
+    /// have to connect to every host one by one. Uses the same number of threads as request processing.
+    /// Failures are recorded in connection stats and otherwise reported when the connection is actually used.
+    pub fn warm_up(&self, hosts: Vec<Host>) {
+        let targets = {
+            let stateful_connectors = self.stateful_connectors.lock().unwrap();
+            hosts.into_iter().flat_map(|host| {
+                stateful_connectors.get(&host.name)
+                    .map(|host_connectors| host_connectors.connectors.values().cloned().collect::<Vec<_>>())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |connector| (host.clone(), connector))
+            }).collect::<Vec<_>>()
+        };
+
+        let stats = self.stats.clone();
+        thread::spawn(move || {
+            let worker_pool = rayon::ThreadPoolBuilder::new().num_threads(MAX_WORKER_THREADS).build().unwrap();
+            worker_pool.scope(|scope| {
+                for (host, connector) in targets {
+                    let stats = stats.clone();
+                    scope.spawn(move |_| {
+                        connector.set_target(&host.get_address());
+                        if let Err(error) = connector.open_connection() {
+                            log::warn!("[{}] Failed to open connection in advance: {}", host.name, error);
+                            if error.kind == ErrorKind::ConnectionFailed {
+                                stats.lock().unwrap().entry(host.name.clone()).or_default().connect_failures += 1;
+                            }
+                        }
+                    });
+                }
+            });
+            log::debug!("Finished opening connections");
+        });
+    }
+
+    /// Opens a new SSH connection to the host and runs a trivial command to check that connecting and authentication work.
+    /// Doesn't use the connectors of monitors and commands, so it works before any are configured.
+    /// Blocks until the connection succeeds or fails. The error (e.g. authentication, timeout or host key) is passed in the result.
+    pub fn test_connection(module_factory: &ModuleFactory, host: &Host, host_config: &ConfigGroup) -> CommandResult {
+        let connector_spec = ModuleSpecification::connector("ssh", "0.0.1");
+        let connector_settings = host_config.connectors.get("ssh").map(|config| config.settings.clone()).unwrap_or_default();
+
+        let connector = match module_factory.new_connector(&connector_spec, &connector_settings) {
+            Some(connector) => connector,
+            None => return CommandResult::new_error("Failed to create SSH connector"),
+        };
+
+        log::debug!("[{}] Testing connection", host.name);
+        connector.set_target(&host.get_address());
+
+        let result = connector.open_connection().and_then(|_| connector.send_message("echo lightkeeper"));
+        let command_result = match result {
+            Ok(response) if response.is_success() && response.message.trim() == "lightkeeper" => {
+                CommandResult::new_info(format!("Connected successfully to {}", host.get_address()))
+            },
+            Ok(response) => {
+                CommandResult::new_error(format!("Connected, but test command failed: {}", response.message))
+                              .with_return_code(response.return_code)
+            },
+            Err(error) => {
+                let reason = match error.kind {
+                    ErrorKind::ConnectionFailed => "Connection failed",
+                    ErrorKind::HostKeyNotVerified => "Host key is not verified",
+                    ErrorKind::InvalidConfig => "Invalid SSH settings",
+                    _ => "Failed to connect",
+                };
+                CommandResult::new_error(format!("{}: {}", reason, error))
+            },
+        };
+
+        if command_result.error.is_empty() {
+            log::info!("[{}] Connection test succeeded", host.name);
+        }
+        else {
+            log::warn!("[{}] Connection test failed: {}", host.name, command_result.error);
+        }
+
+        command_result
+    }
+
+    /// Connection statistics of a host for diagnosing slow refreshes.
+    pub fn get_stats(&self, host_id: &String) -> ConnectionStats {
+        let mut stats = self.stats.lock().unwrap().get(host_id).cloned().unwrap_or_default();
+        // Not locking `stateful_connectors` since it's held by workers while requests are processed.
+        stats.connection_count = self.current_config.get(host_id).map(|config| config.connectors.len()).unwrap_or_default();
+        stats
+    }
+
     pub fn new_request_sender(&mut self) -> mpsc::Sender<ConnectorRequest> {
         self.request_sender_prototype.as_ref().unwrap().clone()
     }
@@ -163,6 +320,8 @@ impl ConnectionManager {
     pub fn start_processing_requests(&mut self) {
         let thread = Self::process_requests(
             self.stateful_connectors.clone(),
+            self.stats.clone(),
+            self.response_cache.clone(),
             self.request_receiver.take().unwrap(),
             self.module_factory.clone(),
         );
@@ -181,6 +340,8 @@ impl ConnectionManager {
 
     fn process_requests(
         stateful_connectors: Arc<Mutex<HashMap<String, ConnectorStates>>>,
+        stats: HostConnectionStats,
+        response_cache: ResponseCache,
         receiver: mpsc::Receiver<ConnectorRequest>,
         module_factory: Arc<ModuleFactory>) -> thread::JoinHandle<()> {
 
@@ -190,6 +351,8 @@ impl ConnectionManager {
 
             loop {
                 let stateful_connectors = stateful_connectors.clone();
+                let stats = stats.clone();
+                let response_cache = response_cache.clone();
                 let module_factory = module_factory.clone();
 
                 let request = match receiver.recv() {
@@ -214,36 +377,47 @@ impl ConnectionManager {
                     }
                 };
 
+                stats.lock().unwrap().entry(request.host.name.clone()).or_default().queued_requests += 1;
+
                 worker_pool.spawn(move || {
-                    let stateful_connectors = stateful_connectors.lock().unwrap();
+                    stats.lock().unwrap().entry(request.host.name.clone()).or_default().queued_requests -= 1;
 
                     log::debug!("[{}][{}] Worker {} processing a request",
                         request.host.name, request.source_id, rayon::current_thread_index().unwrap_or_default());
 
-                    let connector_metadata = module_factory.get_connector_module_metadata(&connector_spec);
+                    let connector_metadata = match module_factory.get_connector_module_metadata(&connector_spec) {
+                        Some(metadata) => metadata,
+                        None => {
+                            let message = format!("Connector module '{}' required by '{}' was not found", connector_spec, request.source_id);
+                            return Self::send_error_response(&request, LkError::new(ErrorKind::InvalidConfig, message));
+                        }
+                    };
+                    let mut instance_id = connector_spec.id.clone();
 
                     // Stateless connectors.
                     let connector = if connector_metadata.is_stateless {
                         match module_factory.new_connector(&connector_spec, &HashMap::new()) {
-                            Some(connector) => &mut Box::new(connector),
-                            None => return,
+                            Some(connector) => Arc::new(connector),
+                            None => {
+                                let message = format!("Failed to create connector '{}' required by '{}'", connector_spec.id, request.source_id);
+                                return Self::send_error_response(&request, LkError::new(ErrorKind::InvalidConfig, message));
+                            }
                         }
                     }
                     // Stateful connectors.
                     else {
-                        let host_connectors = match stateful_connectors.get(&request.host.name) {
-                            Some(connectors) => connectors,
-                            None => {
-                                log::error!("[{}][{}] host connection is not configured", request.host.name, request.source_id);
-                                return;
-                            }
-                        };
-
-                        match host_connectors.get(&connector_spec) {
-                            Some(connector) => connector,
+                        // Lock is only held for the lookup so that requests to different hosts (and different sessions
+                        // of the same host) are processed concurrently.
+                        let stateful_connectors = stateful_connectors.lock().unwrap();
+                        instance_id = stateful_connectors.get(&request.host.name)
+                            .map(|host_connectors| host_connectors.get_instance_id(&request, &connector_spec))
+                            .unwrap_or(instance_id);
+
+                        match stateful_connectors.get(&request.host.name).and_then(|host_connectors| host_connectors.connectors.get(&instance_id)) {
+                            Some(connector) => connector.clone(),
                             None => {
-                                log::error!("[{}][{}] host connection is not configured", request.host.name, request.source_id);
-                                return;
+                                let message = format!("Host '{}' has no connector '{}' required by '{}'", request.host.name, instance_id, request.source_id);
+                                return Self::send_error_response(&request, LkError::new(ErrorKind::InvalidConfig, message));
                             }
                         }
                     };
@@ -261,11 +435,39 @@ impl ConnectionManager {
                     }
 
                     let responses = match &request.request_type {
-                        RequestType::MonitorCommand { extension_monitors: _, parent_datapoint: _, commands } => {
-                            Self::process_commands(&request, &connector, &commands)
+                        RequestType::MonitorCommand { extension_monitors: _, parent_datapoint: _, commands, cache_key: Some(cache_key) } => {
+                            // Only the first message is shared. The rest are specific to the monitor and always executed.
+                            let key = (request.host.name.clone(), instance_id.clone(), cache_key.clone());
+                            let cached = response_cache.lock().unwrap().get(&key)
+                                .filter(|(cache_time, _)| cache_time.elapsed() < RESPONSE_CACHE_TTL && !commands.is_empty())
+                                .map(|(_, response)| response.clone());
+
+                            if let Some(response) = cached {
+                                log::debug!("[{}][{}] Using cached response for {}", request.host.name, request.source_id, cache_key);
+                                let mut results = vec![Ok(response)];
+                                results.extend(Self::process_commands(&request, &connector, &commands[1..].to_vec(), &stats));
+                                results
+                            }
+                            else {
+                                let results = Self::process_commands(&request, &connector, &commands, &stats);
+                                if let Some(Ok(response)) = results.first() {
+                                    if response.is_success() {
+                                        response_cache.lock().unwrap().insert(key, (Instant::now(), response.clone()));
+                                    }
+                                }
+                                results
+                            }
+                        },
+                        RequestType::MonitorCommand { extension_monitors: _, parent_datapoint: _, commands, cache_key: None } => {
+                            Self::process_commands(&request, &connector, &commands, &stats)
                         },
                         RequestType::Command { commands } => {
-                            Self::process_commands(&request, &connector, &commands)
+                            let results = Self::process_commands(&request, &connector, &commands, &stats);
+                            Self::invalidate_cached_responses(&response_cache, &request.host.name);
+                            results
+                        },
+                        RequestType::CommandDryRun { commands } => {
+                            Self::process_commands(&request, &connector, &commands, &stats)
                         },
                         RequestType::CommandFollowOutput { commands } => {
                             if commands.len() != 1 {
@@ -273,13 +475,18 @@ impl ConnectionManager {
                             }
                             else {
                                 let command = commands.first().unwrap();
-                                vec![Self::process_command_follow_output(&request, &connector, command, request.response_sender.clone())]
+                                let result = Self::process_command_follow_output(&request, &connector, command, request.response_sender.clone());
+                                Self::invalidate_cached_responses(&response_cache, &request.host.name);
+                                vec![result]
                             }
                         },
-                        RequestType::Download { remote_file_path: file_path } =>
-                            vec![Self::process_download(&request.host, &connector, &file_path)],
-                        RequestType::Upload { metadata: _, local_file_path } =>
-                            vec![Self::process_upload(&request.host, &connector, &local_file_path)],
+                        RequestType::Download { remote_file_path: file_path, max_size } =>
+                            vec![Self::process_download(&request.host, &connector, &file_path, *max_size)],
+                        RequestType::Upload { metadata: _, local_file_path } => {
+                            let result = Self::process_upload(&request.host, &connector, &local_file_path);
+                            Self::invalidate_cached_responses(&response_cache, &request.host.name);
+                            vec![result]
+                        },
                         _ => {
                             log::error!("[{}][{}] Unsupported request type", request.host.name, request.source_id);
                             vec![Err(LkError::other("Unsupported request type"))]
@@ -295,34 +502,52 @@ impl ConnectionManager {
         })
     }
 
+    /// Commands can change the state of the host, so monitors shouldn't get responses from before it.
+    fn invalidate_cached_responses(response_cache: &ResponseCache, host_name: &str) {
+        response_cache.lock().unwrap().retain(|(cached_host, _, _), _| cached_host != host_name);
+    }
+
+    /// Request can't be processed at all. Error is passed back so the invocation doesn't stay pending.
+    fn send_error_response(request: &ConnectorRequest, error: LkError) {
+        log::error!("[{}][{}] {}", request.host.name, request.source_id, error);
+        request.response_sender.send(RequestResponse::new_error(request, error)).unwrap_or_else(|_response|
+            log::warn!("[{}][{}] Couldn't process response", request.host.name, request.source_id)
+        );
+    }
+
     fn process_commands(request: &ConnectorRequest,
                         connector: &Connector,
-                        request_messages: &Vec<String>) -> Vec<Result<ResponseMessage, LkError>> {
+                        request_messages: &Vec<String>,
+                        stats: &HostConnectionStats) -> Vec<Result<ResponseMessage, LkError>> {
 
         // let request = request.lock().unwrap();
         let mut results = Vec::new();
-        for request_message in request_messages {
+        for (request_index, request_message) in request_messages.iter().enumerate() {
             // Some commands are supposed to not actually execute.
             if request_message.is_empty() {
                 log::debug!("[{}][{}] Ignoring empty command", request.host.name, request.source_id);
                 results.push(Ok(ResponseMessage::empty()));
             }
             else {
-                log::debug!("[{}][{}] Command: {}", request.host.name, request.source_id, request_message);
+                log::debug!("[{}][{}] Command: {}", request.host.name, request.source_id, secret::redact(request_message));
             }
 
+            let start_time = Instant::now();
             let response_result = connector.send_message(request_message);
+            stats.lock().unwrap().entry(request.host.name.clone()).or_default().record(start_time.elapsed(), &response_result);
 
             if let Ok(response) = response_result {
                 if response.return_code != 0 {
-                    log::warn!("[{}][{}] Command returned non-zero exit code: {}",
-                        request.host.name, request.source_id, response.return_code);
+                    log::warn!("[{}][{}] Command {} returned non-zero exit code: {}",
+                        request.host.name, request.source_id, request_index + 1, response.return_code);
                 }
                 results.push(Ok(response))
             }
             else {
-                // Add module name to error details.
-                results.push(response_result.map_err(|error| error.set_source(connector.get_module_spec().id)));
+                log::error!("[{}][{}] Command {} ({}) failed", request.host.name, request.source_id, request_index + 1, secret::redact(request_message));
+
+                // Add module name and failed message to error details.
+                results.push(response_result.map_err(|error| error.set_source(connector.get_module_spec().id).set_request_index(request_index)));
 
                 // Abort on any errors.
                 break;
@@ -339,7 +564,7 @@ impl ConnectionManager {
         response_sender: mpsc::Sender<RequestResponse>,
     ) -> Result<ResponseMessage, LkError> {
 
-        log::debug!("[{}][{}] Command: {}", request.host.name, request.source_id, request_message);
+        log::debug!("[{}][{}] Command: {}", request.host.name, request.source_id, secret::redact(request_message));
         let mut response_message_result = connector.send_message_partial(request_message, request.invocation_id);
 
         // Paradoxical name...
@@ -377,16 +602,24 @@ impl ConnectionManager {
 
     }
 
-    fn process_download(host: &Host, connector: &Connector, file_path: &str) -> Result<ResponseMessage, LkError> {
+    fn process_download(host: &Host, connector: &Connector, file_path: &str, max_size: u64) -> Result<ResponseMessage, LkError> {
         log::debug!("[{}] Downloading file: {}", host.name, file_path);
-        match connector.download_file(file_path) {
-            Ok((metadata, contents)) => {
-                match file_handler::create_file(host, file_path, metadata, contents) {
-                    Ok(file_path) => Ok(ResponseMessage::new_success(file_path)),
-                    Err(error) => Err(error.into()),
+        let (local_file_path, mut local_file) = file_handler::create_download_file(host, file_path)?;
+
+        match connector.download_file(file_path, &mut local_file, max_size) {
+            Ok(mut metadata) => {
+                metadata.local_path = Some(local_file_path.clone());
+                file_handler::write_file_metadata(metadata)?;
+                Ok(ResponseMessage::new_success(local_file_path))
+            },
+            Err(error) => {
+                // Don't leave partial downloads behind.
+                drop(local_file);
+                if let Err(remove_error) = std::fs::remove_file(&local_file_path) {
+                    log::error!("[{}] Failed to remove partial download {}: {}", host.name, local_file_path, remove_error);
                 }
+                Err(error)
             },
-            Err(error) => Err(error),
         }
     }
 
@@ -394,14 +627,33 @@ impl ConnectionManager {
         log::debug!("[{}] Uploading file: {}", host.name, local_file_path);
         match file_handler::read_file(local_file_path) {
             Ok((metadata, contents)) => {
-                let result = connector.upload_file(&metadata, contents);
-
-                // Returns empty or error as is.
-                result.map(|_| ResponseMessage::empty())
+                let local_hash = sha256::hash(&contents);
+                connector.upload_file(&metadata, contents)?;
+                Self::verify_upload(connector, &metadata.remote_path, &local_hash)?;
+                Ok(ResponseMessage::empty())
             },
             Err(error) => Err(error.into()),
         }
     }
+
+    /// Compares checksum of the uploaded remote file to the local one to catch e.g. truncated transfers.
+    fn verify_upload(connector: &Connector, remote_file_path: &str, local_hash: &str) -> Result<(), LkError> {
+        let command = ShellCommand::new_from(vec!["sha256sum", remote_file_path]);
+        let response = connector.send_message(&command.to_string())?;
+
+        if !response.is_success() {
+            return Err(LkError::other_p("Failed to verify uploaded file", &response.message));
+        }
+
+        let remote_hash = response.message.split_whitespace().next().unwrap_or_default();
+        if remote_hash != local_hash {
+            return Err(LkError::other(format!(
+                "Uploaded file is corrupted: checksum {} doesn't match the local checksum {}", remote_hash, local_hash
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 pub struct ConnectorRequest {
@@ -433,12 +685,42 @@ impl Debug for ConnectorRequest {
     }
 }
 
+#[derive(Default, Clone, Debug, Serialize)]
+pub struct ConnectionStats {
+    /// Number of configured connectors.
+    pub connection_count: usize,
+    /// Requests waiting for a free worker thread.
+    pub queued_requests: usize,
+    pub command_count: u64,
+    /// Includes the time spent on connecting.
+    #[serde(skip)]
+    pub total_command_latency: Duration,
+    pub average_command_latency_ms: u64,
+    pub connect_failures: u64,
+}
+
+impl ConnectionStats {
+    fn record(&mut self, latency: Duration, result: &Result<ResponseMessage, LkError>) {
+        self.command_count += 1;
+        self.total_command_latency += latency;
+        self.average_command_latency_ms = self.total_command_latency.as_millis() as u64 / self.command_count;
+
+        if let Err(error) = result {
+            if error.kind == ErrorKind::ConnectionFailed {
+                self.connect_failures += 1;
+            }
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub enum RequestType {
     MonitorCommand {
         extension_monitors: Vec<String>,
         parent_datapoint: Option<DataPoint>,
         commands: Vec<String>,
+        /// See `MonitoringModule::get_cache_key`.
+        cache_key: Option<String>,
     },
     Command {
         commands: Vec<String>,
@@ -446,8 +728,14 @@ pub enum RequestType {
     CommandFollowOutput {
         commands: Vec<String>,
     },
+    /// Preview of a command. Response is shown as-is instead of being processed by the command module.
+    CommandDryRun {
+        commands: Vec<String>,
+    },
     Download {
         remote_file_path: String,
+        /// Maximum file size in bytes.
+        max_size: u64,
     },
     Upload {
         local_file_path: String,
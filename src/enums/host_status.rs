@@ -9,14 +9,20 @@ use std::str::FromStr;
 
 use serde_derive::{Deserialize, Serialize};
 
-#[derive(Serialize, Default, Deserialize, Clone, Copy)]
+#[derive(Serialize, Default, Deserialize, Clone, Copy, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum HostStatus {
     Unknown,
     #[default]
     Pending,
     Up,
+    /// Host is up but some non-critical monitors are at warning level or worse.
+    Warning,
     Down,
+    /// Connecting to the host failed. Unlike Down, monitor results are not known.
+    Unreachable,
+    /// Host is under planned maintenance. Monitoring continues but alerts are suppressed.
+    Maintenance,
 }
 
 impl FromStr for HostStatus {
@@ -27,7 +33,10 @@ impl FromStr for HostStatus {
             "unknown" => Ok(HostStatus::Unknown),
             "pending" => Ok(HostStatus::Pending),
             "up" => Ok(HostStatus::Up),
+            "warning" => Ok(HostStatus::Warning),
             "down" => Ok(HostStatus::Down),
+            "unreachable" => Ok(HostStatus::Unreachable),
+            "maintenance" => Ok(HostStatus::Maintenance),
             _ => panic!("Invalid HostStatus '{}'", s),
         }
     }
@@ -39,7 +48,10 @@ impl Display for HostStatus {
             HostStatus::Unknown => write!(f, "unknown"),
             HostStatus::Pending => write!(f, "pending"),
             HostStatus::Up => write!(f, "up"),
+            HostStatus::Warning => write!(f, "warning"),
             HostStatus::Down => write!(f, "down"),
+            HostStatus::Unreachable => write!(f, "unreachable"),
+            HostStatus::Maintenance => write!(f, "maintenance"),
         }
     }
 }
@@ -14,6 +14,8 @@ pub struct LkError {
     pub kind: ErrorKind,
     pub message: String,
     pub parameter: Option<String>,
+    /// Index of the failed message in a multi-message request.
+    pub request_index: Option<usize>,
 }
 
 impl LkError {
@@ -23,6 +25,7 @@ impl LkError {
             kind: kind,
             message: message.to_string(),
             parameter: None,
+            request_index: None,
         }
     }
 
@@ -32,6 +35,18 @@ impl LkError {
             kind: ErrorKind::InvalidParameter,
             message: message.to_string(),
             parameter: Some(value.to_string()),
+            request_index: None,
+        }
+    }
+
+    /// For command parameters that weren't provided. Parameter is the index of the missing parameter.
+    pub fn missing_parameter(index: usize) -> LkError {
+        LkError {
+            source_id: String::new(),
+            kind: ErrorKind::InvalidParameter,
+            message: format!("Missing parameter {}", index + 1),
+            parameter: Some(index.to_string()),
+            request_index: None,
         }
     }
 
@@ -41,6 +56,7 @@ impl LkError {
             kind: ErrorKind::NotImplemented,
             message: "Not implemented".to_string(),
             parameter: None,
+            request_index: None,
         }
     }
 
@@ -50,6 +66,7 @@ impl LkError {
             kind: ErrorKind::UnsupportedPlatform,
             message: "Unsupported platform".to_string(),
             parameter: None,
+            request_index: None,
         }
     }
 
@@ -59,6 +76,7 @@ impl LkError {
             kind: ErrorKind::HostKeyNotVerified,
             message: message.to_string(),
             parameter: Some(key_id.to_string()),
+            request_index: None,
         }
     }
 
@@ -72,6 +90,7 @@ impl LkError {
             source_id: String::new(),
             message: format!("{}: {}", message, parameter.to_string()),
             parameter: Some(parameter.to_string()),
+            request_index: None,
         }
     }
 
@@ -79,6 +98,11 @@ impl LkError {
         self.source_id = source.to_string();
         self
     }
+
+    pub fn set_request_index(mut self, request_index: usize) -> LkError {
+        self.request_index = Some(request_index);
+        self
+    }
 }
 
 impl fmt::Display for LkError {
@@ -116,6 +140,8 @@ pub enum ErrorKind {
     HostKeyNotVerified,
     /// Error in configuration files.
     InvalidConfig,
+    /// Sudo requires a password that wasn't configured or was incorrect.
+    SudoPasswordRequired,
     /// Not implemented.
     NotImplemented,
     /// Invalid parameter.
@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
-use std::{env, fs, io, path::Path, path::PathBuf};
+use std::{env, fs, io, path::Path, path::PathBuf, sync::RwLock};
 
 use chrono::{DateTime, Utc};
 use serde_derive::{Deserialize, Serialize};
@@ -16,6 +16,13 @@ const MAX_PATH_COMPONENTS: u8 = 2;
 const APP_DIR_NAME: &str = "lightkeeper";
 const METADATA_SUFFIX: &str = ".metadata.yml";
 
+/// Configured download directory. Set on startup from preferences. If not set, cache directory is used.
+static DOWNLOAD_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+/// Configured data directory. Set on startup from preferences. If not set, XDG data directory is used.
+static DATA_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+/// Configured cache directory. Set on startup from preferences. If not set, XDG cache directory is used.
+static CACHE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
 pub fn get_config_dir() -> io::Result<PathBuf> {
     let mut config_dir = if let Some(path) = env::var_os("XDG_CONFIG_HOME") {
         PathBuf::from(path)
@@ -38,7 +45,12 @@ pub fn get_config_dir() -> io::Result<PathBuf> {
     Ok(config_dir)
 }
 
+/// Directory for mutable data that can be regenerated, e.g. downloaded files.
 pub fn get_cache_dir() -> io::Result<PathBuf> {
+    if let Some(path) = CACHE_DIR.read().unwrap().as_ref() {
+        return Ok(path.clone());
+    }
+
     let mut cache_dir = if let Some(path) = env::var_os("XDG_CACHE_HOME") {
         PathBuf::from(path)
     }
@@ -60,7 +72,12 @@ pub fn get_cache_dir() -> io::Result<PathBuf> {
     Ok(cache_dir)
 }
 
+/// Directory for mutable data that should be kept, e.g. metrics, host logs and SSH known hosts.
 pub fn get_data_dir() -> io::Result<PathBuf> {
+    if let Some(path) = DATA_DIR.read().unwrap().as_ref() {
+        return Ok(path.clone());
+    }
+
     let mut data_dir = if let Some(path) = env::var_os("XDG_DATA_HOME") {
         PathBuf::from(path)
     }
@@ -82,6 +99,74 @@ pub fn get_data_dir() -> io::Result<PathBuf> {
     Ok(data_dir)
 }
 
+/// Sets the data directory. `None` resets it back to the XDG data directory.
+pub fn set_data_dir(path: Option<&str>) {
+    *DATA_DIR.write().unwrap() = path.filter(|path| !path.is_empty()).map(PathBuf::from);
+}
+
+/// Sets the cache directory. `None` resets it back to the XDG cache directory.
+pub fn set_cache_dir(path: Option<&str>) {
+    *CACHE_DIR.write().unwrap() = path.filter(|path| !path.is_empty()).map(PathBuf::from);
+}
+
+/// Sets the directory for downloaded files. `None` resets it back to the cache directory.
+pub fn set_download_dir(path: Option<&str>) {
+    *DOWNLOAD_DIR.write().unwrap() = path.filter(|path| !path.is_empty()).map(PathBuf::from);
+}
+
+/// Resolved directory for downloaded files.
+pub fn get_download_dir() -> io::Result<PathBuf> {
+    match DOWNLOAD_DIR.read().unwrap().as_ref() {
+        Some(path) => Ok(path.clone()),
+        None => get_cache_dir(),
+    }
+}
+
+/// Removes downloaded files (and their metadata) that were downloaded more than `max_age_days` ago.
+/// Returns the number of removed files.
+pub fn remove_stale_files(max_age_days: u32) -> io::Result<usize> {
+    let max_age = chrono::Duration::days(max_age_days as i64);
+    let mut removed_count = 0;
+
+    for metadata_path in list_cached_files(true)? {
+        let content_file_path = metadata_path.strip_suffix(METADATA_SUFFIX).unwrap().to_string();
+        let download_time = match read_file_metadata(&content_file_path) {
+            Ok(metadata) => metadata.download_time,
+            // Fall back to modification time if metadata is unreadable.
+            Err(_) => DateTime::<Utc>::from(fs::metadata(&metadata_path)?.modified()?),
+        };
+
+        if Utc::now() - download_time > max_age {
+            match remove_file(&metadata_path) {
+                Ok(()) => removed_count += 1,
+                Err(error) => log::error!("Failed to remove stale file {}: {}", metadata_path, error),
+            }
+        }
+    }
+
+    Ok(removed_count)
+}
+
+/// Creates an empty local file for streaming downloaded contents into. Local path is based on remote host name and
+/// remote file path. Will overwrite any existing files. Metadata has to be written separately with `write_file_metadata`.
+pub fn create_download_file(host: &Host, remote_file_path: &str) -> io::Result<(String, fs::File)> {
+    let (dir_path, file_path) = convert_to_local_paths(host, remote_file_path);
+
+    fs::create_dir_all(&dir_path)?;
+    let file = fs::OpenOptions::new().write(true).create(true).truncate(true).open(&file_path)?;
+
+    Ok((file_path, file))
+}
+
+/// Reads a local file but fails if it's larger than `max_size` bytes.
+pub fn read_file_with_limit(local_file_path: &str, max_size: u64) -> io::Result<(FileMetadata, Vec<u8>)> {
+    let size = fs::metadata(local_file_path)?.len();
+    if size > max_size {
+        return Err(io::Error::new(io::ErrorKind::Other, format!("File is larger than the maximum size ({} bytes)", max_size)));
+    }
+    read_file(local_file_path)
+}
+
 /// Create a local file. Local path is based on remote host name and remote file path.
 /// Will overwrite any existing files.
 pub fn create_file(host: &Host, remote_file_path: &str, mut metadata: FileMetadata, contents: Vec<u8>) -> io::Result<String> {
@@ -100,11 +185,15 @@ pub fn create_file(host: &Host, remote_file_path: &str, mut metadata: FileMetada
 }
 
 pub fn list_cached_files(only_metadata_files: bool) -> io::Result<Vec<String>> {
-    let cache_dir = file_handler::get_cache_dir()?;
+    let download_dir = file_handler::get_download_dir()?;
     let mut files = Vec::new();
 
+    if !download_dir.is_dir() {
+        return Ok(files);
+    }
+
     // Nice drifting...
-    for subdirectory in fs::read_dir(cache_dir)? {
+    for subdirectory in fs::read_dir(download_dir)? {
         match subdirectory {
             Ok(subdirectory) => {
                 if subdirectory.path().is_dir() && subdirectory.file_name() != "qmlcachedir" {
@@ -143,10 +232,10 @@ pub fn list_cached_files(only_metadata_files: bool) -> io::Result<Vec<String>> {
 
 /// Updates existing local file. File has to exist and have accompanying metadata file.
 pub fn write_file(local_file_path: &String, contents: Vec<u8>) -> io::Result<()> {
-    // Verify, just in case, that path belongs to cache directory.
-    let cache_dir = get_cache_dir().unwrap();
-    if Path::new(local_file_path).ancestors().all(|ancestor| ancestor != cache_dir.as_path()) {
-        Err(io::Error::new(io::ErrorKind::Other, "Path does not belong to cache directory"))
+    // Verify, just in case, that path belongs to download directory.
+    let download_dir = get_download_dir()?;
+    if Path::new(local_file_path).ancestors().all(|ancestor| ancestor != download_dir.as_path()) {
+        Err(io::Error::new(io::ErrorKind::Other, "Path does not belong to download directory"))
     }
     else {
         fs::write(local_file_path, contents)?;
@@ -157,7 +246,7 @@ pub fn write_file(local_file_path: &String, contents: Vec<u8>) -> io::Result<()>
 pub fn write_file_metadata(metadata: FileMetadata) -> io::Result<()> {
     let local_file_path = metadata.local_path.clone().unwrap();
     let metadata_path = get_metadata_path(&local_file_path);
-    let metadata_file = fs::OpenOptions::new().write(true).create(true).open(metadata_path)?;
+    let metadata_file = fs::OpenOptions::new().write(true).create(true).truncate(true).open(metadata_path)?;
     serde_yaml::to_writer(metadata_file, &metadata).map_err(|error| io::Error::new(io::ErrorKind::Other, error.to_string()))?;
 
     Ok(())
@@ -165,10 +254,10 @@ pub fn write_file_metadata(metadata: FileMetadata) -> io::Result<()> {
 
 /// Removes local copy of the (possible) content file and metadata file.
 pub fn remove_file(path: &String) -> io::Result<()> {
-    // Verify, just in case, that path belongs to cache directory.
-    let cache_dir = get_cache_dir().unwrap();
-    if Path::new(path).ancestors().all(|ancestor| ancestor != cache_dir.as_path()) {
-        return Err(io::Error::new(io::ErrorKind::Other, "Path does not belong to cache directory"));
+    // Verify, just in case, that path belongs to download directory.
+    let download_dir = get_download_dir()?;
+    if Path::new(path).ancestors().all(|ancestor| ancestor != download_dir.as_path()) {
+        return Err(io::Error::new(io::ErrorKind::Other, "Path does not belong to download directory"));
     }
 
     if path.ends_with(METADATA_SUFFIX) {
@@ -226,8 +315,8 @@ pub fn get_metadata_path(local_file_path: &str) -> String {
 
 /// Provides the local directory and file paths based on remote host name and remote file path.
 pub fn convert_to_local_paths(host: &Host, remote_file_path: &str) -> (String, String) {
-    let cache_dir = file_handler::get_cache_dir().unwrap();
-    let file_dir = cache_dir.join(host.name.clone());
+    let download_dir = file_handler::get_download_dir().unwrap();
+    let file_dir = download_dir.join(host.name.clone());
 
     // Using only hash as the file name would suffice but providing some parts of
     // the file path and name will help the user to identify the file in e.g. text editor.
@@ -6,7 +6,9 @@
 pub mod frontend;
 pub use frontend::DisplayData;
 pub use frontend::HostDisplayData;
+pub use frontend::HostSummary;
 pub use frontend::UIUpdate;
+pub use frontend::UNGROUPED;
 
 pub mod display_options;
 pub use display_options::DisplayOptions;
@@ -90,7 +90,10 @@ fn convert_single(data_point: &DataPoint, display_options: &DisplayOptions) -> S
             match HostStatus::from_str(&data_point.value).unwrap_or_default() {
                 HostStatus::Pending => "Pending".red().to_string(),
                 HostStatus::Up => "Up".green().to_string(),
+                HostStatus::Warning => "Warning".yellow().to_string(),
                 HostStatus::Down => "Down".red().to_string(),
+                HostStatus::Unreachable => "Unreachable".red().to_string(),
+                HostStatus::Maintenance => "Maintenance".blue().to_string(),
             }
         },
         _ => {
@@ -124,7 +127,10 @@ fn convert_multivalue(data_point: &DataPoint, display_options: &DisplayOptions)
                 match HostStatus::from_str(&data_point.value).unwrap_or_default() {
                     HostStatus::Pending => "Pending".red().to_string(),
                     HostStatus::Up => "Up".to_string(),
+                    HostStatus::Warning => "Warning".to_string(),
                     HostStatus::Down => "Down".to_string(),
+                    HostStatus::Unreachable => "Unreachable".to_string(),
+                    HostStatus::Maintenance => "Maintenance".to_string(),
                 }
             },
             _ => {
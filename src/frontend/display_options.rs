@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use std::collections::HashSet;
+use regex::Regex;
 use serde_derive::{Serialize, Deserialize};
 use strum_macros::Display;
 
@@ -33,19 +35,25 @@ pub struct DisplayOptions {
 
     /// Unit may be displayed in the UI.
     pub unit: String,
-    /// Configures value range used in charts.
+    /// Configures value range used in charts and progress bars.
     pub value_min: f64,
-    /// Configures value range used in charts.
+    /// Configures value range used in charts and progress bars. If 0, 100 is used.
     pub value_max: f64,
 
     /// For monitors that produce a group of values.
     pub use_multivalue: bool,
+    /// Sort multivalue rows (on every level) by label so they don't reorder between refreshes.
+    /// For monitors that don't provide a meaningful order themselves.
+    pub sort_multivalue: bool,
     /// Don't include in the host table status summary.
     pub use_without_summary: bool,
     /// Use DataPoint values in graphical charts.
     pub use_with_charts: bool,
     /// extension modules. If set, will hide defined monitor id from summary.
     pub override_summary_monitor_id: String,
+    /// For monitors that check reachability without connecting to the host (e.g. ping).
+    /// Their results don't tell whether connecting works, but are used to tell apart hosts that are down.
+    pub is_reachability_check: bool,
 
     /// Display confirmation dialog with this text.
     pub confirmation_text: String,
@@ -64,12 +72,47 @@ pub struct DisplayOptions {
     pub depends_on_criticality: Vec<Criticality>,
     /// Show only if related monitor's value is one of these.
     pub depends_on_value: Vec<String>,
-    /// Show only if related monitor's tags contain one of these.
+    /// Show only if related monitor's tags contain all of these.
     pub depends_on_tags: Vec<String>,
+    /// Show only if related monitor's tags contain at least one of these.
+    pub depends_on_any_tags: Vec<String>,
+    /// Show only if related monitor's tags contain none of these.
     pub depends_on_no_tags: Vec<String>,
+    /// Show only if data points of any monitor on the host contain all of these tags (e.g. a tag of another monitor).
+    /// Unlike other tag rules, this is also applied to category-level commands that have no parent monitor.
+    pub depends_on_host_tags: Vec<String>,
 }
 
 impl DisplayOptions {
+    /// Evaluates tag rules against the related monitor's tags.
+    /// All rules have to match (AND): `depends_on_tags` requires all tags, `depends_on_any_tags` at least one (OR)
+    /// and `depends_on_no_tags` none of them. Empty rules always match.
+    pub fn tags_match(&self, tags: &[String]) -> bool {
+        self.depends_on_tags.iter().all(|tag| tags.contains(tag)) &&
+        (self.depends_on_any_tags.is_empty() || self.depends_on_any_tags.iter().any(|tag| tags.contains(tag))) &&
+        self.depends_on_no_tags.iter().all(|tag| !tags.contains(tag))
+    }
+
+    /// Evaluates `depends_on_host_tags` against the tags collected from all monitors on the host.
+    pub fn host_tags_match(&self, host_tags: &HashSet<String>) -> bool {
+        self.depends_on_host_tags.iter().all(|tag| host_tags.contains(tag))
+    }
+
+    /// Validates values given for `user_parameters` before a command is executed.
+    /// User input is appended to the command parameters, so the values are the last parameters.
+    pub fn validate_user_input(&self, parameters: &[String]) -> Result<(), String> {
+        if parameters.len() < self.user_parameters.len() {
+            return Err(format!("Expected {} user-provided parameters", self.user_parameters.len()));
+        }
+
+        let user_input = &parameters[parameters.len() - self.user_parameters.len()..];
+        for (field, value) in self.user_parameters.iter().zip(user_input) {
+            field.validate(value).map_err(|error| format!("{}: {}", field.label, error))?;
+        }
+
+        Ok(())
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         if self.display_style == DisplayStyle::Icon && self.display_icon.is_empty() {
             return Err(String::from("Icon display style requires display_icon to be set."));
@@ -80,6 +123,10 @@ impl DisplayOptions {
             return Err(String::from("display_icon must only contain alphanumeric characters and dashes."));
         }
 
+        if self.value_max != 0.0 && self.value_max <= self.value_min {
+            return Err(String::from("value_max must be greater than value_min."));
+        }
+
         if self.display_text.is_empty() {
             return Err(String::from("display_text must be set."));
         }
@@ -105,6 +152,8 @@ pub enum DisplayStyle {
     CriticalityLevel,
     Icon,
     ProgressBar,
+    /// Small trend graph drawn from the buffered values (DataPoint.value_float) of the monitor.
+    Sparkline,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, Display, PartialEq)]
@@ -130,6 +179,33 @@ pub struct UserInputField {
 }
 
 impl UserInputField {
+    /// Checks the value against the validators and allowed options. Same rules are applied in the frontend form.
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        if self.field_type == UserInputFieldType::Option && !self.options.is_empty() && !self.options.iter().any(|option| option == value) {
+            return Err(format!("\"{}\" is not one of the allowed values", value));
+        }
+
+        for validator in [&self.validator_regexp, &self.additional_validator_regexp] {
+            if validator.is_empty() {
+                continue;
+            }
+
+            match Regex::new(validator) {
+                Ok(regex) => {
+                    if !regex.is_match(value) {
+                        return Err(format!("Invalid value \"{}\"", value));
+                    }
+                },
+                Err(error) => {
+                    log::error!("Invalid validator regexp \"{}\": {}", validator, error);
+                    return Err(String::from("Value can't be validated"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn number(label: &str, default_value: &str) -> Self {
         UserInputField {
             field_type: UserInputFieldType::Integer,
@@ -3,10 +3,11 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 use serde_derive::{ Serialize, Deserialize };
 
-use crate::enums::HostStatus;
+use crate::enums::{Criticality, HostStatus};
 use crate::host::Host;
 use crate::host_manager::HostState;
 use crate::metrics::lmserver;
@@ -19,6 +20,8 @@ use crate::utils::ErrorMessage;
 pub enum UIUpdate {
     Host(HostDisplayData),
     Chart(lmserver::LMSResponse),
+    /// Monitors that are due for automatic refresh. Host name is the key.
+    ScheduledRefresh(HashMap<String, Vec<String>>),
     Stop(),
 }
 
@@ -28,10 +31,15 @@ impl Default for UIUpdate {
     }
 }
 
+/// Group name used for hosts that don't belong to any group.
+pub const UNGROUPED: &str = "ungrouped";
+
 #[derive(Default, Clone)]
 pub struct DisplayData {
     // Key is host name.
     pub hosts: HashMap<String, HostDisplayData>,
+    /// Group name as key and sorted host names as value. Hosts can belong to multiple groups.
+    pub host_groups: BTreeMap<String, Vec<String>>,
     pub all_monitor_names: Vec<String>,
     pub table_headers: Vec<String>,
 }
@@ -40,19 +48,47 @@ impl DisplayData {
     pub fn new() -> Self {
         DisplayData {
             hosts: HashMap::new(),
+            host_groups: BTreeMap::new(),
             // To help creating tables.
             all_monitor_names: Vec::new(),
             table_headers: Vec::new(),
         }
     }
+
+    /// Rebuilds `host_groups` from the group memberships of current hosts.
+    pub fn update_host_groups(&mut self) {
+        self.host_groups.clear();
+
+        for (host_name, host_data) in self.hosts.iter() {
+            if host_name == crate::monitor_manager::CERT_MONITOR_HOST_ID {
+                continue;
+            }
+
+            let groups = &host_data.host_state.host.groups;
+            if groups.is_empty() {
+                self.host_groups.entry(String::from(UNGROUPED)).or_default().push(host_name.clone());
+            }
+            for group in groups.iter() {
+                self.host_groups.entry(group.clone()).or_default().push(host_name.clone());
+            }
+        }
+
+        for host_names in self.host_groups.values_mut() {
+            host_names.sort();
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct HostDisplayData {
-    pub host_state: HostState,
-    pub new_monitoring_data: Option<(u64, MonitoringData)>,
-    pub new_command_result: Option<(u64, CommandResult)>,
+    /// Shared snapshot of the host state, so that the state isn't copied for every observer and every stored display data.
+    pub host_state: Arc<HostState>,
+    /// New data points and command results since the last update, in order of arrival. Invocation ID is the first value.
+    pub new_monitoring_data: Vec<(u64, MonitoringData)>,
+    pub new_command_results: Vec<(u64, CommandResult)>,
     pub new_errors: Vec<ErrorMessage>,
+    /// Rollup of current monitor criticalities.
+    pub summary: HostSummary,
     /// Verification requests from connectors. Usually for key verification.
     /// Commands can already request (more diverse) user input so they don't use this.
     pub verification_requests: Vec<VerificationRequest>,
@@ -61,24 +97,56 @@ pub struct HostDisplayData {
 impl Default for HostDisplayData {
     fn default() -> Self {
         HostDisplayData {
-            host_state: HostState {
+            host_state: Arc::new(HostState {
                 host: Host::default(),
                 status: HostStatus::default(),
                 just_initialized: false,
                 is_initialized: false,
+                is_unreachable: false,
+                responds_to_ping: None,
                 monitor_data: HashMap::new(),
                 command_results: HashMap::new(),
+                command_history: HashMap::new(),
                 monitor_invocations: HashMap::new(),
                 command_invocations: HashMap::new(),
-            },
-            new_monitoring_data: None,
-            new_command_result: None,
+                maintenance_until: None,
+                critical_monitors: HashSet::new(),
+                recent_errors: VecDeque::new(),
+            }),
+            new_monitoring_data: Vec::new(),
+            new_command_results: Vec::new(),
             new_errors: Vec::new(),
+            summary: HostSummary::default(),
             verification_requests: Vec::new(),
         }
     }
 }
 
+/// Counts of monitors by their latest criticality. Monitors with `use_without_summary` are not included.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HostSummary {
+    pub normal_count: u32,
+    pub warning_count: u32,
+    pub error_count: u32,
+    pub critical_count: u32,
+    pub no_data_count: u32,
+    /// Worst criticality of the counted monitors. NotAvailable and Ignore don't affect this.
+    pub worst_criticality: Criticality,
+}
+
+impl Default for HostSummary {
+    fn default() -> Self {
+        HostSummary {
+            normal_count: 0,
+            warning_count: 0,
+            error_count: 0,
+            critical_count: 0,
+            no_data_count: 0,
+            worst_criticality: Criticality::Normal,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct VerificationRequest {
     pub source_id: String,
@@ -52,11 +52,12 @@ pub struct ThemeModel {
     categoryColor: qt_method!(fn(&self, category: QString) -> QString),
     criticalityColor: qt_method!(fn(&self, criticality: QString) -> QString),
     categoryIcon: qt_method!(fn(&self, category: QString) -> QString),
+    tagColor: qt_method!(fn(&self, category: QString, tag: QString) -> QString),
+    tagIcon: qt_method!(fn(&self, category: QString, tag: QString) -> QString),
     colorForCriticality: qt_method!(fn(&self, criticality: QString) -> QString),
     iconForCriticality: qt_method!(fn(&self, alert_level: QString) -> QString),
     opacity: qt_method!(fn(&self, is_enabled: bool) -> QString),
     getDisplayOptions: qt_method!(fn(&self) -> QVariant),
-    allowCollapsingCommand: qt_method!(fn(&self, command_id: QString) -> QString),
 
 
     i_display_options: configuration::DisplayOptions,
@@ -132,6 +133,25 @@ impl ThemeModel {
         }
     }
 
+    fn tagColor(&self, category: QString, tag: QString) -> QString {
+        let color = self.get_tag_style(&category.to_string(), &tag.to_string()).and_then(|style| style.color);
+        // Same as the default info color.
+        QString::from(color.unwrap_or_else(|| String::from("#60ffffff")))
+    }
+
+    fn tagIcon(&self, category: QString, tag: QString) -> QString {
+        let icon = self.get_tag_style(&category.to_string(), &tag.to_string()).and_then(|style| style.icon);
+        QString::from(icon.unwrap_or_default())
+    }
+
+    /// Category-specific style takes precedence over the global one.
+    fn get_tag_style(&self, category: &String, tag: &String) -> Option<configuration::TagStyle> {
+        self.i_display_options.categories.get(category)
+            .and_then(|category| category.tag_styles.get(tag))
+            .or_else(|| self.i_display_options.tag_styles.get(tag))
+            .cloned()
+    }
+
     fn opacity(&self, is_enabled: bool) -> QString {
         if is_enabled {
             return QString::from("1.0");
@@ -139,24 +159,6 @@ impl ThemeModel {
         QString::from("0.3")
     }
 
-    // TODO: better place.
-    fn allowCollapsingCommand(&self, command_id: QString) -> QString {
-        // TODO: take category into consideration instead of accepting matching id from any of them.
-        let allows_collapsing = self.i_display_options.categories.values().any(|category| {
-            match &category.collapsible_commands {
-                Some(collapsible_commands) => collapsible_commands.contains(&command_id.to_string()),
-                None => false,
-            }
-        });
-
-        if allows_collapsing {
-            QString::from("1")
-        }
-        else {
-            QString::from("0")
-        }
-    }
-
     fn colorForCriticality(&self, criticality: QString) -> QString {
         let criticality = criticality.to_string();
 
@@ -11,7 +11,9 @@ use qmetaobject::*;
 
 use crate::command_handler::{CommandHandler, CommandButtonData};
 use crate::configuration;
+use crate::file_handler;
 use crate::connection_manager::ConnectorRequest;
+use crate::frontend::UIUpdate;
 use crate::host_manager::StateUpdateMessage;
 use crate::module::command::UIAction;
 use crate::monitor_manager::MonitorManager;
@@ -30,13 +32,20 @@ pub struct CommandHandlerModel {
     getCategoryCommands: qt_method!(fn(&self, host_id: QString, category: QString) -> QVariantList),
     getCustomCommands: qt_method!(fn(&self, host_id: QString) -> QStringList),
     getCommandsOnLevel: qt_method!(fn(&self, host_id: QString, category: QString, parent_id: QString, multivalue_level: QString) -> QVariantList),
+    getDisplayOptions: qt_method!(fn(&self, host_id: QString) -> QVariant),
+    allowCollapsingCommand: qt_method!(fn(&self, host_id: QString, command_id: QString) -> QString),
     execute: qt_method!(fn(&self, button_id: QString, host_id: QString, command_id: QString, parameters: QStringList)),
     executeConfirmed: qt_method!(fn(&self, button_id: QString, host_id: QString, command_id: QString, parameters: QStringList)),
     executePlain: qt_method!(fn(&self, host_id: QString, command_id: QString, parameters: QStringList) -> u64),
+    executeDryRun: qt_method!(fn(&self, host_id: QString, command_id: QString, parameters: QStringList) -> u64),
+    supportsDryRun: qt_method!(fn(&self, host_id: QString, command_id: QString, parameters: QStringList) -> bool),
     saveAndUploadFile: qt_method!(fn(&self, host_id: QString, command_id: QString, local_file_path: QString, contents: QString) -> u64),
     removeFile: qt_method!(fn(&self, local_file_path: QString)),
+    getDownloadDir: qt_method!(fn(&self) -> QString),
     hasFileChanged: qt_method!(fn(&self, local_file_path: QString, contents: QString) -> bool),
     verifyHostKey: qt_method!(fn(&self, host_id: QString, connector_id: QString, key_id: QString)),
+    /// Returns invocation ID.
+    testConnection: qt_method!(fn(&self, host_id: QString) -> u64),
 
     // Host initialization methods.
     initializeHost: qt_method!(fn(&self, host_id: QString)),
@@ -47,6 +56,11 @@ pub struct CommandHandlerModel {
     refreshMonitorsOfCommand: qt_method!(fn(&self, host_id: QString, command_id: QString) -> QVariantList),
     refreshMonitorsOfCategory: qt_method!(fn(&self, host_id: QString, category: QString) -> QVariantList),
     refreshCertificateMonitors: qt_method!(fn(&self) -> QVariantList),
+    refreshAllMonitors: qt_method!(fn(&self, category: QString) -> QVariantList),
+    refreshMatchingHosts: qt_method!(fn(&self, host_selector: QString, category: QString) -> QVariantList),
+    enableMonitor: qt_method!(fn(&self, host_id: QString, monitor_id: QString)),
+    disableMonitor: qt_method!(fn(&self, host_id: QString, monitor_id: QString)),
+    setMonitorCritical: qt_method!(fn(&self, host_id: QString, monitor_id: QString, is_critical: bool)),
 
     //
     // Signals
@@ -92,7 +106,7 @@ impl CommandHandlerModel {
         update_sender: mpsc::Sender<StateUpdateMessage>
     ) {
         self.configuration = main_config.clone();
-        self.monitor_manager.configure(&hosts_config, request_sender.clone(), update_sender.clone());
+        self.monitor_manager.configure(&hosts_config, &main_config.preferences, request_sender.clone(), update_sender.clone());
         self.command_handler.configure(&hosts_config, &main_config.preferences, request_sender, update_sender);
     }
 
@@ -101,6 +115,10 @@ impl CommandHandlerModel {
         self.command_handler.start_processing_responses();
     }
 
+    pub fn start_refresh_scheduler(&mut self, update_sender: mpsc::Sender<UIUpdate>) {
+        self.monitor_manager.start_refresh_scheduler(update_sender);
+    }
+
     pub fn stop(&mut self) {
         self.command_handler.stop();
         self.monitor_manager.stop();
@@ -112,6 +130,46 @@ impl CommandHandlerModel {
         }
     }
 
+    /// Hosts without platform info are initialized first. Monitors get refreshed after initialization.
+    pub fn refresh_scheduled(&mut self, due_refreshes: HashMap<String, Vec<String>>) {
+        for host_id in self.monitor_manager.refresh_scheduled(due_refreshes) {
+            self.hostInitializing(QString::from(host_id));
+        }
+    }
+
+    /// Display options with host-specific category settings applied.
+    fn getDisplayOptions(&self, host_id: QString) -> QVariant {
+        let mut display_options = self.configuration.display_options.clone();
+        display_options.categories = self.get_categories(&host_id.to_string());
+        display_options.to_qvariant()
+    }
+
+    /// Whether the command can be hidden behind a menu in collapsible command rows. Uses host-specific category settings.
+    fn allowCollapsingCommand(&self, host_id: QString, command_id: QString) -> QString {
+        let command_id = command_id.to_string();
+        let command_category = self.command_handler.get_commands_for_host(host_id.to_string())
+                                                   .get(&command_id)
+                                                   .map(|command_data| command_data.display_options.category.clone())
+                                                   .unwrap_or_default();
+
+        let allows_collapsing = match self.get_categories(&host_id.to_string()).get(&command_category) {
+            Some(category) => category.collapsible_commands.as_ref().map(|ids| ids.contains(&command_id)).unwrap_or(false),
+            None => false,
+        };
+
+        if allows_collapsing {
+            QString::from("1")
+        }
+        else {
+            QString::from("0")
+        }
+    }
+
+    fn get_categories(&self, host_id: &String) -> HashMap<String, configuration::Category> {
+        let effective_config = self.command_handler.get_effective_host_config(host_id);
+        configuration::Configuration::get_effective_categories(&self.configuration.display_options, &effective_config)
+    }
+
     // Return CommandDatas relevant to category as QVariants.
     fn getCategoryCommands(&self, host_id: QString, category: QString) -> QVariantList {
         let category_string = category.to_string();
@@ -120,7 +178,7 @@ impl CommandHandlerModel {
                                                         .into_values().filter(|data| data.display_options.category == category_string)
                                                         .collect::<Vec<CommandButtonData>>();
 
-        let command_order = match self.configuration.display_options.categories.get(&category_string) {
+        let command_order = match self.get_categories(&host_id.to_string()).get(&category_string) {
             Some(category_data) => category_data.command_order.clone().unwrap_or_default(),
             None => Vec::new(),
         };
@@ -169,7 +227,7 @@ impl CommandHandlerModel {
 
         let mut valid_commands_sorted = Vec::<CommandButtonData>::new();
 
-        let command_order = match self.configuration.display_options.categories.get(&category_string) {
+        let command_order = match self.get_categories(&host_id.to_string()).get(&category_string) {
             Some(category_data) => category_data.command_order.clone().unwrap_or_default(),
             None => Vec::new(),
         };
@@ -219,14 +277,14 @@ impl CommandHandlerModel {
 
         match display_options.action {
             UIAction::None => {
-                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters);
+                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters, true, false);
 
                 if invocation_id > 0 {
                     self.commandExecuted(invocation_id, host_id.into(), command_id.into(), display_options.category.into(), button_id.into());
                 }
             },
             UIAction::FollowOutput => {
-                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters);
+                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters, true, false);
                 if invocation_id > 0 {
                     let title = match display_options.tab_title.is_empty() {
                         true => QString::from(format!("{}: {}", command_id, parameters.first().unwrap_or(&String::new()))),
@@ -238,26 +296,26 @@ impl CommandHandlerModel {
             },
             UIAction::TextView => {
                 let target_id = parameters.first().unwrap().clone();
-                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters);
+                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters, true, false);
                 if invocation_id > 0 {
                     self.textViewOpened(QString::from(format!("{}: {}", command_id, target_id)), invocation_id)
                 }
             },
             UIAction::TextDialog => {
-                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters);
+                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters, true, false);
                 if invocation_id > 0 {
                     self.textDialogOpened(invocation_id)
                 }
             },
             UIAction::LogView => {
-                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters);
+                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters, true, false);
                 if invocation_id > 0 {
                     let parameters_qs = parameters.into_iter().map(QString::from).collect::<QStringList>();
                     self.logsViewOpened(false, QString::from(display_options.tab_title), QString::from(command_id), parameters_qs, invocation_id);
                 }
             },
             UIAction::LogViewWithTimeControls => {
-                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters);
+                let invocation_id = self.command_handler.execute(&host_id, &command_id, &parameters, true, false);
                 if invocation_id > 0 {
                     let parameters_qs = parameters.into_iter().map(QString::from).collect::<QStringList>();
                     self.logsViewOpened(true, QString::from(display_options.tab_title), QString::from(command_id), parameters_qs, invocation_id);
@@ -265,9 +323,11 @@ impl CommandHandlerModel {
             },
             UIAction::Terminal => {
                 if self.configuration.preferences.terminal == configuration::INTERNAL {
-                    let command = self.command_handler.open_remote_terminal_command(&host_id, &command_id, &parameters);
-                    let command_qsl = command.to_vec().into_iter().map(QString::from).collect::<QStringList>();
-                    self.terminalViewOpened(QString::from(display_options.tab_title), command_qsl)
+                    // Refusals are reported by the command handler.
+                    if let Ok(command) = self.command_handler.open_remote_terminal_command(&host_id, &command_id, &parameters) {
+                        let command_qsl = command.to_vec().into_iter().map(QString::from).collect::<QStringList>();
+                        self.terminalViewOpened(QString::from(display_options.tab_title), command_qsl)
+                    }
                 }
                 else {
                     self.command_handler.open_external_terminal(&host_id, &command_id, parameters);
@@ -277,9 +337,10 @@ impl CommandHandlerModel {
                 let remote_file_path = parameters.first().unwrap().clone();
                 if self.configuration.preferences.use_remote_editor {
                     if self.configuration.preferences.terminal == configuration::INTERNAL {
-                        let command = self.command_handler.open_remote_text_editor(&host_id, &remote_file_path);
-                        let command_qsl = command.to_vec().into_iter().map(QString::from).collect::<QStringList>();
-                        self.terminalViewOpened(QString::from(display_options.tab_title), command_qsl);
+                        if let Ok(command) = self.command_handler.open_remote_text_editor(&host_id, &command_id, &remote_file_path) {
+                            let command_qsl = command.to_vec().into_iter().map(QString::from).collect::<QStringList>();
+                            self.terminalViewOpened(QString::from(display_options.tab_title), command_qsl);
+                        }
                     }
                     else {
                         self.command_handler.open_external_terminal(&host_id, &command_id, parameters);
@@ -287,12 +348,14 @@ impl CommandHandlerModel {
                 }
                 else {
                     if self.configuration.preferences.text_editor == configuration::INTERNAL {
-                        let (invocation_id, file_contents) = self.command_handler.download_editable_file(&host_id, &command_id, &remote_file_path); 
-                        self.textEditorViewOpened(QString::from(command_id), invocation_id, QString::from(file_contents));
+                        if let Ok((invocation_id, file_contents)) = self.command_handler.download_editable_file(&host_id, &command_id, &remote_file_path) {
+                            self.textEditorViewOpened(QString::from(command_id), invocation_id, QString::from(file_contents));
+                        }
                     }
                     else {
-                        let local_file_path = self.command_handler.open_external_text_editor(&host_id, &command_id, &remote_file_path);
-                        let _invocation_id = self.command_handler.upload_file(&host_id, &command_id, &local_file_path);
+                        if let Ok(local_file_path) = self.command_handler.open_external_text_editor(&host_id, &command_id, &remote_file_path) {
+                            let _invocation_id = self.command_handler.upload_file(&host_id, &command_id, &local_file_path);
+                        }
                     }
                 }
             },
@@ -303,7 +366,22 @@ impl CommandHandlerModel {
         let host_id = host_id.to_string();
         let command_id = command_id.to_string();
         let parameters: Vec<String> = parameters.into_iter().map(|qvar| qvar.to_string()).collect();
-        self.command_handler.execute(&host_id, &command_id, &parameters)
+        self.command_handler.execute(&host_id, &command_id, &parameters, false, false)
+    }
+
+    /// Runs only a preview of the command. The result is shown as a notification.
+    fn executeDryRun(&mut self, host_id: QString, command_id: QString, parameters: QStringList) -> u64 {
+        let host_id = host_id.to_string();
+        let command_id = command_id.to_string();
+        let parameters: Vec<String> = parameters.into_iter().map(|qvar| qvar.to_string()).collect();
+        self.command_handler.execute(&host_id, &command_id, &parameters, false, true)
+    }
+
+    fn supportsDryRun(&self, host_id: QString, command_id: QString, parameters: QStringList) -> bool {
+        let host_id = host_id.to_string();
+        let command_id = command_id.to_string();
+        let parameters: Vec<String> = parameters.into_iter().map(|qvar| qvar.to_string()).collect();
+        self.command_handler.supports_dry_run(&host_id, &command_id, &parameters)
     }
 
     fn saveAndUploadFile(&mut self, host_id: QString, command_id: QString, local_file_path: QString, contents: QString) -> u64 {
@@ -322,6 +400,16 @@ impl CommandHandlerModel {
         self.command_handler.remove_file(&local_file_path);
     }
 
+    fn getDownloadDir(&self) -> QString {
+        match file_handler::get_download_dir() {
+            Ok(path) => QString::from(path.to_string_lossy().to_string()),
+            Err(error) => {
+                log::error!("Failed to resolve download directory: {}", error);
+                QString::default()
+            }
+        }
+    }
+
     fn hasFileChanged(&self, local_file_path: QString, contents: QString) -> bool {
         let local_file_path = local_file_path.to_string();
         let contents = contents.to_string().into_bytes();
@@ -335,6 +423,10 @@ impl CommandHandlerModel {
         self.command_handler.verify_host_key(&host_id, &connector_id, &key_id);
     }
 
+    fn testConnection(&self, host_id: QString) -> u64 {
+        self.command_handler.test_connection(&host_id.to_string())
+    }
+
     fn initializeHost(&mut self, host_id: QString) {
         self.monitor_manager.refresh_platform_info(&host_id.to_string());
         self.hostInitializing(host_id);
@@ -345,11 +437,16 @@ impl CommandHandlerModel {
         self.hostInitializing(host_id);
     }
 
+    /// Hosts that already have platform info are not probed again. Only their monitors are refreshed.
     fn forceInitializeHosts(&mut self) {
-        let host_ids = self.monitor_manager.refresh_platform_info_all();
-        for host_id in host_ids {
+        let (probed_host_ids, initialized_host_ids) = self.monitor_manager.refresh_missing_platform_info();
+        for host_id in probed_host_ids {
             self.hostInitializing(QString::from(host_id));
         }
+
+        for host_id in initialized_host_ids {
+            self.refresh_host_monitors(host_id);
+        }
     }
 
     // Finds related monitors for a command and refresh them.
@@ -379,11 +476,47 @@ impl CommandHandlerModel {
         QVariantList::from_iter(invocation_ids)
     }
 
+    /// Refreshes monitors on all hosts. Empty category means all categories.
+    fn refreshAllMonitors(&mut self, category: QString) -> QVariantList {
+        let category = category.to_string();
+        let category = if category.is_empty() { None } else { Some(&category) };
+
+        let progress = self.monitor_manager.refresh_all(category);
+        QVariantList::from_iter(progress.invocation_ids.into_values().flatten())
+    }
+
+    /// Refreshes monitors on hosts matching the selector (host name, glob pattern or @group).
+    fn refreshMatchingHosts(&mut self, host_selector: QString, category: QString) -> QVariantList {
+        let category = category.to_string();
+        let category = if category.is_empty() { None } else { Some(&category) };
+
+        let progress = self.monitor_manager.refresh_matching(&host_selector.to_string(), category);
+        QVariantList::from_iter(progress.invocation_ids.into_values().flatten())
+    }
+
     fn refreshCertificateMonitors(&mut self) -> QVariantList {
         let invocation_ids = self.monitor_manager.refresh_certificate_monitors();
         QVariantList::from_iter(invocation_ids)
     }
 
+    fn enableMonitor(&mut self, host_id: QString, monitor_id: QString) {
+        if let Err(error) = self.monitor_manager.enable_monitor(&host_id.to_string(), &monitor_id.to_string()) {
+            ::log::error!("Failed to enable monitor: {}", error);
+        }
+    }
+
+    fn disableMonitor(&mut self, host_id: QString, monitor_id: QString) {
+        if let Err(error) = self.monitor_manager.disable_monitor(&host_id.to_string(), &monitor_id.to_string()) {
+            ::log::error!("Failed to disable monitor: {}", error);
+        }
+    }
+
+    fn setMonitorCritical(&mut self, host_id: QString, monitor_id: QString, is_critical: bool) {
+        if let Err(error) = self.monitor_manager.set_critical(&host_id.to_string(), &monitor_id.to_string(), is_critical) {
+            ::log::error!("Failed to change monitor criticality: {}", error);
+        }
+    }
+
     fn getAllHostCategories(&self, host_id: QString) -> QVariantList {
         if host_id.is_empty() {
             return QVariantList::default()
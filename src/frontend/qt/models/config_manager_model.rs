@@ -33,6 +33,7 @@ pub struct ConfigManagerModel {
     showCharts: qt_property!(bool; READ showCharts WRITE setShowCharts),
     showInfoNotifications: qt_property!(bool; READ showInfoNotifications),
     isSandboxed: qt_method!(fn(&self) -> bool),
+    isConfigReadOnly: qt_method!(fn(&self) -> bool),
     isDevBuild: qt_method!(fn(&self) -> bool),
     getCurrentWorkDir: qt_method!(fn(&self) -> QString),
 
@@ -138,12 +139,17 @@ impl ConfigManagerModel {
         if Configuration::is_schema_outdated(main_config.schema_version) {
             Configuration::upgrade_schema(&mut main_config, &mut groups_config);
 
-            // If writes fail, log errors but continue nevertheless.
-            if let Err(error) = Configuration::write_main_config(&config_dir, &main_config) {
-                ::log::error!("Failed to write main configuration: {}", error);
+            if Configuration::is_read_only() {
+                ::log::warn!("Configuration schema is outdated but can't be upgraded on disk since the configuration directory is read-only");
             }
-            if let Err(error) = Configuration::write_groups_config(&config_dir, &groups_config) {
-                ::log::error!("Failed to write groups configuration: {}", error);
+            else {
+                // If writes fail, log errors but continue nevertheless.
+                if let Err(error) = Configuration::write_main_config(&config_dir, &main_config) {
+                    ::log::error!("Failed to write main configuration: {}", error);
+                }
+                if let Err(error) = Configuration::write_groups_config(&config_dir, &groups_config) {
+                    ::log::error!("Failed to write groups configuration: {}", error);
+                }
             }
         }
 
@@ -279,6 +285,10 @@ impl ConfigManagerModel {
         self.main_config.preferences.use_sandbox_mode
     }
 
+    fn isConfigReadOnly(&self) -> bool {
+        Configuration::is_read_only()
+    }
+
     fn isDevBuild(&self) -> bool {
         cfg!(debug_assertions)
     }
@@ -355,6 +365,7 @@ impl ConfigManagerModel {
         let host_config = self.hosts_config.hosts.get_mut(&new_host_name).unwrap();
         host_config.address = new_settings.address;
         host_config.fqdn = new_settings.fqdn;
+        host_config.description = new_settings.description;
         host_config.overrides = new_settings.overrides;
     }
 
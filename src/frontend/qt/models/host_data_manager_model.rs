@@ -5,9 +5,10 @@
 
 extern crate qmetaobject;
 use qmetaobject::*;
+use std::collections::HashMap;
 
 use crate::configuration;
-use crate::enums::Criticality;
+use crate::enums::{Criticality, HostStatus};
 use crate::frontend;
 use crate::module::monitoring::MonitoringData;
 
@@ -59,6 +60,12 @@ pub struct HostDataManagerModel {
     // JSON is required since there doesn't seem to be a way to return a self-defined QObject.
     getSummaryMonitorData: qt_method!(fn(&self, host_id: QString) -> QStringList),
     getHostDataJson: qt_method!(fn(&self, host_id: QString) -> QString),
+    getHostSummaryJson: qt_method!(fn(&self, host_id: QString) -> QString),
+    getRecentErrorsJson: qt_method!(fn(&self, host_id: QString) -> QString),
+    /// Previous results of a command, oldest first.
+    getCommandHistoryJson: qt_method!(fn(&self, host_id: QString, command_id: QString) -> QString),
+    /// Group name as key and list of host names as value.
+    getHostGroupsJson: qt_method!(fn(&mut self) -> QString),
 
     //
     // Private properties
@@ -68,63 +75,81 @@ pub struct HostDataManagerModel {
     // Retains data between reloads.
     display_data: frontend::DisplayData,
     display_options_category_order: Vec<String>,
+    /// Host-specific category order if the host's groups override category priorities.
+    host_category_orders: HashMap<String, Vec<String>>,
     configuration_preferences: configuration::Preferences,
 }
 
 #[allow(non_snake_case)]
 impl HostDataManagerModel {
-    pub fn new(display_data: frontend::DisplayData, config: configuration::Configuration) -> Self {
-        let mut priorities = config.display_options.categories.iter()
-                                                              .map(|(category, options)| (category.clone(), options.priority))
-                                                              .collect::<Vec<_>>();
-
-        priorities.sort_by(|left, right| left.1.cmp(&right.1));
-
+    pub fn new(display_data: frontend::DisplayData, config: configuration::Configuration, hosts_config: &configuration::Hosts) -> Self {
         let mut result = HostDataManagerModel {
             display_data: display_data,
-            // display_options: display_options,
-            display_options_category_order: priorities.into_iter().map(|(category, _)| category).collect(),
-            configuration_preferences: config.preferences,
             ..Default::default()
         };
 
+        result.configure(&config, hosts_config);
         result.update_criticality_counts();
         result
     }
 
+    pub fn configure(&mut self, config: &configuration::Configuration, hosts_config: &configuration::Hosts) {
+        self.display_options_category_order = configuration::Configuration::get_category_order(&config.display_options.categories);
+        self.configuration_preferences = config.preferences.clone();
+
+        self.host_category_orders = hosts_config.hosts.iter()
+            .filter(|(_, host_config)| host_config.effective.categories.values().any(|category| category.priority.is_some()))
+            .map(|(host_id, host_config)| {
+                let categories = configuration::Configuration::get_effective_categories(&config.display_options, &host_config.effective);
+                (host_id.clone(), configuration::Configuration::get_category_order(&categories))
+            })
+            .collect();
+    }
+
+    fn get_category_order(&self, host_id: &String) -> &Vec<String> {
+        self.host_category_orders.get(host_id).unwrap_or(&self.display_options_category_order)
+    }
+
 
     pub fn process_update(&mut self, new_display_data: frontend::HostDisplayData) {
         // HostDataModel cannot be passed between threads so parsing happens here.
         let host_state = &new_display_data.host_state;
         let maybe_old_data = self.display_data.hosts.insert(host_state.host.name.clone(), new_display_data.clone());
 
-        if let Some((invocation_id, command_result)) = new_display_data.new_command_result {
+        for (invocation_id, command_result) in new_display_data.new_command_results {
             let json = QString::from(serde_json::to_string(&command_result).unwrap());
             self.commandResultReceived(json, invocation_id);
         }
 
-        if let Some((invocation_id, new_monitor_data)) = new_display_data.new_monitoring_data {
+        // Update can contain multiple data points for the same monitor, so the latest criticality is tracked here.
+        let mut old_criticalities = HashMap::<String, Criticality>::new();
+        if let Some(old_data) = maybe_old_data {
+            for (monitor_id, old_monitor_data) in old_data.host_state.monitor_data.iter() {
+                old_criticalities.insert(monitor_id.clone(), old_monitor_data.values.back().unwrap().criticality);
+            }
+        }
+
+        for (invocation_id, new_monitor_data) in new_display_data.new_monitoring_data {
             self.monitoringDataReceived(QString::from(host_state.host.name.clone()),
                                         QString::from(new_monitor_data.display_options.category.clone()),
                                         new_monitor_data.to_qvariant(),
                                         invocation_id);
 
             // Find out any monitor state changes and signal accordingly.
-            if let Some(old_data) = maybe_old_data {
-                let new_criticality = new_monitor_data.values.back().unwrap().criticality;
-
-                if let Some(old_monitor_data) = old_data.host_state.monitor_data.get(&new_monitor_data.monitor_id) {
-                    let old_criticality = old_monitor_data.values.back().unwrap().criticality;
+            let new_criticality = new_monitor_data.values.back().unwrap().criticality;
 
-                    if new_criticality != old_criticality {
+            if let Some(old_criticality) = old_criticalities.insert(new_monitor_data.monitor_id.clone(), new_criticality) {
+                if new_criticality != old_criticality {
+                    // Alerts are suppressed during maintenance.
+                    if host_state.status != HostStatus::Maintenance {
                         self.monitorStateChanged(
                             QString::from(host_state.host.name.clone()),
                             QString::from(new_monitor_data.monitor_id.clone()),
                             QString::from(new_criticality.to_string())
                         );
-
-                        self.update_criticality_counts();
                     }
+
+                    self.update_criticality_counts();
                 }
             }
         }
@@ -284,8 +309,10 @@ impl HostDataManagerModel {
 
         let mut result = QStringList::default();
 
+        let category_order = self.get_category_order(&host_id);
+
         // First add categories in the order they are defined in the config.
-        for prioritized_category in self.display_options_category_order.iter() {
+        for prioritized_category in category_order.iter() {
             if categories.contains(prioritized_category) &&
                !ignore_empty ||
                !self.is_empty_category(&host_id, &prioritized_category) {
@@ -295,7 +322,7 @@ impl HostDataManagerModel {
         }
 
         for remaining_category in categories.iter() {
-            if !self.display_options_category_order.contains(remaining_category) {
+            if !category_order.contains(remaining_category) {
                 if !ignore_empty || !self.is_empty_category(&host_id, &remaining_category) {
                     result.push(QString::from(remaining_category.clone()));
                 }
@@ -323,7 +350,7 @@ impl HostDataManagerModel {
                 .filter(|data| !data.display_options.use_without_summary && !overridden_monitors.contains(&&data.monitor_id))
                 .collect();
 
-            let sorted_keys = self.get_monitor_data_keys_sorted(summary_compatible);
+            let sorted_keys = self.get_monitor_data_keys_sorted(&host_id, summary_compatible);
 
             for key in sorted_keys {
                 let monitoring_data = display_data.host_state.monitor_data.get(&key).unwrap();
@@ -339,9 +366,10 @@ impl HostDataManagerModel {
     fn getHostDataJson(&self, host_id: QString) -> QString {
         // Doesn't include monitor and command datas.
         if let Some(display_data) = self.display_data.hosts.get(&host_id.to_string()) {
-            let mut stripped = display_data.host_state.clone();
+            let mut stripped = display_data.host_state.as_ref().clone();
             stripped.monitor_data.clear();
             stripped.command_results.clear();
+            stripped.command_history.clear();
             QString::from(serde_json::to_string(&stripped).unwrap())
         }
         else {
@@ -349,12 +377,46 @@ impl HostDataManagerModel {
         }
     }
 
+    fn getHostSummaryJson(&self, host_id: QString) -> QString {
+        if let Some(display_data) = self.display_data.hosts.get(&host_id.to_string()) {
+            QString::from(serde_json::to_string(&display_data.summary).unwrap())
+        }
+        else {
+            QString::from("{}")
+        }
+    }
+
+    fn getRecentErrorsJson(&self, host_id: QString) -> QString {
+        if let Some(display_data) = self.display_data.hosts.get(&host_id.to_string()) {
+            QString::from(serde_json::to_string(&display_data.host_state.recent_errors).unwrap())
+        }
+        else {
+            QString::from("[]")
+        }
+    }
+
+    fn getCommandHistoryJson(&self, host_id: QString, command_id: QString) -> QString {
+        let history = self.display_data.hosts.get(&host_id.to_string())
+            .and_then(|display_data| display_data.host_state.command_history.get(&command_id.to_string()));
+
+        match history {
+            Some(history) => QString::from(serde_json::to_string(history).unwrap()),
+            None => QString::from("[]"),
+        }
+    }
+
+    fn getHostGroupsJson(&mut self) -> QString {
+        // Hosts can be added and removed after initialization, so groups are rebuilt here.
+        self.display_data.update_host_groups();
+        QString::from(serde_json::to_string(&self.display_data.host_groups).unwrap())
+    }
+
     // Returns list of MonitorData structs in JSON. Empty if host doesn't exist.
-    fn get_monitor_data_keys_sorted(&self, monitoring_data: Vec<&MonitoringData>) -> Vec<String> {
+    fn get_monitor_data_keys_sorted(&self, host_id: &String, monitoring_data: Vec<&MonitoringData>) -> Vec<String> {
         let mut keys_ordered = Vec::<String>::new();
 
         // First include data of categories in an order that's defined in configuration.
-        for category in self.display_options_category_order.iter() {
+        for category in self.get_category_order(host_id).iter() {
             let category_monitors = monitoring_data.iter().filter(|data| &data.display_options.category == category)
                                                           .collect::<Vec<&&MonitoringData>>();
             keys_ordered.extend(Self::sort_by_value_type(category_monitors));
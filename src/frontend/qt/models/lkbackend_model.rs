@@ -4,7 +4,7 @@
  */
 
 extern crate qmetaobject;
-use std::{cell::RefCell, rc::Rc, sync::mpsc, thread};
+use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::mpsc, thread};
 
 use qmetaobject::*;
 
@@ -42,6 +42,9 @@ pub struct LkBackend {
     receiveUpdates: qt_method!(fn(&self)),
     reload: qt_method!(fn(&mut self)),
     stop: qt_method!(fn(&mut self)),
+    getConnectionStatsJson: qt_method!(fn(&self, host_id: QString) -> QString),
+    /// Duration 0 ends the maintenance.
+    setHostMaintenance: qt_method!(fn(&self, host_id: QString, duration_minutes: u32)),
 
     //
     // Signals
@@ -103,7 +106,7 @@ impl LkBackend {
                 // Special case for host initialization. Proper monitor processing is started after initialization step.
                 if new_data.host_state.just_initialized {
                     ::log::debug!("Host {} initialized", new_data.host_state.host.name);
-                    self_pinned.borrow().command.borrow_mut().refresh_host_monitors(new_data.host_state.host.name);
+                    self_pinned.borrow().command.borrow_mut().refresh_host_monitors(new_data.host_state.host.name.clone());
                     return;
                 }
 
@@ -127,20 +130,28 @@ impl LkBackend {
             }
         });
 
+        let self_ptr = QPointer::from(&*self);
+        let process_scheduled_refresh = qmetaobject::queued_callback(move |due_refreshes: HashMap<String, Vec<String>>| {
+            if let Some(self_pinned) = self_ptr.as_pinned() {
+                self_pinned.borrow().command.borrow_mut().refresh_scheduled(due_refreshes);
+            }
+        });
+
         let thread = std::thread::spawn(move || {
             loop {
                 match update_receiver.recv() {
                     Ok(received_data) => {
                         match received_data {
                             UIUpdate::Host(display_data) => {
-                                if let Some(new_monitoring_data) = &display_data.new_monitoring_data {
-                                    if new_monitoring_data.1.display_options.use_with_charts {
-                                        process_chart_insert((display_data.host_state.host.name.clone(), new_monitoring_data.1.clone()));
+                                for (_, new_monitoring_data) in display_data.new_monitoring_data.iter() {
+                                    if new_monitoring_data.display_options.use_with_charts {
+                                        process_chart_insert((display_data.host_state.host.name.clone(), new_monitoring_data.clone()));
                                     }
                                 }
                                 process_host_update(display_data);
                             }
                             UIUpdate::Chart(metrics) => process_chart_update(metrics),
+                            UIUpdate::ScheduledRefresh(due_refreshes) => process_scheduled_refresh(due_refreshes),
                             UIUpdate::Stop() => {
                                 ::log::debug!("Gracefully exiting UI state receiver thread");
                                 return;
@@ -167,6 +178,7 @@ impl LkBackend {
             Ok((main_config, hosts_config)) => {
                 self.connection_manager.configure(&hosts_config);
                 self.host_manager.borrow_mut().configure(&hosts_config);
+                self.hosts.borrow_mut().configure(&main_config, &hosts_config);
                 self.command.borrow_mut().configure(
                     &main_config,
                     &hosts_config,
@@ -179,6 +191,7 @@ impl LkBackend {
                 self.host_manager.borrow_mut().start_receiving_updates();
                 self.connection_manager.start_processing_requests();
                 self.command.borrow_mut().start_processing_responses();
+                self.command.borrow_mut().start_refresh_scheduler(self.new_update_sender());
 
                 self.reloaded(QString::from(""));
             },
@@ -190,6 +203,21 @@ impl LkBackend {
         }
     }
 
+    fn getConnectionStatsJson(&self, host_id: QString) -> QString {
+        let stats = self.connection_manager.get_stats(&host_id.to_string());
+        QString::from(serde_json::to_string(&stats).unwrap())
+    }
+
+    fn setHostMaintenance(&self, host_id: QString, duration_minutes: u32) {
+        let until = if duration_minutes > 0 {
+            Some(chrono::Utc::now() + chrono::Duration::minutes(duration_minutes as i64))
+        }
+        else {
+            None
+        };
+        self.host_manager.borrow().set_maintenance(&host_id.to_string(), until);
+    }
+
     pub fn stop(&mut self) {
         if let Some(thread) = self.update_receiver_thread.take() {
             if let Err(error) = self.new_update_sender().send(UIUpdate::Stop()) {
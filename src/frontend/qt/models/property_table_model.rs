@@ -160,7 +160,8 @@ impl PropertyTableModel {
         if monitoring_data.display_options.use_multivalue {
 
             for multivalue1 in last_data_point.multivalue.iter() {
-                if let Some(row_data) = Self::create_single_row_data(monitoring_data, multivalue1.clone(), 1, command_datas) {
+                if let Some(mut row_data) = Self::create_single_row_data(monitoring_data, multivalue1.clone(), 1, command_datas) {
+                    row_data.history = Self::get_history(monitoring_data, Some(&multivalue1.label));
                     row_datas.push(row_data);
                 }
 
@@ -172,7 +173,8 @@ impl PropertyTableModel {
             }
         }
         else {
-            if let Some(row_data) = Self::create_single_row_data(monitoring_data, last_data_point.clone(), 0, command_datas) {
+            if let Some(mut row_data) = Self::create_single_row_data(monitoring_data, last_data_point.clone(), 0, command_datas) {
+                row_data.history = Self::get_history(monitoring_data, None);
                 row_datas.push(row_data);
             }
         }
@@ -180,6 +182,22 @@ impl PropertyTableModel {
         row_datas
     }
 
+    /// Returns the buffered numeric values for sparklines, oldest first.
+    /// For multivalues, values are matched by label since the set of values can change between refreshes.
+    fn get_history(monitoring_data: &MonitoringData, label: Option<&String>) -> Vec<f32> {
+        if monitoring_data.display_options.display_style != frontend::DisplayStyle::Sparkline {
+            return Vec::new();
+        }
+
+        monitoring_data.values.iter()
+            .filter(|data_point| !data_point.is_empty())
+            .filter_map(|data_point| match label {
+                Some(label) => data_point.multivalue.iter().find(|child| &child.label == label).map(|child| child.value_float),
+                None => Some(data_point.value_float),
+            })
+            .collect()
+    }
+
     fn sort_row_data(&self, row_datas: &mut Vec<RowData>) {
         if row_datas.is_empty() {
             return;
@@ -236,6 +254,7 @@ impl PropertyTableModel {
             value: data_point,
             display_options: monitoring_data.display_options.clone(),
             command_datas: level_commands,
+            ..Default::default()
         })
     }
 
@@ -305,7 +324,8 @@ impl QAbstractTableModel for PropertyTableModel {
             1 => {
                 let styled_value = StyledValue {
                     data_point: row_data.value.clone(),
-                    display_options: row_data.display_options.clone()
+                    display_options: row_data.display_options.clone(),
+                    history: row_data.history.clone(),
                 };
 
                 let styled_value_json = serde_json::to_string(&styled_value).unwrap();
@@ -318,8 +338,7 @@ impl QAbstractTableModel for PropertyTableModel {
                                       command.display_options.depends_on_criticality.contains(&row_data.value.criticality))
                     .filter(|command| command.display_options.depends_on_value.is_empty() ||
                                       command.display_options.depends_on_value.contains(&row_data.value.value))
-                    .filter(|command| command.display_options.depends_on_tags.iter().all(|tag| row_data.value.tags.contains(tag)))
-                    .filter(|command| command.display_options.depends_on_no_tags.iter().all(|tag| !row_data.value.tags.contains(tag)))
+                    .filter(|command| command.display_options.tags_match(&row_data.value.tags))
                     .collect::<Vec<CommandButtonData>>();
 
                 serde_json::to_string(&command_datas).unwrap().to_qvariant()
@@ -340,12 +359,15 @@ struct RowData {
     value: DataPoint,
     display_options: frontend::DisplayOptions,
     command_datas: Vec<CommandButtonData>,
+    /// Only filled for DisplayStyle::Sparkline.
+    history: Vec<f32>,
 }
 
 #[derive(Default, Clone, Serialize)]
 struct StyledValue {
     data_point: DataPoint,
     display_options: frontend::DisplayOptions,
+    history: Vec<f32>,
 }
 
 
@@ -7,6 +7,8 @@ import "../Button"
 
 Item {
     id: root
+    /// Used for host-specific display settings.
+    property string hostId: ""
     /// List of CommandButtonData objects.
     property var commands: []
     property int size: 24
@@ -23,7 +25,7 @@ Item {
 
     property bool _showBackground: false
     property bool _showCommands: false
-    property var _alwaysShownCommandIds: commands.filter(command => Theme.allowCollapsingCommand(command.command_id) === "0")
+    property var _alwaysShownCommandIds: commands.filter(command => LK.command.allowCollapsingCommand(root.hostId, command.command_id) === "0")
                                                  .map(command => command.command_id)
     // Shown when `collapsible` is enabled and all of the commands aren't already visible.
     property bool _showMenu: collapsible && _alwaysShownCommandIds.length < commands.length
@@ -103,7 +103,7 @@ Item {
 
                 NormalText {
                     visible: !root.showTimeControls
-                    text: "Lines fo fetch"
+                    text: "Lines to fetch"
                 }
 
                 TextField {
@@ -315,7 +315,7 @@ Item {
             root.pendingInvocation = LK.command.executePlain(
                 root.hostId,
                 root.commandId,
-                [...root.commandParams, "", "", "", ""]
+                [...root.commandParams, "", "", "1", numberOfLines.text]
             )
         }
     }
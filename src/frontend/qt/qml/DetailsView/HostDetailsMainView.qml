@@ -73,6 +73,7 @@ Item {
                 status: root._hostDetails.status
                 fqdn: root._hostDetails.host.fqdn
                 ipAddress: root._hostDetails.host.ip_address
+                description: root._hostDetails.host.description
 
                 Layout.minimumWidth: root.columnMinimumWidth
                 Layout.maximumWidth: root.columnMaximumWidth
@@ -210,6 +211,7 @@ Item {
 
                         PropertyTable {
                             id: propertyTable
+                            hostId: root.hostId
                             category: groupBox.categoryName
                             monitoring_datas: LK.hosts.getCategoryMonitorIds(root.hostId, groupBox.categoryName)
                                                       .map(monitorId => LK.hosts.getMonitoringData(root.hostId, monitorId))
@@ -15,6 +15,7 @@ CategoryGroupBox {
     property string status: ""
     property string fqdn: ""
     property string ipAddress: ""
+    property string description: ""
     property string _categoryName: "host"
     property int refreshProgress: 100
     property bool blocked: refreshProgress < 100
@@ -174,8 +175,21 @@ CategoryGroupBox {
             }
         }
 
+        SmallText {
+            visible: root.description !== ""
+            text: root.description
+            wrapMode: Text.Wrap
+            color: Theme.textColorDark
+
+            Layout.fillWidth: true
+            Layout.topMargin: Theme.spacingNormal
+            Layout.leftMargin: Theme.spacingLoose
+            Layout.rightMargin: Theme.spacingLoose
+        }
+
         PropertyTable {
             id: propertyTable
+            hostId: root.hostId
             category: root._categoryName
             monitoring_datas: LK.hosts.getCategoryMonitorIds(root.hostId, root._categoryName)
                                       .map(monitorId => LK.hosts.getMonitoringData(root.hostId, monitorId))
@@ -7,12 +7,14 @@ import Theme
 
 import "../Text"
 import "../js/ValueUnit.js" as ValueUnit
+import "../js/Utils.js" as Utils
 import "../StyleOverride"
 
 
 // Table for displaying monitoring data and command buttons.
 TableView {
     id: root 
+    property string hostId: ""
     property string category: ""
     // MonitoringDatas as QVariants.
     property var monitoring_datas: []
@@ -37,7 +39,7 @@ TableView {
     model: PropertyTableModel {
         monitoring_datas: root.monitoring_datas
         command_datas: root.command_datas
-        display_options: Theme.getDisplayOptions()
+        display_options: LK.command.getDisplayOptions(root.hostId)
     }
 
 
@@ -139,6 +141,14 @@ TableView {
                 visible: !isSeparator
                 implicitWidth: root.width * root.model.get_column_width(row, column)
 
+                HoverHandler {
+                    id: valueHoverHandler
+                }
+
+                ToolTip.visible: valueHoverHandler.hovered
+                ToolTip.delay: Theme.tooltipDelay
+                ToolTip.text: "Updated " + Utils.formatTimeAgo(styledValue.data_point.time)
+
                 Row {
                     width: parent.width
                     anchors.verticalCenter: parent.verticalCenter
@@ -154,10 +164,11 @@ TableView {
                             anchors.verticalCenter: parent.verticalCenter
                             width: parent.parent.width * 0.6
                             height: 6
-                            value: styledValue.data_point.value_float / 100.0
+                            from: styledValue.display_options.value_min
+                            to: styledValue.display_options.value_max > 0 ? styledValue.display_options.value_max : 100
+                            value: styledValue.data_point.value_float
 
                             // The color can be wrong on some platforms and progress bar invisible, so force color.
-                            // This can also later be used to set color according to criticality level.
                             contentItem: Rectangle {
                                 implicitHeight: progressBar.height
                                 implicitWidth: progressBar.width
@@ -167,7 +178,8 @@ TableView {
                                 Rectangle {
                                     height: parent.height
                                     width: progressBar.visualPosition * parent.width
-                                    color: palette.highlight
+                                    color: ["Warning", "Error", "Critical"].includes(styledValue.data_point.criticality) ?
+                                        Theme.colorForCriticality(styledValue.data_point.criticality) : palette.highlight
                                     radius: parent.radius
                                 }
                             }
@@ -180,6 +192,56 @@ TableView {
                         }
                     }
 
+                    Row {
+                        visible: styledValue.display_options.display_style === "Sparkline"
+                        spacing: Theme.spacingNormal
+
+                        Canvas {
+                            id: sparkline
+                            anchors.verticalCenter: parent.verticalCenter
+                            width: parent.parent.width * 0.4
+                            height: 16
+
+                            property var history: styledValue.history
+                            onHistoryChanged: requestPaint()
+
+                            onPaint: {
+                                let context = getContext("2d")
+                                context.reset()
+
+                                if (history.length < 2) {
+                                    return
+                                }
+
+                                // Scale to the configured range, but expand it if values exceed it.
+                                let minValue = Math.min(styledValue.display_options.value_min, ...history)
+                                let maxValue = Math.max(styledValue.display_options.value_max, ...history)
+                                let range = maxValue - minValue > 0 ? maxValue - minValue : 1
+                                let step = width / (history.length - 1)
+
+                                context.lineWidth = 1.5
+                                context.strokeStyle = palette.highlight
+                                context.beginPath()
+                                for (let i = 0; i < history.length; i++) {
+                                    let y = height - (history[i] - minValue) / range * height
+                                    if (i === 0) {
+                                        context.moveTo(0, y)
+                                    }
+                                    else {
+                                        context.lineTo(i * step, y)
+                                    }
+                                }
+                                context.stroke()
+                            }
+                        }
+
+                        SmallerText {
+                            text: ValueUnit.AsText(styledValue.data_point.value, styledValue.display_options.unit)
+                            anchors.verticalCenter: parent.verticalCenter
+                            lineHeight: 0.9
+                        }
+                    }
+
                     SmallText {
                         visible: styledValue.display_options.display_style === "Text"
                         text: ValueUnit.AsText(styledValue.data_point.value, styledValue.display_options.unit)
@@ -201,7 +263,8 @@ TableView {
                         PillText {
                             anchors.verticalCenter: parent.verticalCenter
                             text: modelData
-                            pillColor: Theme.colorForCriticality("Info")
+                            icon: Theme.tagIcon(styledValue.display_options.category, modelData)
+                            pillColor: Theme.tagColor(styledValue.display_options.category, modelData)
                         }
                     }
                 }
@@ -225,6 +288,7 @@ TableView {
                 // Row-level command buttons, aligned to the right.
                 CommandButtonRow {
                     id: commandButtonRow
+                    hostId: root.hostId
                     visible: parsedCommands.length > 0
                     anchors.verticalCenter: parent.verticalCenter
                     anchors.right: parent.right
@@ -13,9 +13,11 @@ LightkeeperDialog {
     property string text: ""
     // Center short text automatically.
     property bool centerText: text.length < 40
+    // Adds a button for previewing the changes. Emits applied().
+    property bool showDryRun: false
 
     title: "Confirmation"
-    standardButtons: Dialog.Yes | Dialog.No
+    standardButtons: root.showDryRun ? Dialog.Yes | Dialog.No | Dialog.Apply : Dialog.Yes | Dialog.No
     implicitWidth: Utils.clamp(dialogText.implicitWidth, 300, 1000) + 100
     implicitHeight: Utils.clamp(dialogText.implicitHeight, 200, 600) + 50
     anchors.centerIn: parent
@@ -37,6 +39,9 @@ LightkeeperDialog {
     }
 
     Component.onCompleted: {
+        if (root.showDryRun) {
+            root.standardButton(Dialog.Apply).text = "Dry run"
+        }
         visible = true
     }
 }
\ No newline at end of file
@@ -56,6 +56,8 @@ LightkeeperDialog {
             newSettings.fqdn = hostAddressField.text
         }
 
+        newSettings.description = descriptionField.text
+
         if (sshPortField.text !== "" && sshPortField.acceptableInput) {
             newSettings.overrides.connectors = {
                 ssh: {
@@ -143,6 +145,23 @@ LightkeeperDialog {
             }
         }
 
+        Column {
+            spacing: Theme.spacingTight
+            Layout.fillWidth: true
+
+            Label {
+                text: "Description"
+            }
+
+            TextField {
+                id: descriptionField
+                width: parent.width
+                placeholderText: "Notes about the host..."
+                placeholderTextColor: Theme.textColorDark
+                text: root.hostSettings.description === undefined ? "" : root.hostSettings.description
+            }
+        }
+
         Column {
             spacing: Theme.spacingTight
             Layout.fillWidth: true
@@ -185,9 +185,10 @@ Item {
         commandOutputDialog.progress = progress
     }
 
-    function openConfirmationDialog(text, onAccepted) {
+    // If onDryRun is given, the dialog also offers a dry-run that only previews the changes.
+    function openConfirmationDialog(text, onAccepted, onDryRun) {
         let [instanceId, instance] = confirmationDialogManager.create(
-            { text: text },
+            { text: text, showDryRun: onDryRun !== undefined },
             // Removes connection after triggering once.
             {
                 onAccepted: () => {
@@ -196,6 +197,10 @@ Item {
                 },
                 onRejected: () => {
                     confirmationDialogManager.destroyInstance(instanceId)
+                },
+                onApplied: () => {
+                    confirmationDialogManager.destroyInstance(instanceId)
+                    onDryRun()
                 }
             }
         )
@@ -16,7 +16,10 @@ Item {
     Component.onCompleted: {
         colors = {
             up: "forestgreen",
+            warning: "orange",
             down: "firebrick",
+            unreachable: "firebrick",
+            maintenance: "steelblue",
             _: "orange",
         }
     }
@@ -46,7 +49,10 @@ Item {
         NormalText {
             text: root.status.toUpperCase()
             font.family: fontStatus.name
-            color: Theme.criticalityColor(root.status === "up" ? "normal" : root.status === "down" ? "error" : "_")
+            color: Theme.criticalityColor(root.status === "up" ? "normal" :
+                                          root.status === "warning" ? "warning" :
+                                          root.status === "down" || root.status === "unreachable" ? "error" :
+                                          root.status === "maintenance" ? "info" : "_")
 
             Layout.fillWidth: true
             Layout.alignment: Qt.AlignLeft | Qt.AlignVCenter
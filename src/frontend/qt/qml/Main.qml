@@ -81,6 +81,9 @@ ApplicationWindow {
             let hostId = hostTableModel.getSelectedHostId()
             dialogHandler.openHostConfig(hostId)
         }
+        onClickedTestConnection: {
+            LK.command.testConnection(hostTableModel.getSelectedHostId())
+        }
         onClickedCertificateMonitor: {
             root.dialogHandler.openCertificateMonitor()
         }
@@ -157,12 +160,11 @@ ApplicationWindow {
             if (commandResult.show_in_notification === true &&
                 (commandResult.criticality !== "Normal" || LK.config.showInfoNotifications === false)) {
 
-                if (commandResult.error !== "") {
-                    snackbarContainer.addSnackbar(commandResult.criticality, commandResult.error)
-                }
-                else {
-                    snackbarContainer.addSnackbar(commandResult.criticality, commandResult.message)
+                let text = commandResult.error !== "" ? commandResult.error : commandResult.message
+                if (commandResult.return_code !== null && commandResult.return_code !== 0) {
+                    text += ` (exited with code ${commandResult.return_code})`
                 }
+                snackbarContainer.addSnackbar(commandResult.criticality, text)
             }
 
             // No need to check if invocation is relevant to specific dialogs. DialogHandler takes care of that.
@@ -197,7 +199,15 @@ ApplicationWindow {
         target: LK.command
 
         function onConfirmationDialogOpened(text, buttonId, hostId, commandId, commandParams) {
-            root.dialogHandler.openConfirmationDialog(text, () => LK.command.executeConfirmed(buttonId, hostId, commandId, commandParams))
+            let onDryRun = LK.command.supportsDryRun(hostId, commandId, commandParams) ?
+                () => LK.command.executeDryRun(hostId, commandId, commandParams) :
+                undefined
+
+            root.dialogHandler.openConfirmationDialog(
+                text,
+                () => LK.command.executeConfirmed(buttonId, hostId, commandId, commandParams),
+                onDryRun
+            )
         }
 
         function onTextDialogOpened(invocationId) {
@@ -22,6 +22,7 @@ ToolBar {
     signal clickedAdd()
     signal clickedRemove()
     signal clickedEdit()
+    signal clickedTestConnection()
     signal clickedPreferences()
     signal clickedHotkeyHelp()
     signal clickedCertificateMonitor()
@@ -76,6 +77,18 @@ ToolBar {
             padding: 4
         }
 
+        ToolButton {
+            enabled: root.enableEditButtons
+            opacity: Theme.opacity(enabled)
+            display: AbstractButton.IconOnly
+            text: "Test connection"
+            icon.source: "qrc:/main/images/button/ok"
+            onClicked: root.clickedTestConnection()
+            icon.height: root.iconSize
+            icon.width: root.iconSize
+            padding: 4
+        }
+
         Item {
             Layout.fillWidth: true
 
@@ -7,9 +7,10 @@ Item {
     property string text: ""
     property alias textColor: textElement.color
     property string pillColor: "#30FFFFFF"
+    property string icon: ""
     property int padding: 2
 
-    implicitWidth: textElement.implicitWidth + padding * 2
+    implicitWidth: textElement.implicitWidth + padding * 2 + (iconElement.visible ? iconElement.width + padding : 0)
     implicitHeight: textElement.implicitHeight
 
     Rectangle {
@@ -19,6 +20,17 @@ Item {
         visible: TextTransform.removeWhitespaces(textElement.text).length > 0
     }
 
+    Image {
+        id: iconElement
+        visible: root.icon !== ""
+        source: root.icon
+        anchors.left: parent.left
+        anchors.leftMargin: root.padding
+        anchors.verticalCenter: parent.verticalCenter
+        width: textElement.implicitHeight * 0.8
+        height: width
+    }
+
     Text {
         id: textElement
         text: root.text
@@ -26,7 +38,7 @@ Item {
         font.pointSize: 8
         verticalAlignment: Text.AlignVCenter
         horizontalAlignment: Text.AlignHCenter
-        leftPadding: root.padding
+        leftPadding: root.padding + (iconElement.visible ? iconElement.width + root.padding : 0)
         rightPadding: root.padding
     }
 }
\ No newline at end of file
@@ -30,4 +30,19 @@ function getLocalTimezoneISOString(now) {
     const seconds = ('0' + date.getSeconds()).slice(-2);
 
     return year + '-' + month + '-' + day + 'T' + hours + ':' + minutes + ':' + seconds;
-}
\ No newline at end of file
+}
+
+/// Returns elapsed time in human-readable format, e.g. "3 min ago".
+function formatTimeAgo(timestamp) {
+    let seconds = Math.max(0, Math.floor((Date.now() - new Date(timestamp).getTime()) / 1000));
+    if (seconds < 60) {
+        return seconds + " s ago";
+    }
+    else if (seconds < 3600) {
+        return Math.floor(seconds / 60) + " min ago";
+    }
+    else if (seconds < 86400) {
+        return Math.floor(seconds / 3600) + " h ago";
+    }
+    return Math.floor(seconds / 86400) + " d ago";
+}
@@ -21,9 +21,12 @@ pub fn init_resources() {
             "images/breeze/light/alarm-symbolic.svg" as "images/criticality/nodata",
 
             "images/fontawesome/circle-arrow-up.svg" as "images/status/up",
+            "images/fontawesome/circle-exclamation.svg" as "images/status/warning",
             "images/fontawesome/circle-arrow-down.svg" as "images/status/down",
+            "images/fontawesome/circle-exclamation.svg" as "images/status/unreachable",
             "images/breeze/dark/alarm-symbolic.svg" as "images/status/pending",
             "images/breeze/dark/alarm-symbolic.svg" as "images/status/unknown",
+            "images/breeze/dark/configure.svg" as "images/status/maintenance",
 
             "images/breeze/dark/data-information.svg" as "images/alert/info",
             "images/breeze/dark/data-warning.svg" as "images/alert/warning",
@@ -112,9 +115,12 @@ pub fn init_resources() {
             "images/breeze/light/alarm-symbolic.svg" as "images/criticality/nodata",
 
             "images/fontawesome/circle-arrow-up.svg" as "images/status/up",
+            "images/fontawesome/circle-exclamation.svg" as "images/status/warning",
             "images/fontawesome/circle-arrow-down.svg" as "images/status/down",
+            "images/fontawesome/circle-exclamation.svg" as "images/status/unreachable",
             "images/breeze/light/alarm-symbolic.svg" as "images/status/pending",
             "images/breeze/light/alarm-symbolic.svg" as "images/status/unknown",
+            "images/breeze/light/configure.svg" as "images/status/maintenance",
 
             "images/breeze/light/data-information.svg" as "images/alert/info",
             "images/breeze/light/data-warning.svg" as "images/alert/warning",
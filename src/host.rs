@@ -16,6 +16,12 @@ pub struct Host {
     pub ip_address: IpAddr,
     pub platform: PlatformInfo,
     pub settings: Vec<HostSetting>,
+    /// Configuration groups the host belongs to.
+    #[serde(default)]
+    pub groups: Vec<String>,
+    /// Free-text notes from configuration.
+    #[serde(default)]
+    pub description: String,
 }
 
 impl Host {
@@ -37,6 +43,8 @@ impl Host {
             },
             platform: PlatformInfo::new(),
             settings: settings.to_vec(),
+            groups: Vec::new(),
+            description: String::new(),
         };
 
         Ok(new)
@@ -49,6 +57,8 @@ impl Host {
             ip_address: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
             platform: PlatformInfo::default(),
             settings: settings.to_vec(),
+            groups: Vec::new(),
+            description: String::new(),
         }
     }
 
@@ -96,6 +106,8 @@ impl Default for Host {
             ip_address: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
             platform: PlatformInfo::default(),
             settings: Vec::default(),
+            groups: Vec::default(),
+            description: String::default(),
         }
     }
 }
@@ -108,4 +120,7 @@ pub enum HostSetting {
     #[default]
     /// Use sudo for commands that require higher privileges.
     UseSudo,
+    /// Use Podman instead of Docker with container modules. Uses Podman's Docker-compatible socket and CLI.
+    /// Podman is also used without this if it's detected and Docker isn't.
+    UsePodman,
 }
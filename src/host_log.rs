@@ -0,0 +1,88 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::file_handler;
+
+/// When a host log file grows larger than this, it's rotated and the previous rotated file is overwritten.
+const MAX_LOG_FILE_SIZE: u64 = 1024 * 1024;
+const LOG_DIR_NAME: &str = "logs";
+
+/// Hosts whose log lines are also written to a separate file. Empty if per-host logs are disabled.
+static LOGGED_HOSTS: RwLock<Option<HashSet<String>>> = RwLock::new(None);
+
+/// Enables or disables per-host log files. Log lines are routed by the `[host_name]` prefix in the message.
+pub fn configure(enabled: bool, host_ids: HashSet<String>) {
+    *LOGGED_HOSTS.write().unwrap() = if enabled { Some(host_ids) } else { None };
+}
+
+/// Log target that writes everything to stderr and additionally copies host-specific lines to per-host files.
+pub struct HostLogWriter {
+    stderr: io::Stderr,
+}
+
+impl HostLogWriter {
+    pub fn new() -> Self {
+        HostLogWriter {
+            stderr: io::stderr(),
+        }
+    }
+
+    fn write_host_log(host_id: &str, line: &[u8]) -> io::Result<()> {
+        let log_dir = file_handler::get_data_dir()?.join(LOG_DIR_NAME);
+        fs::create_dir_all(&log_dir)?;
+
+        let log_path = log_dir.join(format!("{}.log", host_id));
+        if fs::metadata(&log_path).map(|metadata| metadata.len() > MAX_LOG_FILE_SIZE).unwrap_or(false) {
+            fs::rename(&log_path, Self::get_rotated_path(&log_path))?;
+        }
+
+        fs::OpenOptions::new().create(true).append(true).open(&log_path)?.write_all(line)
+    }
+
+    fn get_rotated_path(log_path: &PathBuf) -> PathBuf {
+        let mut rotated_path = log_path.clone().into_os_string();
+        rotated_path.push(".1");
+        PathBuf::from(rotated_path)
+    }
+
+    /// Parses host name from a formatted log line, e.g. "[2025-01-01T00:00:00Z INFO  lightkeeper] [host][module] Message".
+    fn parse_host_id(line: &str) -> Option<&str> {
+        let message = line.split_once("] ")?.1;
+        let host_id = message.strip_prefix('[')?.split_once(']')?.0;
+        Some(host_id)
+    }
+}
+
+impl Default for HostLogWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Write for HostLogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(logged_hosts) = LOGGED_HOSTS.read().unwrap().as_ref() {
+            let line = String::from_utf8_lossy(buf);
+            if let Some(host_id) = Self::parse_host_id(&line).filter(|host_id| logged_hosts.contains(*host_id)) {
+                if let Err(error) = Self::write_host_log(host_id, buf) {
+                    // Can't use the logger here.
+                    let _ = writeln!(self.stderr, "Failed to write host log for {}: {}", host_id, error);
+                }
+            }
+        }
+
+        self.stderr.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stderr.flush()
+    }
+}
@@ -4,12 +4,14 @@
  */
 
 
-use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::str::FromStr;
 use std::sync::mpsc;
 use std::thread;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use serde_derive::{Deserialize, Serialize};
 
 use crate::configuration::ConfigGroup;
@@ -34,6 +36,12 @@ use crate::{
 };
 
 const DATA_POINT_BUFFER_SIZE: usize = 4;
+const ERROR_BUFFER_SIZE: usize = 50;
+const COMMAND_HISTORY_SIZE: usize = 10;
+/// State updates arriving within this window are sent to the front end together.
+const UPDATE_BATCH_WINDOW: Duration = Duration::from_millis(50);
+/// How often expired maintenance periods are checked, even if no state updates arrive.
+const MAINTENANCE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
 
 
 /// Manages the host states.
@@ -106,7 +114,10 @@ impl HostManager {
             log::debug!("Configuring host {}", host_id);
 
             // TODO: UseSudo is currently always assumed.
-            if let Ok(host) = Host::new(host_id, &new_host_config.address, &new_host_config.fqdn, &vec![crate::host::HostSetting::UseSudo]) {
+            if let Ok(mut host) = Host::new(host_id, &new_host_config.address, &new_host_config.fqdn, &vec![crate::host::HostSetting::UseSudo]) {
+                host.groups = new_host_config.groups.clone();
+                host.description = new_host_config.description.clone();
+
                 if host_states.hosts.contains_key(&host.name) {
                     log::error!("Host '{}' already exists", host.name);
                     continue;
@@ -121,6 +132,18 @@ impl HostManager {
             }
         }
 
+        // Group membership and description can change without reinitializing the host.
+        for (host_id, host_config) in hosts_config.hosts.iter() {
+            if let Some(host_state) = host_states.hosts.get_mut(host_id) {
+                host_state.host.groups = host_config.groups.clone();
+                host_state.host.description = host_config.description.clone();
+                host_state.critical_monitors = host_config.effective.monitors.iter()
+                    .filter(|(_, monitor_config)| monitor_config.is_critical.unwrap_or(false))
+                    .map(|(monitor_id, _)| monitor_id.clone())
+                    .collect();
+            }
+        }
+
         self.current_config = hosts_config.hosts.iter()
             .map(|(host_id, config)| (host_id.clone(), config.effective.clone()))
             .collect();
@@ -148,6 +171,73 @@ impl HostManager {
                    .host.clone()
     }
 
+    pub fn get_host_status(&self, host_name: &String) -> HostStatus {
+        let hosts = self.hosts.lock().unwrap();
+        hosts.hosts.get(host_name).map(|host_state| host_state.status).unwrap_or_default()
+    }
+
+    /// Returns tags from all monitors of the host. Used for evaluating host-level tag dependencies.
+    pub fn get_host_tags(&self, host_name: &String) -> HashSet<String> {
+        let hosts = self.hosts.lock().unwrap();
+        hosts.hosts.get(host_name).map(|host_state| host_state.get_all_tags()).unwrap_or_default()
+    }
+
+    /// Puts host in maintenance mode until the given time. `None` ends the maintenance.
+    pub fn set_maintenance(&self, host_name: &String, until: Option<DateTime<Utc>>) {
+        let mut hosts = self.hosts.lock().unwrap();
+        if let Some(host_state) = hosts.hosts.get_mut(host_name) {
+            match until {
+                Some(until) => log::info!("[{}] Maintenance mode enabled until {}", host_name, until),
+                None => log::info!("[{}] Maintenance mode disabled", host_name),
+            }
+            host_state.maintenance_until = until;
+            host_state.update_status();
+        }
+    }
+
+    /// Changes whether the monitor affects the host status. Status is updated on the next state update.
+    pub fn set_monitor_critical(&self, host_name: &String, monitor_id: &String, is_critical: bool) {
+        let mut hosts = self.hosts.lock().unwrap();
+        if let Some(host_state) = hosts.hosts.get_mut(host_name) {
+            if is_critical {
+                host_state.critical_monitors.insert(monitor_id.clone());
+            }
+            else {
+                host_state.critical_monitors.remove(monitor_id);
+            }
+
+            if let Some(monitoring_data) = host_state.monitor_data.get_mut(monitor_id) {
+                monitoring_data.is_critical = is_critical;
+            }
+        }
+    }
+
+    /// Returns the latest data points of the given monitors, labeled with the monitor ID.
+    /// Monitors that have no data yet are left out.
+    pub fn get_latest_data_points(&self, host_name: &String, monitor_ids: &[String]) -> Vec<DataPoint> {
+        let hosts = self.hosts.lock().unwrap();
+        let host_state = match hosts.hosts.get(host_name) {
+            Some(host_state) => host_state,
+            None => return Vec::new(),
+        };
+
+        monitor_ids.iter().filter_map(|monitor_id| {
+            host_state.monitor_data.get(monitor_id)
+                .and_then(|monitoring_data| monitoring_data.values.back())
+                .map(|data_point| {
+                    let mut data_point = data_point.clone();
+                    data_point.label = monitor_id.clone();
+                    data_point
+                })
+        }).collect()
+    }
+
+    /// Returns IDs of all monitor invocations that are still in progress.
+    pub fn get_pending_monitor_invocations(&self) -> HashSet<u64> {
+        let hosts = self.hosts.lock().unwrap();
+        hosts.hosts.values().flat_map(|host_state| host_state.monitor_invocations.keys().cloned()).collect()
+    }
+
     pub fn new_state_update_sender(&self) -> mpsc::Sender<StateUpdateMessage> {
         self.data_sender_prototype.as_ref().unwrap().clone()
     }
@@ -174,152 +264,287 @@ impl HostManager {
         thread::spawn(move || {
             log::debug!("Started receiving updates");
 
+            let mut next_maintenance_check = Instant::now() + MAINTENANCE_CHECK_INTERVAL;
+
             loop {
-                let state_update = match receiver.recv() {
+                if Instant::now() >= next_maintenance_check {
+                    next_maintenance_check = Instant::now() + MAINTENANCE_CHECK_INTERVAL;
+                    let expired_updates = Self::expire_maintenance(&mut hosts.lock().unwrap());
+                    Self::send_pending_updates(&hosts.lock().unwrap(), expired_updates, &mut observers.lock().unwrap());
+                }
+
+                let mut state_update = match receiver.recv_timeout(next_maintenance_check.saturating_duration_since(Instant::now())) {
                     Ok(data) => data,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
                     Err(error) => {
                         log::error!("Stopped receiver thread: {}", error);
                         return;
                     }
                 };
 
-                if state_update.stop {
+                // Updates arriving within the batch window are coalesced into one update per host.
+                // Host name is the key.
+                let mut pending_updates = HashMap::<String, frontend::HostDisplayData>::new();
+                let batch_deadline = Instant::now() + UPDATE_BATCH_WINDOW;
+                let mut stop = false;
+
+                loop {
+                    if state_update.stop {
+                        stop = true;
+                        break;
+                    }
+
+                    Self::process_state_update(&mut hosts.lock().unwrap(), state_update, &mut pending_updates);
+
+                    state_update = match receiver.recv_timeout(batch_deadline.saturating_duration_since(Instant::now())) {
+                        Ok(data) => data,
+                        Err(mpsc::RecvTimeoutError::Timeout) => break,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => {
+                            // Error is logged on the next `recv`.
+                            break;
+                        }
+                    };
+                }
+
+                Self::send_pending_updates(&hosts.lock().unwrap(), pending_updates, &mut observers.lock().unwrap());
+
+                if stop {
                     log::debug!("Gracefully stopping receiver thread");
                     return;
                 }
+            }
+        })
+    }
 
-                let mut host_states = hosts.lock().unwrap();
-                let host_state = match host_states.hosts.get_mut(&state_update.host_name) {
-                    Some(host_state) => host_state,
-                    // It's possible that we receive state update from host that was just removed.
-                    None => {
-                        ::log::debug!("Host {} not found", state_update.host_name);
-                        continue;
-                    },
-                };
+    /// Updates host state and collects the new data to `pending_updates`.
+    fn process_state_update(
+        host_states: &mut HostStateCollection,
+        state_update: StateUpdateMessage,
+        pending_updates: &mut HashMap<String, frontend::HostDisplayData>) {
+
+        let host_state = match host_states.hosts.get_mut(&state_update.host_name) {
+            Some(host_state) => host_state,
+            // It's possible that we receive state update from host that was just removed.
+            None => {
+                ::log::debug!("Host {} not found", state_update.host_name);
+                return;
+            },
+        };
 
-                host_state.just_initialized = false;
-                let mut new_monitoring_data: Option<(u64, MonitoringData)> = None;
-                let mut new_command_results: Option<(u64, CommandResult)> = None;
+        let pending = pending_updates.entry(state_update.host_name.clone()).or_default();
 
-                if let Some(message_data_point) = state_update.data_point {
-                    // Specially structured data point for passing platform info here.
-                    if message_data_point.is_platform_info() {
-                        host_state.monitor_invocations.remove(&state_update.invocation_id);
+        host_state.just_initialized = false;
 
-                        if let Ok((platform, ip_address)) = Self::read_platform_info(&message_data_point) {
-                            host_state.host.platform = platform;
-                            host_state.host.ip_address = ip_address;
-                            log::debug!("[{}] Platform info updated", host_state.host.name);
+        let source_id = state_update.module_spec.id.clone();
+        let connection_failed = state_update.errors.iter().any(|error| error.kind == crate::error::ErrorKind::ConnectionFailed);
+        // NoData points and results are sent when invocations start so they are not responses from the host.
+        // Reachability checks, like ping, are run locally, so their responses don't tell whether connecting to the host works.
+        let is_reachability_check = state_update.display_options.is_reachability_check;
+        let received_response = !is_reachability_check && state_update.errors.is_empty() &&
+            (state_update.data_point.as_ref().map(|data_point| data_point.criticality != Criticality::NoData).unwrap_or(false) ||
+             state_update.command_result.as_ref().map(|result| result.criticality != Criticality::NoData).unwrap_or(false));
 
-                            // TODO: handle multiple platform info's.
-                            host_state.just_initialized = true;
-                            host_state.is_initialized = true;
-                        }
-                        else {
-                            log::error!("[{}] Invalid platform info received", host_state.host.name);
-                        }
-                    }
-                    else {
-                        // Initial NoData point will have invocation ID 0.
-                        if state_update.invocation_id == 0 {
-                            if !host_state.monitor_data.contains_key(&state_update.module_spec.id) {
-                                let mut new_data = MonitoringData::new(state_update.module_spec.id.clone(), state_update.display_options);
-                                new_data.values.push_back(message_data_point.clone());
-                                host_state.monitor_data.insert(state_update.module_spec.id.clone(), new_data);
-                            }
-                        }
-                        else if message_data_point.criticality == Criticality::NoData {
-                            host_state.monitor_invocations
-                                .entry(state_update.invocation_id)
-                                .or_insert(InvocationDetails::new(state_update.invocation_id, state_update.display_options.category));
-                        }
-                        else {
-                            host_state.monitor_invocations.remove(&state_update.invocation_id);
-
-                            // Monitoring data for platform info providers / internal modules won't exist in `monitor_data`.
-                            if let Some(monitoring_data) = host_state.monitor_data.get_mut(&state_update.module_spec.id) {
-                                monitoring_data.values.push_back(message_data_point.clone());
-
-                                if monitoring_data.values.len() > DATA_POINT_BUFFER_SIZE {
-                                    monitoring_data.values.pop_front();
-                                }
-
-                                // Also add to a list of new data points.
-                                let mut new = host_state.monitor_data.get(&state_update.module_spec.id).unwrap().clone();
-                                new.values = VecDeque::from(vec![message_data_point.clone()]);
-                                new_monitoring_data = Some((state_update.invocation_id, new));
-                            }
-                        }
-                    }
+        if state_update.remove_monitor_data {
+            host_state.monitor_data.remove(&state_update.module_spec.id);
+            host_state.monitor_invocations.remove(&state_update.invocation_id);
+        }
+        else if let Some(message_data_point) = state_update.data_point {
+            // Specially structured data point for passing platform info here.
+            if message_data_point.is_platform_info() {
+                host_state.monitor_invocations.remove(&state_update.invocation_id);
+
+                if let Ok((platform, ip_address)) = Self::read_platform_info(&message_data_point) {
+                    host_state.host.platform = platform;
+                    host_state.host.ip_address = ip_address;
+                    log::debug!("[{}] Platform info updated", host_state.host.name);
+
+                    // TODO: handle multiple platform info's.
+                    host_state.just_initialized = true;
+                    host_state.is_initialized = true;
+                    // Has to be preserved over the rest of the batch.
+                    Arc::make_mut(&mut pending.host_state).just_initialized = true;
+                }
+                else {
+                    log::error!("[{}] Invalid platform info received", host_state.host.name);
                 }
-                else if let Some(command_result) = state_update.command_result {
-                    if command_result.criticality == Criticality::NoData {
-                        host_state.command_invocations
-                            .entry(state_update.invocation_id)
-                            .or_insert(InvocationDetails::new(state_update.invocation_id, state_update.display_options.category));
+            }
+            else {
+                // Initial NoData point will have invocation ID 0.
+                if state_update.invocation_id == 0 {
+                    if !host_state.monitor_data.contains_key(&state_update.module_spec.id) {
+                        let mut new_data = MonitoringData::new(state_update.module_spec.id.clone(), state_update.display_options);
+                        new_data.is_critical = host_state.critical_monitors.contains(&state_update.module_spec.id);
+                        new_data.values.push_back(message_data_point.clone());
+                        host_state.monitor_data.insert(state_update.module_spec.id.clone(), new_data);
                     }
-                    else {
-                        // Can be a partial result.
-                        if command_result.progress < 100 {
-                            host_state.command_invocations
-                                .entry(state_update.invocation_id)
-                                .and_modify(|invocation| invocation.progress = command_result.progress);
-                        }
-                        else {
-                            host_state.command_invocations.remove(&state_update.invocation_id);
+                }
+                else if message_data_point.criticality == Criticality::NoData {
+                    host_state.monitor_invocations
+                        .entry(state_update.invocation_id)
+                        .or_insert(InvocationDetails::new(state_update.invocation_id, state_update.display_options.category));
+                }
+                else {
+                    host_state.monitor_invocations.remove(&state_update.invocation_id);
+
+                    // Monitoring data for platform info providers / internal modules won't exist in `monitor_data`.
+                    if let Some(monitoring_data) = host_state.monitor_data.get_mut(&state_update.module_spec.id) {
+                        monitoring_data.values.push_back(message_data_point.clone());
+
+                        if monitoring_data.values.len() > DATA_POINT_BUFFER_SIZE {
+                            monitoring_data.values.pop_front();
                         }
-                        host_state.command_results.insert(state_update.module_spec.id, command_result.clone());
-                        // Also add to a list of new command results.
-                        new_command_results = Some((state_update.invocation_id, command_result));
+
+                        // Also add to a list of new data points.
+                        let mut new = host_state.monitor_data.get(&state_update.module_spec.id).unwrap().clone();
+                        new.values = VecDeque::from(vec![message_data_point.clone()]);
+                        pending.new_monitoring_data.push((state_update.invocation_id, new));
                     }
                 }
+            }
+        }
+        else if let Some(command_result) = state_update.command_result {
+            if command_result.criticality == Criticality::NoData {
+                host_state.command_invocations
+                    .entry(state_update.invocation_id)
+                    .or_insert(InvocationDetails::new(state_update.invocation_id, state_update.display_options.category));
+            }
+            else {
+                // Can be a partial result.
+                if command_result.progress < 100 {
+                    host_state.command_invocations
+                        .entry(state_update.invocation_id)
+                        .and_modify(|invocation| invocation.progress = command_result.progress);
+                }
                 else {
-                    // Not all state updates will have valid data points or command results.
-                    // Still need to remove invocation data.
-                    match state_update.module_spec.module_type {
-                        crate::module::ModuleType::Command => {
-                            host_state.command_invocations.remove(&state_update.invocation_id);
-                        },
-                        crate::module::ModuleType::Monitor => {
-                            host_state.monitor_invocations.remove(&state_update.invocation_id);
-                        },
-                        crate::module::ModuleType::Unknown |
-                        crate::module::ModuleType::Connector => {},
+                    host_state.command_invocations.remove(&state_update.invocation_id);
+
+                    let history = host_state.command_history.entry(state_update.module_spec.id.clone()).or_default();
+                    history.push_back(command_result.clone());
+                    if history.len() > COMMAND_HISTORY_SIZE {
+                        history.pop_front();
                     }
                 }
+                host_state.command_results.insert(state_update.module_spec.id, command_result.clone());
+                // Also add to a list of new command results.
+                pending.new_command_results.push((state_update.invocation_id, command_result));
+            }
+        }
+        else {
+            // Not all state updates will have valid data points or command results.
+            // Still need to remove invocation data.
+            match state_update.module_spec.module_type {
+                crate::module::ModuleType::Command => {
+                    host_state.command_invocations.remove(&state_update.invocation_id);
+                },
+                crate::module::ModuleType::Monitor => {
+                    host_state.monitor_invocations.remove(&state_update.invocation_id);
+                },
+                crate::module::ModuleType::Unknown |
+                crate::module::ModuleType::Connector => {},
+            }
+        }
+
+        if is_reachability_check {
+            if let Some(data_point) = host_state.monitor_data.get(&source_id).and_then(|data| data.values.back()) {
+                if data_point.criticality != Criticality::NoData {
+                    host_state.responds_to_ping = Some(data_point.criticality != Criticality::Critical);
+                }
+            }
+        }
+
+        if connection_failed {
+            if !host_state.is_unreachable {
+                if host_state.responds_to_ping == Some(true) {
+                    log::warn!("[{}] Host is unreachable even though it responds to ping", host_state.host.name);
+                }
+                else {
+                    log::warn!("[{}] Host is unreachable", host_state.host.name);
+                }
+            }
+            host_state.is_unreachable = true;
+        }
+        else if received_response {
+            host_state.is_unreachable = false;
+        }
+
+        host_state.update_status();
+
+        let (verification_requests, unhandled_errors): (Vec<_>, Vec<_>) = state_update.errors.into_iter()
+            .partition(|error| error.kind == crate::error::ErrorKind::HostKeyNotVerified);
+
+        pending.verification_requests.extend(verification_requests.iter()
+            .map(|error| VerificationRequest {
+                source_id: error.source_id.to_owned(),
+                key_id: error.parameter.to_owned().unwrap(),
+                message: error.message.to_owned(),
+            }));
+
+        for error in unhandled_errors {
+            let mut error = ErrorMessage::from(error);
+            // Errors from connectors have the connector as the source, so prefer the module that sent the update.
+            if !source_id.is_empty() {
+                error = error.with_source(&source_id);
+            }
+            host_state.recent_errors.push_back(error.clone());
+            if host_state.recent_errors.len() > ERROR_BUFFER_SIZE {
+                host_state.recent_errors.pop_front();
+            }
+            pending.new_errors.push(error);
+        }
+    }
+
+    /// Ends maintenance periods that have expired. Returns updates for the affected hosts.
+    fn expire_maintenance(host_states: &mut HostStateCollection) -> HashMap<String, frontend::HostDisplayData> {
+        let mut pending_updates = HashMap::new();
 
+        for (host_name, host_state) in host_states.hosts.iter_mut() {
+            if matches!(host_state.maintenance_until, Some(until) if until <= Utc::now()) {
                 host_state.update_status();
+                pending_updates.insert(host_name.clone(), frontend::HostDisplayData::default());
+            }
+        }
 
-                let (verification_requests, unhandled_errors): (Vec<_>, Vec<_>) = state_update.errors.into_iter()
-                    .partition(|error| error.kind == crate::error::ErrorKind::HostKeyNotVerified);
-
-                let verification_requests: Vec<_> = verification_requests.iter()
-                    .map(|error| VerificationRequest {
-                        source_id: error.source_id.to_owned(),
-                        key_id: error.parameter.to_owned().unwrap(),
-                        message: error.message.to_owned(),
-                    }) .collect();
-
-
-                // Send the state update to the front end.
-                for observer in observers.lock().unwrap().iter() {
-                    observer.send(frontend::UIUpdate::Host(
-                        frontend::HostDisplayData {
-                            host_state: host_state.clone(),
-                            new_monitoring_data: new_monitoring_data.clone(),
-                            new_command_result: new_command_results.clone(),
-                            new_errors: unhandled_errors.iter().cloned().map(ErrorMessage::from).collect(),
-                            verification_requests: verification_requests.clone(),
-                            ..Default::default()
-                        })
-                    ).unwrap();
+        pending_updates
+    }
+
+    /// Sends the state updates to the front end. Host state is copied only once per batch.
+    /// Observers that have dropped their receiver are removed.
+    fn send_pending_updates(
+        host_states: &HostStateCollection,
+        pending_updates: HashMap<String, frontend::HostDisplayData>,
+        observers: &mut Vec<mpsc::Sender<frontend::UIUpdate>>) {
+
+        for (host_name, mut display_data) in pending_updates {
+            let host_state = match host_states.hosts.get(&host_name) {
+                Some(host_state) => host_state,
+                None => continue,
+            };
+
+            let mut host_state = host_state.clone();
+            host_state.just_initialized = display_data.host_state.just_initialized;
+            display_data.summary = host_state.get_summary();
+            display_data.host_state = Arc::new(host_state);
+
+            // Only the Arc is cloned for each observer.
+            observers.retain(|observer| {
+                match observer.send(frontend::UIUpdate::Host(display_data.clone())) {
+                    Ok(()) => true,
+                    Err(_) => {
+                        log::debug!("Removing observer that has stopped receiving updates");
+                        false
+                    }
                 }
-            }
-        })
+            });
+        }
     }
 
     pub fn get_display_data(&self) -> frontend::DisplayData {
+        self.get_filtered_display_data(None)
+    }
+
+    /// Returns only monitors and command results matching the filter. Filter is a case-insensitive substring matched
+    /// against monitor IDs, display texts and multivalue labels. Filter in format `tag:<tag>` matches data point tags instead.
+    pub fn get_filtered_display_data(&self, filter: Option<&str>) -> frontend::DisplayData {
         let mut display_data = frontend::DisplayData::new();
 
         let hosts = self.hosts.lock().unwrap();
@@ -338,16 +563,61 @@ impl HostManager {
         }
 
         for (host_name, state) in hosts.hosts.iter() {
+            let host_state = match filter {
+                Some(filter) if !filter.is_empty() => Self::filter_host_state(state, filter),
+                _ => state.clone(),
+            };
+
             display_data.hosts.insert(host_name.clone(), frontend::HostDisplayData {
-                host_state: state.clone(),
+                summary: host_state.get_summary(),
+                host_state: Arc::new(host_state),
                 ..Default::default()
             });
         }
 
+        display_data.update_host_groups();
         display_data.table_headers = vec![String::from("Status"), String::from("Name"), String::from("FQDN"), String::from("IP address")];
         display_data
     }
 
+    fn filter_host_state(host_state: &HostState, filter: &str) -> HostState {
+        let filter = filter.to_lowercase();
+        let tag_filter = filter.strip_prefix("tag:").map(|tag| tag.to_string());
+        let matches_text = |text: &String| tag_filter.is_none() && text.to_lowercase().contains(&filter);
+
+        let mut filtered_state = host_state.clone();
+
+        filtered_state.monitor_data.retain(|monitor_id, monitor_data| {
+            if matches_text(monitor_id) || matches_text(&monitor_data.display_options.display_text) {
+                return true;
+            }
+
+            // Otherwise, only keep the matching multivalue rows.
+            for data_point in monitor_data.values.iter_mut() {
+                if let Some(tag) = &tag_filter {
+                    if !data_point.multivalue.is_empty() {
+                        data_point.multivalue.retain(|child| child.tags.iter().any(|child_tag| &child_tag.to_lowercase() == tag));
+                    }
+                    else if !data_point.tags.iter().any(|data_point_tag| &data_point_tag.to_lowercase() == tag) {
+                        data_point.value.clear();
+                    }
+                }
+                else {
+                    data_point.multivalue.retain(|child| matches_text(&child.label));
+                    if data_point.multivalue.is_empty() {
+                        data_point.value.clear();
+                    }
+                }
+            }
+
+            monitor_data.values.back().map(|data_point| !data_point.is_empty()).unwrap_or(false)
+        });
+
+        filtered_state.command_results.retain(|command_id, _| matches_text(command_id));
+        filtered_state.command_history.retain(|command_id, _| matches_text(command_id));
+        filtered_state
+    }
+
     fn read_platform_info(data_point: &DataPoint) -> Result<(platform_info::PlatformInfo, std::net::IpAddr), String> {
         let mut ip_address = std::net::IpAddr::V4(std::net::Ipv4Addr::new(0, 0, 0, 0));
         let mut platform = platform_info::PlatformInfo::default();
@@ -365,6 +635,9 @@ impl HostManager {
                 "architecture" => {
                     platform.architecture = platform_info::Architecture::from(&data.value)
                 },
+                "container_runtime" => {
+                    platform.container_runtime = platform_info::ContainerRuntime::from_str(data.value.as_str()).map_err(|error| error.to_string())?
+                },
                 "ip_address" => {
                     ip_address = std::net::IpAddr::from_str(data.value.as_str()).map_err(|error| error.to_string())?
                 },
@@ -395,6 +668,8 @@ pub struct StateUpdateMessage {
     pub errors: Vec<LkError>,
     /// Unique invocation ID. Used as an identifier for asynchronously executed requests and received results.
     pub invocation_id: u64,
+    /// Only used with monitors. Removes existing data of the monitor, e.g. when the monitor gets disabled.
+    pub remove_monitor_data: bool,
     /// Stops the receiver thread.
     pub stop: bool,
 }
@@ -429,14 +704,32 @@ pub struct HostState {
     /// Host has received a real-time update for platform info.
     pub just_initialized: bool,
     pub is_initialized: bool,
+    /// Latest connection attempt failed. Cleared when a response is received successfully.
+    #[serde(default)]
+    pub is_unreachable: bool,
+    /// Latest result of a reachability check (e.g. the ping monitor), if used. Tells apart hosts that are down from hosts where only connecting fails.
+    #[serde(default)]
+    pub responds_to_ping: Option<bool>,
     /// Monitor ID as key.
     pub monitor_data: HashMap<String, MonitoringData>,
     /// Command ID as key.
     pub command_results: HashMap<String, CommandResult>,
+    /// Previous final results, oldest first. Command ID as key. Limited to `COMMAND_HISTORY_SIZE` entries per command.
+    #[serde(default)]
+    pub command_history: HashMap<String, VecDeque<CommandResult>>,
     /// Invocations in progress. Keeps track of monitor progress. Empty when all is done.
     pub monitor_invocations: HashMap<u64, InvocationDetails>,
     /// Invocations in progress. Keeps track of command progress. Empty when all is done.
     pub command_invocations: HashMap<u64, InvocationDetails>,
+    /// Host is in maintenance mode until this time. Cleared automatically when expired.
+    #[serde(default)]
+    pub maintenance_until: Option<DateTime<Utc>>,
+    /// Monitors that affect the host status. Host is considered down if any of them is critical.
+    #[serde(default)]
+    pub critical_monitors: HashSet<String>,
+    /// Latest errors, oldest first. Limited to `ERROR_BUFFER_SIZE` entries.
+    #[serde(default)]
+    pub recent_errors: VecDeque<ErrorMessage>,
 }
 
 impl HostState {
@@ -446,13 +739,62 @@ impl HostState {
             status: status,
             just_initialized: false,
             is_initialized: false,
+            is_unreachable: false,
+            responds_to_ping: None,
             monitor_data: HashMap::new(),
             command_results: HashMap::new(),
+            command_history: HashMap::new(),
             monitor_invocations: HashMap::new(),
             command_invocations: HashMap::new(),
+            maintenance_until: None,
+            critical_monitors: HashSet::new(),
+            recent_errors: VecDeque::new(),
         }
     }
 
+    /// Collects tags from the latest data points (including multivalues) of all monitors.
+    pub fn get_all_tags(&self) -> HashSet<String> {
+        fn collect(data_point: &DataPoint, tags: &mut HashSet<String>) {
+            tags.extend(data_point.tags.iter().cloned());
+            for child in data_point.multivalue.iter() {
+                collect(child, tags);
+            }
+        }
+
+        let mut tags = HashSet::new();
+        for monitoring_data in self.monitor_data.values() {
+            if let Some(data_point) = monitoring_data.values.back() {
+                collect(data_point, &mut tags);
+            }
+        }
+        tags
+    }
+
+    pub fn get_summary(&self) -> frontend::HostSummary {
+        let mut summary = frontend::HostSummary::default();
+
+        let criticalities = self.monitor_data.values()
+            .filter(|monitor_data| !monitor_data.display_options.use_without_summary)
+            .filter_map(|monitor_data| monitor_data.values.back().map(|data_point| data_point.criticality));
+
+        for criticality in criticalities {
+            match criticality {
+                Criticality::Normal | Criticality::Info => summary.normal_count += 1,
+                Criticality::Warning => summary.warning_count += 1,
+                Criticality::Error => summary.error_count += 1,
+                Criticality::Critical => summary.critical_count += 1,
+                Criticality::NoData => summary.no_data_count += 1,
+                Criticality::Ignore | Criticality::NotAvailable => continue,
+            }
+
+            if criticality > summary.worst_criticality {
+                summary.worst_criticality = criticality;
+            }
+        }
+
+        summary
+    }
+
     fn update_status(&mut self) {
         // There should always be some monitoring data available at this point.
         let critical_monitor = self.monitor_data.iter()
@@ -465,19 +807,48 @@ impl HostState {
         let has_only_pending_monitors = self.monitor_data.values()
             .all(|data| data.values.iter().all(|datapoint| datapoint.criticality == Criticality::NoData));
 
-        if let Some((name, _)) = critical_monitor {
-            log::debug!("Host is now down since monitor \"{}\" is at critical level", name);
+        let warning_monitor = self.monitor_data.iter()
+            .find(|(_, data)| !data.is_critical &&
+                              data.values.back().map(|datapoint| datapoint.criticality >= Criticality::Warning &&
+                                                                 datapoint.criticality <= Criticality::Critical).unwrap_or(false));
+
+        if let Some(until) = self.maintenance_until {
+            if until <= Utc::now() {
+                log::info!("[{}] Maintenance mode expired", self.host.name);
+                self.maintenance_until = None;
+            }
+        }
+
+        let new_status = if self.maintenance_until.is_some() {
+            HostStatus::Maintenance
+        }
+        else if self.is_unreachable {
+            HostStatus::Unreachable
         }
-        self.status = if critical_monitor.is_some() {
+        else if critical_monitor.is_some() {
             HostStatus::Down
         }
         else if pending_critical_monitor.is_some() || has_only_pending_monitors {
             HostStatus::Pending
         }
+        else if warning_monitor.is_some() {
+            HostStatus::Warning
+        }
         else {
             HostStatus::Up
         };
 
+        if new_status != self.status {
+            match (new_status, critical_monitor, warning_monitor) {
+                (HostStatus::Down, Some((name, _)), _) =>
+                    log::debug!("[{}] Host is now down since monitor \"{}\" is at critical level", self.host.name, name),
+                (HostStatus::Warning, _, Some((name, _))) =>
+                    log::debug!("[{}] Host status is now warning since monitor \"{}\" is at warning level or worse", self.host.name, name),
+                _ => log::debug!("[{}] Host status changed from {} to {}", self.host.name, self.status, new_status),
+            }
+        }
+        self.status = new_status;
+
     }
 }
 
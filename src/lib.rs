@@ -10,6 +10,7 @@ pub mod module;
 pub mod configuration;
 mod host_manager;
 mod monitor_manager;
+mod refresh_scheduler;
 mod host;
 mod utils;
 mod enums;
@@ -18,9 +19,11 @@ mod connection_manager;
 mod command_handler;
 mod file_handler;
 mod metrics;
+mod host_log;
 
 pub use module::ModuleFactory;
 pub use configuration::Configuration;
+pub use host_log::HostLogWriter;
 use std::sync::Arc;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -51,6 +54,9 @@ pub fn run(
 
     let module_factory = Arc::<ModuleFactory>::new(ModuleFactory::new());
     let module_metadatas = module_factory.get_module_metadatas();
+    if let Err(error) = module_factory.validate_host_config(hosts_config) {
+        log::warn!("Configuration problem: {}", error);
+    }
     let mut frontend = frontend::qt::QmlFrontend::new(
         config_dir,
         main_config,
@@ -59,6 +65,19 @@ pub fn run(
         module_metadatas,
     );
 
+    utils::units::set_unit_system(main_config.preferences.unit_system);
+    file_handler::set_data_dir(main_config.preferences.data_dir.as_deref());
+    file_handler::set_cache_dir(main_config.preferences.cache_dir.as_deref());
+    host_log::configure(main_config.preferences.per_host_logs, hosts_config.hosts.keys().cloned().collect());
+    file_handler::set_download_dir(main_config.preferences.download_dir.as_deref());
+    if main_config.preferences.download_retention_days > 0 {
+        match file_handler::remove_stale_files(main_config.preferences.download_retention_days) {
+            Ok(0) => {},
+            Ok(count) => log::info!("Removed {} stale downloaded files", count),
+            Err(error) => log::error!("Failed to remove stale downloaded files: {}", error),
+        }
+    }
+
     let host_manager = Rc::new(RefCell::new(HostManager::new()));
     host_manager.borrow_mut().configure(&hosts_config);
 
@@ -73,9 +92,10 @@ pub fn run(
         None
     };
 
-    let mut monitor_manager = MonitorManager::new(host_manager.clone(), module_factory.clone());
+    let mut monitor_manager = MonitorManager::new(config_dir, host_manager.clone(), module_factory.clone());
     monitor_manager.configure(
         &hosts_config,
+        &main_config.preferences,
         connection_manager.new_request_sender(),
         host_manager.borrow().new_state_update_sender()
     );
@@ -91,8 +111,13 @@ pub fn run(
     // Start backend threads.
     host_manager.borrow_mut().start_receiving_updates();
     connection_manager.start_processing_requests();
+    if main_config.preferences.warm_up_connections {
+        let hosts = hosts_config.hosts.keys().map(|host_id| host_manager.borrow().get_host(host_id)).collect();
+        connection_manager.warm_up(hosts);
+    }
     command_handler.start_processing_responses();
     monitor_manager.start_processing_responses();
+    monitor_manager.start_refresh_scheduler(frontend.new_update_sender());
 
     // TODO: Needs a proper fix for a race.
     // Wait a small amount as a workaround so initial data points have time to get sent to host manager.
@@ -107,4 +132,27 @@ pub fn run(
         // TODO
         ExitReason::Quit
     }
-}
\ No newline at end of file
+}
+
+/// Tests connecting to the hosts one by one without starting the UI. See `ConnectionManager::test_connection`.
+/// Returns the results in the same order as `host_ids`.
+pub fn test_connections(main_config: &Configuration, hosts_config: &configuration::Hosts, host_ids: &[String]) -> Vec<module::command::CommandResult> {
+    // See `run`.
+    let _ = openssl::ssl::SslConnector::builder(openssl::ssl::SslMethod::tls()).unwrap();
+
+    // Known hosts file is located in the data directory.
+    file_handler::set_data_dir(main_config.preferences.data_dir.as_deref());
+
+    let module_factory = ModuleFactory::new();
+    host_ids.iter().map(|host_id| {
+        let host_config = match hosts_config.hosts.get(host_id) {
+            Some(host_config) => host_config,
+            None => return module::command::CommandResult::new_error(format!("Host '{}' doesn't exist", host_id)),
+        };
+
+        match Host::new(host_id, &host_config.address, &host_config.fqdn, &[host::HostSetting::UseSudo]) {
+            Ok(host) => ConnectionManager::test_connection(&module_factory, &host, &host_config.effective),
+            Err(error) => module::command::CommandResult::new_error(error),
+        }
+    }).collect()
+}
@@ -7,6 +7,8 @@
 #![allow(clippy::needless_return)]
 #![forbid(unsafe_code)]
 
+use std::collections::BTreeMap;
+
 use clap::Parser;
 use lightkeeper::*;
 
@@ -20,13 +22,34 @@ pub struct Args {
     pub command_module_info: bool,
     #[clap(long)]
     pub connector_module_info: bool,
+    /// Print all available modules, their versions and settings in YAML format.
+    #[clap(long)]
+    pub list_modules: bool,
+    /// Validate configuration and print the effective host configuration. Exit code is non-zero on errors.
+    #[clap(long)]
+    pub validate_config: bool,
+    /// Only print the configuration of matching hosts when validating, or only test connections to matching hosts.
+    /// Accepts a host name, a glob pattern (e.g. "web-*") or a group name prefixed with @ (e.g. "@prod").
+    #[clap(long, default_value = "")]
+    pub host: String,
+    /// Test SSH connections to hosts and exit. Exit code is non-zero if any connection fails.
+    #[clap(long)]
+    pub test_connection: bool,
+    /// Print the host inventory in the given format and exit. Supported formats: csv, ansible.
+    #[clap(long)]
+    pub export_hosts: Option<String>,
+    /// Add hosts from an SSH client config file (e.g. ~/.ssh/config) or a CSV file (.csv) to the host configuration.
+    #[clap(long)]
+    pub import_hosts: Option<String>,
 }
 
 fn main() {
     // Only english is currently supported.
     std::env::set_var("LANGUAGE", "en_US");
 
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .target(env_logger::Target::Pipe(Box::new(HostLogWriter::new())))
+        .init();
     let args = Args::parse();
 
     if args.monitoring_module_info {
@@ -45,6 +68,34 @@ fn main() {
         return;
     }
 
+    if args.list_modules {
+        let module_factory = ModuleFactory::new();
+        match serde_yaml::to_string(&module_factory.catalog()) {
+            Ok(output) => print!("{}", output),
+            Err(error) => {
+                eprintln!("Failed to print modules: {}", error);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if args.validate_config {
+        std::process::exit(validate_config(&args.config_dir, &args.host));
+    }
+
+    if args.test_connection {
+        std::process::exit(test_connection(&args.config_dir, &args.host));
+    }
+
+    if let Some(format) = &args.export_hosts {
+        std::process::exit(export_hosts(&args.config_dir, format));
+    }
+
+    if let Some(file_path) = &args.import_hosts {
+        std::process::exit(import_hosts(&args.config_dir, file_path));
+    }
+
     loop {
         log::info!("Lightkeeper starting...");
 
@@ -65,3 +116,168 @@ fn main() {
         };
     }
 }
+
+/// Returns the exit code.
+fn validate_config(config_dir: &String, host_filter: &String) -> i32 {
+    let (_, hosts_config, _) = match Configuration::read(config_dir) {
+        Ok(configuration) => configuration,
+        Err(error) => {
+            eprintln!("Invalid configuration: {}", error);
+            return 1;
+        }
+    };
+
+    let module_factory = ModuleFactory::new();
+    if let Err(error) = module_factory.validate_host_config(&hosts_config) {
+        eprintln!("Invalid configuration: {}", error);
+        return 1;
+    }
+
+    let selected_hosts = hosts_config.select_hosts(host_filter);
+    let effective_configs = hosts_config.hosts.iter()
+        .filter(|(host_id, _)| host_filter.is_empty() || selected_hosts.contains(host_id))
+        .map(|(host_id, host_config)| (host_id.clone(), host_config.effective.clone()))
+        .collect::<BTreeMap<_, _>>();
+
+    if !host_filter.is_empty() && effective_configs.is_empty() {
+        eprintln!("No hosts matching '{}' were found", host_filter);
+        return 1;
+    }
+
+    match serde_yaml::to_string(&effective_configs) {
+        Ok(output) => {
+            print!("{}", output);
+            0
+        },
+        Err(error) => {
+            eprintln!("Failed to print configuration: {}", error);
+            1
+        }
+    }
+}
+
+/// Returns the exit code.
+fn test_connection(config_dir: &String, host_filter: &String) -> i32 {
+    let (main_config, hosts_config, _) = match Configuration::read(config_dir) {
+        Ok(configuration) => configuration,
+        Err(error) => {
+            eprintln!("Invalid configuration: {}", error);
+            return 1;
+        }
+    };
+
+    let host_ids = if host_filter.is_empty() {
+        hosts_config.hosts.keys().cloned().collect::<Vec<_>>()
+    }
+    else {
+        hosts_config.select_hosts(host_filter)
+    };
+
+    if host_ids.is_empty() {
+        eprintln!("No hosts matching '{}' were found", host_filter);
+        return 1;
+    }
+
+    let results = lightkeeper::test_connections(&main_config, &hosts_config, &host_ids);
+    let mut exit_code = 0;
+
+    for (host_id, result) in host_ids.iter().zip(results) {
+        if result.error.is_empty() {
+            println!("{}: OK: {}", host_id, result.message);
+        }
+        else {
+            println!("{}: FAILED: {}", host_id, result.error);
+            exit_code = 1;
+        }
+    }
+
+    exit_code
+}
+
+/// Returns the exit code.
+fn export_hosts(config_dir: &String, format: &str) -> i32 {
+    let (_, hosts_config, _) = match Configuration::read(config_dir) {
+        Ok(configuration) => configuration,
+        Err(error) => {
+            eprintln!("Invalid configuration: {}", error);
+            return 1;
+        }
+    };
+
+    match format {
+        "csv" => print!("{}", hosts_config.to_csv()),
+        "ansible" => print!("{}", hosts_config.to_ansible_inventory()),
+        _ => {
+            eprintln!("Unsupported export format '{}'. Supported formats: csv, ansible", format);
+            return 1;
+        }
+    }
+    0
+}
+
+/// Returns the exit code.
+fn import_hosts(config_dir: &String, file_path: &String) -> i32 {
+    let (_, mut hosts_config, _) = match Configuration::read(config_dir) {
+        Ok(configuration) => configuration,
+        Err(error) => {
+            eprintln!("Invalid configuration: {}", error);
+            return 1;
+        }
+    };
+
+    let contents = match std::fs::read_to_string(file_path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            eprintln!("Failed to read {}: {}", file_path, error);
+            return 1;
+        }
+    };
+
+    let new_hosts = if file_path.ends_with(".csv") {
+        match configuration::Hosts::parse_csv(&contents) {
+            Ok(new_hosts) => new_hosts,
+            Err(error) => {
+                eprintln!("Invalid CSV file: {}", error);
+                return 1;
+            }
+        }
+    }
+    else {
+        configuration::Hosts::parse_ssh_config(&contents, &[String::from("defaults"), String::from("linux")])
+    };
+
+    let mut imported_count = 0;
+    for (host_id, host_settings) in new_hosts {
+        if hosts_config.hosts.contains_key(&host_id) && !confirm(&format!("Host '{}' already exists. Overwrite?", host_id)) {
+            println!("Skipping host '{}'", host_id);
+            continue;
+        }
+
+        hosts_config.hosts.insert(host_id, host_settings);
+        imported_count += 1;
+    }
+
+    if imported_count == 0 {
+        println!("No hosts were imported");
+        return 0;
+    }
+
+    match Configuration::write_hosts_config(config_dir, &hosts_config) {
+        Ok(()) => {
+            println!("Imported {} hosts", imported_count);
+            0
+        },
+        Err(error) => {
+            eprintln!("{}", error);
+            1
+        }
+    }
+}
+
+fn confirm(question: &str) -> bool {
+    print!("{} [y/N] ", question);
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).is_ok() && answer.trim().eq_ignore_ascii_case("y")
+}
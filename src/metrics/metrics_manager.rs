@@ -16,6 +16,7 @@ use crate::error::LkError;
 use crate::frontend::UIUpdate;
 use crate::metrics::lmserver::{self, RequestType, LMSRequest, LMSResponse};
 use crate::file_handler;
+use crate::utils::next_invocation_id;
 
 //
 // NOTE: MetricsManager handles connections to metrics server that stores host metrics for charts.
@@ -38,8 +39,6 @@ pub struct MetricsManager {
     request_thread: Option<thread::JoinHandle<()>>,
     request_sender: Option<mpsc::Sender<LMSRequest>>,
 
-    /// Every request gets an invocation ID. Valid numbers begin from 1.
-    invocation_id_counter: u64,
     // TODO: support remote database backends in the future?
     update_sender: mpsc::Sender<UIUpdate>,
 }
@@ -51,7 +50,6 @@ impl MetricsManager {
             log_thread: None,
             request_thread: None,
             request_sender: None,
-            invocation_id_counter: 1,
             update_sender: update_sender,
         }
     }
@@ -342,8 +340,7 @@ impl MetricsManager {
 
     fn send_request(&mut self, request_type: RequestType) -> Result<u64, LkError> {
         if let Some(request_sender) = self.request_sender.as_ref() {
-            let invocation_id = self.invocation_id_counter;
-            self.invocation_id_counter += 1;
+            let invocation_id = next_invocation_id();
 
             let current_unix_ms = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis() as u32;
 
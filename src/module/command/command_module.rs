@@ -30,6 +30,12 @@ pub trait CommandModule: BoxCloneableCommand + MetadataSupport + Module {
         None
     }
 
+    /// Whether the command changes anything on the target. Read-only commands should override this and return false.
+    /// Mutating commands are refused when read-only mode is enabled.
+    fn is_mutating(&self) -> bool {
+        true
+    }
+
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
             display_style: frontend::DisplayStyle::Text,
@@ -46,6 +52,12 @@ pub trait CommandModule: BoxCloneableCommand + MetadataSupport + Module {
         Err(LkError::not_implemented())
     }
 
+    /// Like `get_connector_message`, but returns a command that only previews the changes (e.g. `apt --dry-run`).
+    /// Commands that don't implement this are refused in dry-run mode.
+    fn get_connector_message_dry_run(&self, _host: Host, _parameters: Vec<String>) -> Result<String, LkError> {
+        Err(LkError::not_implemented())
+    }
+
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
         Ok(CommandResult::new_info(response.message.clone()))
     }
@@ -71,6 +83,9 @@ pub struct CommandResult {
     pub error: String,
     pub criticality: Criticality,
     pub time: DateTime<Utc>,
+    /// Exit code of the command. None if not available, e.g. for partial results or HTTP requests.
+    #[serde(default)]
+    pub return_code: Option<i32>,
 }
 
 impl CommandResult {
@@ -147,6 +162,11 @@ impl CommandResult {
         self.criticality = criticality;
         self.to_owned()
     }
+
+    pub fn with_return_code(&mut self, return_code: i32) -> Self {
+        self.return_code = Some(return_code);
+        self.to_owned()
+    }
 }
 
 impl Default for CommandResult {
@@ -159,6 +179,7 @@ impl Default for CommandResult {
             error: String::from(""),
             criticality: Criticality::Normal,
             time: Utc::now(),
+            return_code: None,
         }
     }
 }
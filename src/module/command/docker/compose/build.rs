@@ -11,6 +11,7 @@ use crate::frontend;
 use crate::host::*;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_cli_command;
 use crate::utils::ShellCommand;
 use lightkeeper_module::command_module;
 
@@ -54,8 +55,8 @@ impl CommandModule for Build {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let compose_file = parameters.first().unwrap();
-        let service_name = parameters.get(2).unwrap();
+        let compose_file = parameters.first().ok_or(LkError::missing_parameter(0))?;
+        let service_name = parameters.get(2).ok_or(LkError::missing_parameter(2))?;
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
@@ -66,7 +67,7 @@ impl CommandModule for Build {
            host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
            host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") {
 
-            command.arguments(vec!["docker", "compose", "-f", compose_file, "build", service_name]);
+            command.arguments(vec![get_cli_command(&host), "compose", "-f", compose_file, "build", service_name]);
         }
         else {
             return Err(LkError::unsupported_platform())
@@ -79,9 +80,9 @@ impl CommandModule for Build {
             let mut progress: u8 = 1;
             for line in response.message.lines().rev() {
                 if let Some(captures) = self.regex_step.captures(line) {
-                    let current = captures.get(1).unwrap().as_str().parse::<u8>().unwrap();
-                    let total = captures.get(2).unwrap().as_str().parse::<u8>().unwrap();
-                    progress = (current as f32 / total as f32 * 100.0) as u8;
+                    let current = captures[1].parse::<u32>().unwrap_or_default();
+                    let total = captures[2].parse::<u32>().unwrap_or_default().max(1);
+                    progress = (current as f32 / total as f32 * 100.0).min(100.0) as u8;
                     break;
                 }
             }
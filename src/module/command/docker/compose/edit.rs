@@ -46,7 +46,7 @@ impl CommandModule for Edit {
     }
 
     fn get_connector_message(&self, _host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let compose_file = parameters.first().unwrap().clone();
+        let compose_file = parameters.first().ok_or(LkError::missing_parameter(0))?.clone();
         Ok(compose_file)
     }
 }
\ No newline at end of file
@@ -9,20 +9,27 @@ use crate::frontend;
 use crate::host::*;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_cli_command;
 use crate::utils::ShellCommand;
+use crate::utils::string_validation;
 use lightkeeper_module::command_module;
 
 #[command_module(
     name="docker-compose-logs",
     version="0.0.1",
     description="Show docker-compose logs for services.",
+    settings={
+        page_size => "Number of log lines fetched per page if not given by the log view. Default: 1000.",
+    }
 )]
 pub struct Logs {
+    page_size: u32,
 }
 
 impl Module for Logs {
-    fn new(_settings: &HashMap<String, String>) -> Logs {
+    fn new(settings: &HashMap<String, String>) -> Logs {
         Logs {
+            page_size: settings.get("page_size").and_then(|value| value.parse().ok()).unwrap_or(1000),
         }
     }
 }
@@ -32,6 +39,10 @@ impl CommandModule for Logs {
         Some(ModuleSpecification::connector("ssh", "0.0.1"))
     }
 
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
             category: String::from("docker-compose"),
@@ -47,13 +58,19 @@ impl CommandModule for Logs {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let compose_file = parameters.get(0).unwrap();
-        // let project = parameters.get(1).unwrap();
-        let service_name = parameters.get(2).unwrap();
-        // let start_time = parameters.get(3).cloned().unwrap_or(String::from(""));
-        // let end_time = parameters.get(4).cloned().unwrap_or(String::from(""));
-        let page_number = parameters.get(5).unwrap_or(&String::from("")).parse::<i32>().unwrap_or(1);
-        let page_size = parameters.get(6).unwrap_or(&String::from("")).parse::<i32>().unwrap_or(1000);
+        let compose_file = parameters.get(0).ok_or(LkError::missing_parameter(0))?;
+        // let project = parameters.get(1).ok_or(LkError::missing_parameter(1))?;
+        let service_name = parameters.get(2).ok_or(LkError::missing_parameter(2))?;
+        let start_time = parameters.get(3).cloned().unwrap_or(String::from(""));
+        let end_time = parameters.get(4).cloned().unwrap_or(String::from(""));
+        let page_number = string_validation::parse_count(parameters.get(5), 1)?;
+        let page_size = string_validation::parse_count(parameters.get(6), self.page_size)?;
+
+        for time in [&start_time, &end_time] {
+            if !string_validation::is_time_expression(time) {
+                return Err(LkError::invalid_parameter("Invalid time", time));
+            }
+        }
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
@@ -64,10 +81,18 @@ impl CommandModule for Logs {
            host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
            host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") {
 
-            command.arguments(vec!["docker", "compose", "-f", compose_file, "logs", "--no-color", "-t"]);
+            command.arguments(vec![get_cli_command(&host), "compose", "-f", compose_file, "logs", "--no-color", "-t"]);
+
+            // Docker accepts e.g. "2025-01-01T12:00:00" or relative "1h" but not "now".
+            if !start_time.is_empty() {
+                command.arguments(vec!["--since", &start_time.replace(' ', "T")]);
+            }
+            if !end_time.is_empty() && end_time != "now" {
+                command.arguments(vec!["--until", &end_time.replace(' ', "T")]);
+            }
 
             if page_number > 0 {
-                let row_count = page_number * page_size;
+                let row_count = page_number.saturating_mul(page_size);
                 command.arguments(vec!["--tail", &row_count.to_string()]);
             }
 
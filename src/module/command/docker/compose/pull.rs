@@ -10,6 +10,7 @@ use crate::frontend;
 use crate::host::*;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_cli_command;
 use crate::utils::ShellCommand;
 use lightkeeper_module::command_module;
 
@@ -46,7 +47,7 @@ impl CommandModule for Pull {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let compose_file = parameters.first().unwrap();
+        let compose_file = parameters.first().ok_or(LkError::missing_parameter(0))?;
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
@@ -57,7 +58,31 @@ impl CommandModule for Pull {
            host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
            host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") {
 
-            command.arguments(vec!["docker", "compose", "-f", compose_file, "pull"]);
+            command.arguments(vec![get_cli_command(&host), "compose", "-f", compose_file, "pull"]);
+            if let Some(service_name) = parameters.get(2) {
+                command.argument(service_name);
+            }
+        }
+        else {
+            return Err(LkError::unsupported_platform())
+        }
+        Ok(command.to_string())
+    }
+
+    // Requires Docker Compose 2.20 or later.
+    fn get_connector_message_dry_run(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let compose_file = parameters.first().ok_or(LkError::missing_parameter(0))?;
+
+        let mut command = ShellCommand::new();
+        command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
+
+        if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "8") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::NixOS, "20") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") {
+
+            command.arguments(vec![get_cli_command(&host), "compose", "-f", compose_file, "--dry-run", "pull"]);
             if let Some(service_name) = parameters.get(2) {
                 command.argument(service_name);
             }
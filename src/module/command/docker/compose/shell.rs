@@ -10,6 +10,7 @@ use crate::host::*;
 use crate::module::connection::ResponseMessage;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_cli_command;
 use crate::utils::ShellCommand;
 use crate::enums;
 use lightkeeper_module::command_module;
@@ -48,8 +49,8 @@ impl CommandModule for Shell {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let compose_file = parameters.first().unwrap();
-        let service = parameters.get(2).unwrap();
+        let compose_file = parameters.first().ok_or(LkError::missing_parameter(0))?;
+        let service = parameters.get(2).ok_or(LkError::missing_parameter(2))?;
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
@@ -60,7 +61,7 @@ impl CommandModule for Shell {
            host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
            host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") {
 
-            command.arguments(vec!["docker", "compose", "-f", compose_file, "exec", service,
+            command.arguments(vec![get_cli_command(&host), "compose", "-f", compose_file, "exec", service,
                                    "/bin/sh", "-c", "test -e /bin/bash && /bin/bash || /bin/sh"]);
         }
         else {
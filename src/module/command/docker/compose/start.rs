@@ -10,6 +10,7 @@ use crate::frontend;
 use crate::host::*;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_cli_command;
 use crate::utils::ShellCommand;
 use lightkeeper_module::command_module;
 
@@ -47,7 +48,7 @@ impl CommandModule for Start {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let compose_file = parameters.first().unwrap();
+        let compose_file = parameters.first().ok_or(LkError::missing_parameter(0))?;
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
@@ -58,7 +59,7 @@ impl CommandModule for Start {
            host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
            host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") {
 
-            command.arguments(vec!["docker", "compose", "-f", compose_file, "start"]);
+            command.arguments(vec![get_cli_command(&host), "compose", "-f", compose_file, "start"]);
             if let Some(service_name) = parameters.get(2) {
                 command.argument(service_name);
             }
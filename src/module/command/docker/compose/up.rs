@@ -9,6 +9,7 @@ use crate::frontend;
 use crate::host::*;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_cli_command;
 use crate::utils::ShellCommand;
 use lightkeeper_module::command_module;
 
@@ -45,7 +46,7 @@ impl CommandModule for Up {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let compose_file = parameters.first().unwrap();
+        let compose_file = parameters.first().ok_or(LkError::missing_parameter(0))?;
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
 
@@ -55,7 +56,30 @@ impl CommandModule for Up {
            host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
            host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") {
 
-            command.arguments(vec!["docker", "compose", "-f", compose_file, "up", "-d"]);
+            command.arguments(vec![get_cli_command(&host), "compose", "-f", compose_file, "up", "-d"]);
+            if let Some(service_name) = parameters.get(2) {
+                command.argument(service_name);
+            }
+        }
+        else {
+            return Err(LkError::unsupported_platform())
+        }
+        Ok(command.to_string())
+    }
+
+    // Requires Docker Compose 2.20 or later.
+    fn get_connector_message_dry_run(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let compose_file = parameters.first().ok_or(LkError::missing_parameter(0))?;
+        let mut command = ShellCommand::new();
+        command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
+
+        if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "8") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::NixOS, "20") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") {
+
+            command.arguments(vec![get_cli_command(&host), "compose", "-f", compose_file, "--dry-run", "up", "-d"]);
             if let Some(service_name) = parameters.get(2) {
                 command.argument(service_name);
             }
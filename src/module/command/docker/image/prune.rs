@@ -12,6 +12,7 @@ use crate::host::*;
 use crate::module::connection::ResponseMessage;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_api_socket_path;
 use crate::utils::ShellCommand;
 use lightkeeper_module::command_module;
 
@@ -49,7 +50,7 @@ impl CommandModule for Prune {
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
 
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "-X", "POST", "http://localhost/images/prune"]);
+            command.arguments(vec!["curl", "-s", "--unix-socket", get_api_socket_path(&host), "-X", "POST", "http://localhost/images/prune"]);
             Ok(command.to_string())
         }
         else {
@@ -45,6 +45,10 @@ impl CommandModule for RemoteTags {
         Some(ModuleSpecification::connector("http-jwt", "0.0.1"))
     }
 
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
             category: String::from("docker-images"),
@@ -60,8 +64,8 @@ impl CommandModule for RemoteTags {
     }
 
     fn get_connector_messages(&self, _host: Host, parameters: Vec<String>) -> Result<Vec<String>, LkError> {
-        let _image_id = &parameters[0];
-        let image_repo_tag = &parameters[1];
+        let _image_id = parameters.get(0).ok_or(LkError::missing_parameter(0))?;
+        let image_repo_tag = parameters.get(1).ok_or(LkError::missing_parameter(1))?;
 
         if image_repo_tag.is_empty() {
             // Containers without a tag can not be used.
@@ -72,7 +76,7 @@ impl CommandModule for RemoteTags {
 
         if image.contains(".") {
             // Looks like a domain name. Use Docker Registry API v2.
-            let (domain, repository) = image.split_once("/").unwrap();
+            let (domain, repository) = image.split_once("/").ok_or(LkError::invalid_parameter("Invalid image name", image))?;
             Ok(vec![format!("https://{}/v2/{}/tags/list?n={}&last={}", domain, repository, self.page_size, 0)])
         }
         else {
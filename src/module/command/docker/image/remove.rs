@@ -14,6 +14,7 @@ use crate::host::*;
 use crate::module::connection::ResponseMessage;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_api_socket_path;
 use crate::utils::ShellCommand;
 use crate::utils::string_validation;
 use lightkeeper_module::command_module;
@@ -50,7 +51,7 @@ impl CommandModule for Remove {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let target_id = parameters.first().unwrap();
+        let target_id = parameters.first().ok_or(LkError::missing_parameter(0))?;
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
@@ -60,7 +61,7 @@ impl CommandModule for Remove {
         }
         else if host.platform.os == platform_info::OperatingSystem::Linux {
             let url = format!("http://localhost/images/{}", target_id);
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "-X", "DELETE", &url]);
+            command.arguments(vec!["curl", "-s", "--unix-socket", get_api_socket_path(&host), "-X", "DELETE", &url]);
             Ok(command.to_string())
         }
         else {
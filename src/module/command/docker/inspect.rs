@@ -10,6 +10,7 @@ use crate::host::*;
 use crate::module::connection::ResponseMessage;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_api_socket_path;
 use crate::utils::ShellCommand;
 use crate::utils::string_validation;
 use lightkeeper_module::command_module;
@@ -17,7 +18,7 @@ use lightkeeper_module::command_module;
 #[command_module(
     name="docker-inspect",
     version="0.0.1",
-    description="Inspects a Docker container.",
+    description="Inspects a Docker container. Shows the details as formatted JSON.",
 )]
 pub struct Inspect;
 
@@ -32,6 +33,10 @@ impl CommandModule for Inspect {
         Some(ModuleSpecification::connector("ssh", "0.0.1"))
     }
 
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
             category: String::from("docker-containers"),
@@ -45,7 +50,7 @@ impl CommandModule for Inspect {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let target_id = parameters.first().unwrap();
+        let target_id = parameters.first().ok_or(LkError::missing_parameter(0))?;
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
@@ -54,8 +59,8 @@ impl CommandModule for Inspect {
             Err(LkError::invalid_parameter("Invalid container ID", target_id))
         }
         else if host.platform.os == platform_info::OperatingSystem::Linux {
-            let url = format!("http://localhost/containers/{}/json?all=true", target_id);
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", &url]);
+            let url = format!("http://localhost/containers/{}/json", target_id);
+            command.arguments(vec!["curl", "-s", "--unix-socket", get_api_socket_path(&host), &url]);
             Ok(command.to_string())
         }
         else {
@@ -64,6 +69,25 @@ impl CommandModule for Inspect {
     }
 
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
-        Ok(CommandResult::new_info(response.message.clone()))
+        if response.return_code == 7 {
+            return Ok(CommandResult::new_error("Couldn't connect to Docker daemon."));
+        }
+
+        let mut details: serde_json::Value = serde_json::from_str(&response.message).map_err(|error| error.to_string())?;
+
+        if let Some(message) = details.get("message").and_then(|message| message.as_str()) {
+            // Docker API returns errors (e.g. unknown container) as {"message": "..."}.
+            return Ok(CommandResult::new_error(message));
+        }
+
+        // `docker inspect` wraps the result in an array.
+        if let serde_json::Value::Array(items) = &mut details {
+            if items.len() == 1 {
+                details = items.remove(0);
+            }
+        }
+
+        let pretty = serde_json::to_string_pretty(&details).map_err(|error| error.to_string())?;
+        Ok(CommandResult::new_info(pretty))
     }
 }
\ No newline at end of file
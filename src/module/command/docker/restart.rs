@@ -10,6 +10,7 @@ use crate::host::*;
 use crate::module::connection::ResponseMessage;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_api_socket_path;
 use crate::utils::ShellCommand;
 use crate::utils::string_validation;
 use lightkeeper_module::command_module;
@@ -48,13 +49,13 @@ impl CommandModule for Restart {
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
 
-        let target_id = parameters.first().unwrap();
+        let target_id = parameters.first().ok_or(LkError::missing_parameter(0))?;
         if !string_validation::is_alphanumeric(target_id) {
             Err(LkError::invalid_parameter("Invalid container ID", target_id))
         }
         else if host.platform.os == platform_info::OperatingSystem::Linux {
             let url = format!("http://localhost/containers/{}/restart", target_id);
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "-X", "POST", &url]);
+            command.arguments(vec!["curl", "-s", "--unix-socket", get_api_socket_path(&host), "-X", "POST", &url]);
             Ok(command.to_string())
         }
         else {
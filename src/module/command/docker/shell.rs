@@ -10,6 +10,7 @@ use crate::host::*;
 use crate::module::connection::ResponseMessage;
 use crate::module::*;
 use crate::module::command::*;
+use crate::module::monitoring::docker::get_cli_command;
 use crate::utils::ShellCommand;
 use crate::utils::string_validation;
 use lightkeeper_module::command_module;
@@ -46,7 +47,7 @@ impl CommandModule for Shell {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let target_id = parameters.first().unwrap();
+        let target_id = parameters.first().ok_or(LkError::missing_parameter(0))?;
 
         if !string_validation::is_alphanumeric(target_id) {
             return Err(LkError::invalid_parameter("Invalid container ID", target_id));
@@ -60,7 +61,7 @@ impl CommandModule for Shell {
            host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
            host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") {
 
-            command.arguments(vec!["docker", "exec", "-it", target_id, "/bin/sh"]);
+            command.arguments(vec![get_cli_command(&host), "exec", "-it", target_id, "/bin/sh"]);
         }
         else {
             return Err(LkError::unsupported_platform())
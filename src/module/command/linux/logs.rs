@@ -33,6 +33,10 @@ impl CommandModule for Logs {
         Some(ModuleSpecification::connector("ssh", "0.0.1"))
     }
 
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
             category: String::from("host"),
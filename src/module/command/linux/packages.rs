@@ -22,4 +22,7 @@ pub mod refresh;
 pub use refresh::Refresh;
 
 pub mod logs;
-pub use logs::Logs;
\ No newline at end of file
+pub use logs::Logs;
+
+pub mod search;
+pub use search::Search;
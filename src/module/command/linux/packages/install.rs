@@ -6,12 +6,17 @@
 use std::collections::HashMap;
 use crate::error::LkError;
 use crate::frontend;
-use crate::host::Host;
+use crate::host::*;
 use crate::module::connection::ResponseMessage;
 use crate::module::*;
 use crate::module::command::*;
+use crate::utils::string_validation;
+use crate::utils::ShellCommand;
 use lightkeeper_module::command_module;
 
+/// Debian, RedHat and Arch package names (optionally with architecture, version or target release suffix) are covered by this.
+pub const PACKAGE_NAME_REGEXP: &str = "^[a-zA-Z0-9][a-zA-Z0-9+._:/-]*$";
+
 #[command_module(
     name="linux-packages-install",
     version="0.0.1",
@@ -34,17 +39,69 @@ impl CommandModule for Install {
         frontend::DisplayOptions {
             category: String::from("packages"),
             display_style: frontend::DisplayStyle::Icon,
-            display_icon: String::from(""),
-            display_text: String::from("Install packages"),
+            display_icon: String::from("add"),
+            display_text: String::from("Install package"),
+            confirmation_text: String::from("Really install package?"),
+            user_parameters: vec![
+                frontend::UserInputField {
+                    label: String::from("Package name"),
+                    validator_regexp: String::from(PACKAGE_NAME_REGEXP),
+                    ..Default::default()
+                },
+            ],
+            tab_title: String::from("Install package"),
+            action: UIAction::FollowOutput,
             ..Default::default()
         }
     }
 
-    fn get_connector_message(&self, _host: Host, _parameters: Vec<String>) -> Result<String, LkError> {
-        todo!()
+    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let package = parameters.first().cloned().unwrap_or_default();
+
+        if !is_valid_package_name(&package) {
+            return Err(LkError::other_p("Invalid package name", &package));
+        }
+
+        let mut command = ShellCommand::new();
+        command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
+
+        if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "9") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") {
+            command.arguments(vec!["apt-get", "install", "-y", &package]);
+        }
+        else if host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::Fedora, "30") {
+            command.arguments(vec!["dnf", "install", "-y", &package]);
+        }
+        else if host.platform.os_flavor == platform_info::Flavor::ArchLinux {
+            command.arguments(vec!["pacman", "-S", "--noconfirm", &package]);
+        }
+        else {
+            return Err(LkError::unsupported_platform());
+        }
+        Ok(command.to_string())
     }
 
-    fn process_response(&self, _host: Host, _response: &ResponseMessage) -> Result<CommandResult, String> {
-        todo!()
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.is_partial {
+            Ok(CommandResult::new_partial(response.message.clone(), 10))
+        }
+        else {
+            if response.return_code == 0 {
+                Ok(CommandResult::new_hidden(response.message.clone()))
+            }
+            else {
+                Ok(CommandResult::new_hidden(response.message.clone())
+                                 .with_criticality(crate::enums::Criticality::Error))
+            }
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Package names are passed as arguments, so this mainly protects against names being interpreted as options.
+pub fn is_valid_package_name(package: &str) -> bool {
+    !package.is_empty() &&
+    string_validation::is_alphanumeric_with(package, "-_.+:/") &&
+    !string_validation::begins_with_dash(package)
+}
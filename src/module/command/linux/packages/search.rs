@@ -0,0 +1,97 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::frontend;
+use crate::host::*;
+use crate::module::connection::ResponseMessage;
+use crate::module::*;
+use crate::module::command::*;
+use crate::utils::ShellCommand;
+use lightkeeper_module::command_module;
+
+use super::install::{is_valid_package_name, PACKAGE_NAME_REGEXP};
+
+#[command_module(
+    name="linux-packages-search",
+    version="0.0.1",
+    description="Searches available system packages.",
+)]
+pub struct Search;
+
+impl Module for Search {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        Self { }
+    }
+}
+
+impl CommandModule for Search {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            category: String::from("packages"),
+            display_style: frontend::DisplayStyle::Icon,
+            display_icon: String::from("search"),
+            display_text: String::from("Search packages"),
+            user_parameters: vec![
+                frontend::UserInputField {
+                    label: String::from("Search term"),
+                    validator_regexp: String::from(PACKAGE_NAME_REGEXP),
+                    ..Default::default()
+                },
+            ],
+            tab_title: String::from("Package search"),
+            action: UIAction::TextDialog,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let search_term = parameters.first().cloned().unwrap_or_default();
+
+        if !is_valid_package_name(&search_term) {
+            return Err(LkError::other_p("Invalid search term", &search_term));
+        }
+
+        let mut command = ShellCommand::new();
+
+        if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "9") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") {
+            command.arguments(vec!["apt-cache", "search", &search_term]);
+        }
+        else if host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::Fedora, "30") {
+            command.arguments(vec!["dnf", "search", "-q", &search_term]);
+        }
+        else if host.platform.os_flavor == platform_info::Flavor::ArchLinux {
+            command.arguments(vec!["pacman", "-Ss", &search_term]);
+        }
+        else {
+            return Err(LkError::unsupported_platform());
+        }
+        Ok(command.to_string())
+    }
+
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        // pacman returns 1 when nothing is found.
+        if response.message.is_empty() {
+            return Ok(CommandResult::new_info(String::from("No matching packages found")));
+        }
+        if response.is_error() {
+            return Err(response.message.clone());
+        }
+        let monospaced_markdown = format!("```\n{}\n```", response.message);
+        Ok(CommandResult::new_hidden(monospaced_markdown))
+    }
+}
@@ -13,6 +13,8 @@ use crate::module::command::*;
 use crate::utils::ShellCommand;
 use lightkeeper_module::command_module;
 
+use super::install::{is_valid_package_name, PACKAGE_NAME_REGEXP};
+
 #[command_module(
     name="linux-packages-uninstall",
     version="0.0.1",
@@ -38,31 +40,48 @@ impl CommandModule for Uninstall {
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
             category: String::from("packages"),
-            parent_id: String::from("package"),
             display_style: frontend::DisplayStyle::Icon,
             display_icon: String::from("delete"),
             display_text: String::from("Uninstall package"),
+            confirmation_text: String::from("Really uninstall package?"),
+            user_parameters: vec![
+                frontend::UserInputField {
+                    label: String::from("Package name"),
+                    validator_regexp: String::from(PACKAGE_NAME_REGEXP),
+                    ..Default::default()
+                },
+            ],
+            tab_title: String::from("Uninstall package"),
+            action: UIAction::FollowOutput,
             ..Default::default()
         }
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let package = parameters.first().unwrap();
+        let package = parameters.first().cloned().unwrap_or_default();
+
+        if !is_valid_package_name(&package) {
+            return Err(LkError::other_p("Invalid package name", &package));
+        }
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
 
         if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "9") ||
            host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") {
-            command.arguments(vec!["apt-get", "remove", "-y", package]);
+            command.arguments(vec!["apt-get", "remove", "-y", &package]);
 
             if self.purge {
                 command.argument("--purge");
             }
         }
         else if host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") ||
-                host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") {
-            command.arguments(vec!["dnf", "remove", "-y", package]);
+                host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::Fedora, "30") {
+            command.arguments(vec!["dnf", "remove", "-y", &package]);
+        }
+        else if host.platform.os_flavor == platform_info::Flavor::ArchLinux {
+            command.arguments(vec!["pacman", "-R", "--noconfirm", &package]);
         }
         else {
             return Err(LkError::unsupported_platform());
@@ -71,11 +90,17 @@ impl CommandModule for Uninstall {
     }
 
     fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
-        if response.return_code >= 0 {
-            Ok(CommandResult::new_info(String::new()))
+        if response.is_partial {
+            Ok(CommandResult::new_partial(response.message.clone(), 10))
         }
         else {
-            Ok(CommandResult::new_error(response.message.clone()))
+            if response.return_code == 0 {
+                Ok(CommandResult::new_hidden(response.message.clone()))
+            }
+            else {
+                Ok(CommandResult::new_hidden(response.message.clone())
+                                 .with_criticality(crate::enums::Criticality::Error))
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -15,6 +15,8 @@ use crate::module::command::*;
 use crate::utils::ShellCommand;
 use lightkeeper_module::command_module;
 
+use super::install::is_valid_package_name;
+
 #[command_module(
     name="linux-packages-update",
     version="0.0.1",
@@ -50,18 +52,56 @@ impl CommandModule for Update {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let package = parameters.first().unwrap();
+        let package = Self::get_package(&parameters)?;
+
+        let mut command = ShellCommand::new();
+        command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
+
+        if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "9") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") {
+            command.arguments(vec!["apt", "--only-upgrade", "-y", "install", &package]); 
+        }
+        else if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "8") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "16") {
+            command.arguments(vec!["apt-get", "--only-upgrade", "-y", "install", &package]);
+        }
+        else if host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
+                host.platform.os_flavor == platform_info::Flavor::Fedora {
+            command.arguments(vec!["dnf", "upgrade", "-y", &package]);
+        }
+        else if host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "7") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "7") {
+            command.arguments(vec!["yum", "update", "-y", &package]);
+        }
+        else {
+            return Err(LkError::unsupported_platform());
+        }
+        Ok(command.to_string())
+    }
+
+    fn get_connector_message_dry_run(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let package = Self::get_package(&parameters)?;
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
 
         if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "9") ||
            host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") {
-            command.arguments(vec!["apt", "--only-upgrade", "-y", "install", package]); 
+            command.arguments(vec!["apt", "--dry-run", "--only-upgrade", "install", &package]);
+        }
+        else if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "8") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "16") {
+            command.arguments(vec!["apt-get", "--dry-run", "--only-upgrade", "install", &package]);
         }
         else if host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") ||
-                host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") {
-            command.arguments(vec!["dnf", "upgrade", "-y", package]);
+                host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") ||
+                host.platform.os_flavor == platform_info::Flavor::Fedora {
+            command.arguments(vec!["dnf", "upgrade", "--assumeno", &package]);
+        }
+        else if host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "7") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "7") {
+            command.arguments(vec!["yum", "update", "--assumeno", &package]);
         }
         else {
             return Err(LkError::unsupported_platform());
@@ -71,14 +111,10 @@ impl CommandModule for Update {
 
     fn process_response(&self, host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
         if response.is_partial {
-            let progress = if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "9") ||
-                              host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") {
+            let progress = if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "8") ||
+                              host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "16") {
                 self.parse_progress_for_apt(response)
             }
-            else if host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") ||
-                    host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") {
-                1
-            }
             else {
                 1
             };
@@ -97,8 +133,17 @@ impl CommandModule for Update {
     }
 }
 
-
 impl Update {
+    fn get_package(parameters: &[String]) -> Result<String, LkError> {
+        let package = parameters.first().cloned().unwrap_or_default();
+        if is_valid_package_name(&package) {
+            Ok(package)
+        }
+        else {
+            Err(LkError::other_p("Invalid package name", &package))
+        }
+    }
+
     // It's not critical if apt output format changes.
     // It will only make the progress reporting less granular.
     fn parse_progress_for_apt(&self, response: &ResponseMessage) -> u8 {
@@ -106,8 +151,8 @@ impl Update {
 
         for line in response.message.lines() {
             if let Some(captures) = self.regex_install_counts.captures(line) {
-                let upgraded = captures.get(1).unwrap().as_str().parse::<u32>().unwrap();
-                let new = captures.get(2).unwrap().as_str().parse::<u32>().unwrap();
+                let upgraded = captures[1].parse::<u32>().unwrap_or_default();
+                let new = captures[2].parse::<u32>().unwrap_or_default();
                 total_to_install = upgraded + new;
                 break;
             }
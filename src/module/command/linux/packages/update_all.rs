@@ -66,6 +66,24 @@ impl CommandModule for UpdateAll {
         Ok(command.to_string())
     }
 
+    fn get_connector_message_dry_run(&self, host: Host, _parameters: Vec<String>) -> Result<String, LkError> {
+        let mut command = ShellCommand::new();
+        command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
+
+        if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "9") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") {
+            command.arguments(vec!["apt", "upgrade", "--dry-run"]);
+        }
+        else if host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") ||
+                host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") {
+            command.arguments(vec!["dnf", "update", "--assumeno"]);
+        }
+        else {
+            return Err(LkError::unsupported_platform());
+        }
+        Ok(command.to_string())
+    }
+
     fn process_response(&self, host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
         if response.is_partial {
             let progress = if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "9") ||
@@ -102,8 +120,8 @@ impl UpdateAll {
 
         for line in response.message.lines() {
             if let Some(captures) = self.regex_install_counts.captures(line) {
-                let upgraded = captures.get(1).unwrap().as_str().parse::<u32>().unwrap();
-                let new = captures.get(2).unwrap().as_str().parse::<u32>().unwrap();
+                let upgraded = captures[1].parse::<u32>().unwrap_or_default();
+                let new = captures[2].parse::<u32>().unwrap_or_default();
                 total_to_install = upgraded + new;
                 break;
             }
@@ -32,6 +32,10 @@ impl CommandModule for SocketListen {
         Some(ModuleSpecification::connector("ssh", "0.0.1"))
     }
 
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
             category: String::from("network"),
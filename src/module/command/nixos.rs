@@ -19,4 +19,7 @@ pub mod channel_update;
 pub use channel_update::ChannelUpdate;
 
 pub mod rebuild_rollback;
-pub use rebuild_rollback::RebuildRollback;
\ No newline at end of file
+pub use rebuild_rollback::RebuildRollback;
+
+pub mod switch_generation;
+pub use switch_generation::SwitchGeneration;
\ No newline at end of file
@@ -18,7 +18,7 @@ use lightkeeper_module::command_module;
     version="0.0.1",
     description="Collects garbage from the Nix store.",
     settings={
-        delete_older_than => "Delete generations of profiles older than the specified number of days. Default: 7.",
+        delete_older_than => "Delete generations of profiles older than the specified number of days. 0 deletes all old generations. Default: 7.",
     }
 )]
 pub struct CollectGarbage {
@@ -45,6 +45,7 @@ impl CommandModule for CollectGarbage {
             display_icon: String::from("clear"),
             display_text: String::from("Delete old generations and collect garbage"),
             tab_title: String::from("nix-collect-garbage"),
+            confirmation_text: String::from("Really delete old generations? It won't be possible to roll back to them."),
             action: UIAction::FollowOutput,
             ..Default::default()
         }
@@ -55,7 +56,12 @@ impl CommandModule for CollectGarbage {
         command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
 
         if host.platform.is_same_or_greater(platform_info::Flavor::NixOS, "20") {
-            command.arguments(vec!["nix-collect-garbage", "--delete-older-than", format!("{}d", self.delete_older_than).as_str()]); 
+            if self.delete_older_than == 0 {
+                command.arguments(vec!["nix-collect-garbage", "-d"]);
+            }
+            else {
+                command.arguments(vec!["nix-collect-garbage", "--delete-older-than", format!("{}d", self.delete_older_than).as_str()]); 
+            }
         }
         else {
             return Err(LkError::unsupported_platform());
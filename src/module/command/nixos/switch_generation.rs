@@ -0,0 +1,89 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::frontend;
+use crate::host::*;
+use crate::module::connection::ResponseMessage;
+use crate::module::*;
+use crate::module::command::*;
+use crate::utils::ShellCommand;
+use lightkeeper_module::command_module;
+
+/// Generation number is passed as a positional parameter so it's never interpreted by the shell.
+const SCRIPT: &str = r#"nix-env --profile /nix/var/nix/profiles/system --switch-generation "$1" && /nix/var/nix/profiles/system/bin/switch-to-configuration switch"#;
+
+#[command_module(
+    name="nixos-switch-generation",
+    version="0.0.1",
+    description="Switches to a specific NixOS generation and activates it."
+)]
+pub struct SwitchGeneration {
+}
+
+impl Module for SwitchGeneration {
+    fn new(_settings: &HashMap<String, String>) -> SwitchGeneration {
+        SwitchGeneration {
+        }
+    }
+}
+
+impl CommandModule for SwitchGeneration {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            category: String::from("nixos"),
+            parent_id: String::from("nixos-rebuild-generations"),
+            display_style: frontend::DisplayStyle::Icon,
+            display_icon: String::from("start"),
+            display_text: String::from("Switch to this generation"),
+            depends_on_no_tags: vec![String::from("Current")],
+            confirmation_text: String::from("Really switch to this generation?"),
+            tab_title: String::from("Switch generation"),
+            action: UIAction::FollowOutput,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
+        let generation = parameters.first().cloned().unwrap_or_default();
+
+        let mut command = ShellCommand::new();
+        command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
+
+        // Only plain generation numbers are accepted.
+        if generation.is_empty() || !generation.chars().all(|char| char.is_ascii_digit()) {
+            return Err(LkError::other_p("Invalid generation", &generation));
+        }
+
+        if host.platform.is_same_or_greater(platform_info::Flavor::NixOS, "23.11") {
+            command.arguments(vec!["sh", "-c", SCRIPT, "sh", &generation]);
+        }
+        else {
+            return Err(LkError::unsupported_platform());
+        }
+        Ok(command.to_string())
+    }
+
+    fn process_response(&self, _host: Host, response: &ResponseMessage) -> Result<CommandResult, String> {
+        if response.is_partial {
+            let progress = if response.message.contains("activating the configuration") { 50 } else { 10 };
+            Ok(CommandResult::new_partial(response.message.clone(), progress))
+        }
+        else {
+            if response.return_code == 0 {
+                Ok(CommandResult::new_hidden(response.message.clone()))
+            }
+            else {
+                Ok(CommandResult::new_hidden(response.message.clone())
+                                 .with_criticality(crate::enums::Criticality::Error))
+            }
+        }
+    }
+}
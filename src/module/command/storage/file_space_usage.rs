@@ -42,6 +42,10 @@ impl CommandModule for FileSpaceUsage {
         Some(ModuleSpecification::connector("ssh", "0.0.1"))
     }
 
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
             category: String::from("storage"),
@@ -56,7 +60,7 @@ impl CommandModule for FileSpaceUsage {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let mountpoint = &parameters[0];
+        let mountpoint = parameters.get(0).ok_or(LkError::missing_parameter(0))?;
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
@@ -49,7 +49,7 @@ impl CommandModule for LVRefresh {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let lv_path = parameters.get(0).unwrap();
+        let lv_path = parameters.get(0).ok_or(LkError::missing_parameter(0))?;
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
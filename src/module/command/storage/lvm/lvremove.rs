@@ -47,10 +47,10 @@ impl CommandModule for LVRemove {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let lv_path = parameters.get(0).unwrap();
-        let _vg_name = parameters.get(1).unwrap();
-        let _lv_name = parameters.get(2).unwrap();
-        let _lv_size = parameters.get(3).unwrap();
+        let lv_path = parameters.get(0).ok_or(LkError::missing_parameter(0))?;
+        let _vg_name = parameters.get(1).ok_or(LkError::missing_parameter(1))?;
+        let _lv_name = parameters.get(2).ok_or(LkError::missing_parameter(2))?;
+        let _lv_size = parameters.get(3).ok_or(LkError::missing_parameter(3))?;
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
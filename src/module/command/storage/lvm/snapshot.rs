@@ -60,11 +60,11 @@ impl CommandModule for Snapshot {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let lv_path = parameters.get(0).unwrap();
-        let _vg_name = parameters.get(1).unwrap();
-        let lv_name = parameters.get(2).unwrap();
-        let _lv_size = parameters.get(3).unwrap();
-        let new_size = crate::utils::remove_whitespace(parameters.get(4).unwrap());
+        let lv_path = parameters.get(0).ok_or(LkError::missing_parameter(0))?;
+        let _vg_name = parameters.get(1).ok_or(LkError::missing_parameter(1))?;
+        let lv_name = parameters.get(2).ok_or(LkError::missing_parameter(2))?;
+        let _lv_size = parameters.get(3).ok_or(LkError::missing_parameter(3))?;
+        let new_size = crate::utils::remove_whitespace(parameters.get(4).ok_or(LkError::missing_parameter(4))?);
 
         let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S").to_string();
         let snapshot_suffix_with_timestamp = self.snapshot_suffix.replace("$TIME", &timestamp);
@@ -4,6 +4,7 @@
  */
 
 use std::collections::HashMap;
+use regex::Regex;
 use crate::error::LkError;
 use crate::frontend;
 use crate::host::*;
@@ -18,12 +19,19 @@ use lightkeeper_module::command_module;
     name="systemd-service-logs",
     version="0.0.1",
     description="Shows journald logs of a systemd service.",
+    settings={
+        page_size => "Number of log lines fetched per page if not given by the log view. Default: 1000.",
+    }
 )]
-pub struct Logs;
+pub struct Logs {
+    page_size: u32,
+}
 
 impl Module for Logs {
-    fn new(_settings: &HashMap<String, String>) -> Self {
-        Logs { }
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Logs {
+            page_size: settings.get("page_size").and_then(|value| value.parse().ok()).unwrap_or(1000),
+        }
     }
 }
 
@@ -32,6 +40,10 @@ impl CommandModule for Logs {
         Some(ModuleSpecification::connector("ssh", "0.0.1"))
     }
 
+    fn is_mutating(&self) -> bool {
+        false
+    }
+
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
             category: String::from("systemd"),
@@ -46,11 +58,12 @@ impl CommandModule for Logs {
     }
 
     fn get_connector_message(&self, host: Host, parameters: Vec<String>) -> Result<String, LkError> {
-        let service = parameters.get(0).unwrap();
+        let service = parameters.get(0).ok_or(LkError::missing_parameter(0))?;
         let start_time = parameters.get(1).cloned().unwrap_or(String::from(""));
         let end_time = parameters.get(2).cloned().unwrap_or(String::from(""));
-        let page_number = parameters.get(3).unwrap_or(&String::from("")).parse::<i32>().unwrap_or(1);
-        let page_size = parameters.get(4).unwrap_or(&String::from("")).parse::<i32>().unwrap_or(1000);
+        let page_number = string_validation::parse_count(parameters.get(3), 1)?;
+        let page_size = string_validation::parse_count(parameters.get(4), self.page_size)?;
+        let grep = parameters.get(5).cloned().unwrap_or(String::from(""));
 
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
@@ -61,6 +74,17 @@ impl CommandModule for Logs {
             return Err(LkError::other_p("Invalid unit name", service));
         }
 
+        for time in [&start_time, &end_time] {
+            if !string_validation::is_time_expression(time) {
+                return Err(LkError::invalid_parameter("Invalid time", time));
+            }
+        }
+
+        // journalctl interprets the pattern as PCRE2, but this catches most mistakes before running anything.
+        if !grep.is_empty() && (Regex::new(&grep).is_err() || grep.contains(['\n', '\0'])) {
+            return Err(LkError::invalid_parameter("Invalid grep pattern", &grep));
+        }
+
         if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "8") ||
            host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") ||
            host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "7") ||
@@ -75,9 +99,13 @@ impl CommandModule for Logs {
             if !end_time.is_empty() {
                 command.arguments(vec!["--until", &end_time]);
             }
+            if !grep.is_empty() {
+                // Passed as a single quoted argument so the pattern can't be interpreted as an option or by the shell.
+                command.argument(&format!("--grep={}", grep));
+            }
 
             if page_number > 0 {
-                let row_count = page_number * page_size;
+                let row_count = page_number.saturating_mul(page_size);
                 command.arguments(vec!["-n", &row_count.to_string()]);
             }
 
@@ -96,4 +124,43 @@ impl CommandModule for Logs {
             Ok(CommandResult::new_hidden(response.message.clone()))
         }
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::utils::{shell_quote, VersionNumber};
+    use super::*;
+
+    fn debian_host() -> Host {
+        let mut host = Host::new("test", "127.0.0.1", "", &[]).unwrap();
+        host.platform.os = platform_info::OperatingSystem::Linux;
+        host.platform.os_flavor = platform_info::Flavor::Debian;
+        host.platform.os_version = VersionNumber::from_str("12").unwrap();
+        host
+    }
+
+    fn parameters(grep: &str) -> Vec<String> {
+        vec!["nginx.service", "", "", "", "", grep].into_iter().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_malicious_grep_is_quoted() {
+        let logs = Logs::new(&HashMap::new());
+        // Valid as a regex, so it has to be made harmless by quoting.
+        let grep = "x'; rm -rf / ; echo \"$(reboot)\" `id` $HOME";
+
+        let message = logs.get_connector_message(debian_host(), parameters(grep)).unwrap();
+        assert!(message.contains(&format!(" {} ", shell_quote(&format!("--grep={}", grep)))));
+    }
+
+    #[test]
+    fn test_invalid_grep_is_rejected() {
+        let logs = Logs::new(&HashMap::new());
+
+        for grep in ["$(reboot", "x\n; reboot", "x\0"] {
+            assert!(logs.get_connector_message(debian_host(), parameters(grep)).is_err(), "{:?} was accepted", grep);
+        }
+    }
+}
@@ -13,6 +13,7 @@ pub use request_response::RequestResponse;
 
 pub mod ssh;
 pub use ssh::Ssh2;
+pub use ssh::SshConnectorSettings;
 
 pub mod http;
 pub use http::Http;
@@ -24,4 +25,8 @@ pub mod local_command;
 pub use local_command::LocalCommand;
 
 pub mod tcp;
-pub use tcp::Tcp;
\ No newline at end of file
+pub use tcp::Tcp;
+
+pub mod docker_api;
+pub use docker_api::DockerApi;
+pub use docker_api::DockerApiSettings;
@@ -4,6 +4,7 @@
  */
 
 use std::collections::HashMap;
+use std::io;
 
 use crate::error::LkError;
 use crate::file_handler::FileMetadata;
@@ -17,6 +18,11 @@ pub trait ConnectionModule: MetadataSupport + Module {
     /// Stores target address. Should be called before anything else since connects/reconnects can happen at any point.
     fn set_target(&self, _address: &str) {}
 
+    /// Connects in advance so that the first request doesn't have to. Otherwise, connecting happens when needed.
+    fn open_connection(&self) -> Result<(), LkError> {
+        Ok(())
+    }
+
     /// Sends a request / message and waits for response. Response can be complete or partial.
     fn send_message(&self, message: &str) -> Result<ResponseMessage, LkError>;
 
@@ -29,7 +35,8 @@ pub trait ConnectionModule: MetadataSupport + Module {
         Err(LkError::not_implemented())
     }
 
-    fn download_file(&self, _source: &str) -> Result<(FileMetadata, Vec<u8>), LkError> {
+    /// Streams the remote file to `destination`. Fails if the file is larger than `max_size` bytes.
+    fn download_file(&self, _source: &str, _destination: &mut dyn io::Write, _max_size: u64) -> Result<FileMetadata, LkError> {
         Err(LkError::not_implemented())
     }
 
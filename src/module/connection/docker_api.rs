@@ -0,0 +1,220 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use ureq;
+
+use lightkeeper_module::connection_module;
+use crate::error::*;
+use crate::module::*;
+use crate::module::connection::*;
+
+#[connection_module(
+    name="docker",
+    version="0.0.1",
+    description="Connects directly to the Docker Engine API over TCP. Supports TLS with client certificates.",
+    settings={
+        port => "Docker API port. Default: 2376.",
+        use_tls => "Connect with TLS. Default: true.",
+        ca_certificate_path => "Path to the CA certificate used to verify the daemon. Expects PEM format. Default: (empty).",
+        client_certificate_path => "Path to the client certificate. Expects PEM format. Default: (empty).",
+        client_key_path => "Path to the client private key. Expects PEM format. Default: (empty).",
+        timeout => "Request timeout in seconds. Default: 15.",
+    }
+)]
+pub struct DockerApi {
+    address: Mutex<String>,
+    port: u16,
+    use_tls: bool,
+    /// If TLS can't be configured, the error is returned for every request instead of connecting without it.
+    agent: Result<ureq::Agent, LkError>,
+}
+
+/// Typed and validated settings of the Docker API connector.
+#[derive(Clone)]
+pub struct DockerApiSettings {
+    pub port: u16,
+    pub use_tls: bool,
+    pub ca_certificate_path: Option<String>,
+    pub client_certificate_path: Option<String>,
+    pub client_key_path: Option<String>,
+    /// In seconds.
+    pub timeout: u64,
+}
+
+impl Default for DockerApiSettings {
+    fn default() -> Self {
+        DockerApiSettings {
+            port: 2376,
+            use_tls: true,
+            ca_certificate_path: None,
+            client_certificate_path: None,
+            client_key_path: None,
+            timeout: 15,
+        }
+    }
+}
+
+impl DockerApiSettings {
+    /// Returns `ErrorKind::InvalidConfig` if a setting has an invalid value.
+    pub fn from_settings(settings: &HashMap<String, String>) -> Result<Self, LkError> {
+        let defaults = Self::default();
+
+        let client_certificate_path = settings.get("client_certificate_path").cloned();
+        let client_key_path = settings.get("client_key_path").cloned();
+        if client_certificate_path.is_some() != client_key_path.is_some() {
+            return Err(LkError::new(ErrorKind::InvalidConfig,
+                "Docker API settings \"client_certificate_path\" and \"client_key_path\" must be set together"));
+        }
+
+        Ok(DockerApiSettings {
+            port: Self::parse_setting(settings, "port", defaults.port)?,
+            use_tls: Self::parse_setting(settings, "use_tls", defaults.use_tls)?,
+            ca_certificate_path: settings.get("ca_certificate_path").cloned(),
+            client_certificate_path: client_certificate_path,
+            client_key_path: client_key_path,
+            timeout: Self::parse_setting(settings, "timeout", defaults.timeout)?,
+        })
+    }
+
+    fn parse_setting<T: FromStr>(settings: &HashMap<String, String>, key: &str, default: T) -> Result<T, LkError> {
+        match settings.get(key) {
+            Some(value) => value.parse::<T>().map_err(|_| {
+                LkError::new(ErrorKind::InvalidConfig, format!("Invalid value for Docker API setting \"{}\": {}", key, value))
+            }),
+            None => Ok(default),
+        }
+    }
+
+    fn build_agent(&self) -> Result<ureq::Agent, LkError> {
+        let mut agent_builder = ureq::AgentBuilder::new()
+            .timeout(std::time::Duration::from_secs(self.timeout));
+
+        if self.use_tls {
+            let tls_config = build_tls_config(self)
+                .map_err(|error| LkError::new(ErrorKind::InvalidConfig, format!("Failed to configure TLS for Docker API: {}", error)))?;
+            agent_builder = agent_builder.tls_config(Arc::new(tls_config));
+        }
+
+        Ok(agent_builder.build())
+    }
+}
+
+impl Module for DockerApi {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        let (settings, agent) = match DockerApiSettings::from_settings(settings) {
+            Ok(settings) => {
+                let agent = settings.build_agent();
+                (settings, agent)
+            },
+            // Settings are already validated when reading configuration, so this shouldn't happen.
+            Err(error) => (DockerApiSettings::default(), Err(error)),
+        };
+
+        if let Err(error) = &agent {
+            log::error!("{}", error);
+        }
+
+        DockerApi {
+            address: Mutex::new(String::new()),
+            port: settings.port,
+            use_tls: settings.use_tls,
+            agent: agent,
+        }
+    }
+}
+
+impl ConnectionModule for DockerApi {
+    fn set_target(&self, address: &str) {
+        let mut mutex_address = self.address.lock().unwrap();
+        *mutex_address = address.to_string();
+    }
+
+    /// Message is the API path (e.g. "/containers/json?all=true"), optionally followed by a newline and a POST body.
+    /// Non-success HTTP statuses are returned as responses with the status as the return code.
+    fn send_message(&self, message: &str) -> Result<ResponseMessage, LkError> {
+        if message.is_empty() {
+            return Ok(ResponseMessage::empty());
+        }
+
+        let (path, data) = message.split_once('\n').unwrap_or((message, ""));
+        if !path.starts_with('/') {
+            return Err(LkError::invalid_parameter("Invalid Docker API path", path));
+        }
+
+        let agent = self.agent.as_ref().map_err(|error| error.clone())?;
+        let address = self.address.lock().unwrap().clone();
+        let scheme = if self.use_tls { "https" } else { "http" };
+        let url = format!("{}://{}:{}{}", scheme, address, self.port, path);
+
+        let response = if data.is_empty() {
+            agent.get(&url).call()
+        }
+        else {
+            agent.post(&url).set("Content-Type", "application/json").send_string(data)
+        };
+
+        match response {
+            Ok(response) => Ok(ResponseMessage::new_success(response.into_string()?)),
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                Ok(ResponseMessage::new(body, status as i32))
+            },
+            Err(error) => Err(LkError::new(ErrorKind::ConnectionFailed, error)),
+        }
+    }
+}
+
+fn build_tls_config(settings: &DockerApiSettings) -> Result<rustls::ClientConfig, LkError> {
+    let mut store = rustls::RootCertStore::empty();
+
+    if let Some(path) = &settings.ca_certificate_path {
+        for cert in load_certs(Path::new(path))? {
+            store.add(cert).map_err(|error| LkError::other_p("Failed to add CA certificate", error))?;
+        }
+    }
+    else {
+        let cert_result = rustls_native_certs::load_native_certs();
+        for error in cert_result.errors {
+            log::error!("Failed to load native CA certificate: {}", error);
+        }
+        for cert in cert_result.certs {
+            if let Err(_) = store.add(cert) {
+                log::error!("Failed to add certificate to CA certificate store.");
+            }
+        }
+    }
+
+    let builder = rustls::ClientConfig::builder().with_root_certificates(store);
+
+    match (&settings.client_certificate_path, &settings.client_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            let certs = load_certs(Path::new(cert_path))?;
+            let key = load_private_key(Path::new(key_path))?;
+            builder.with_client_auth_cert(certs, key)
+                   .map_err(|error| LkError::other_p("Invalid client certificate", error))
+        },
+        (None, None) => Ok(builder.with_no_client_auth()),
+        _ => Err(LkError::other("Both client_certificate_path and client_key_path must be set.")),
+    }
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, LkError> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>()
+        .map_err(|error| LkError::other_p(&format!("Failed to load certificates from {}", path.display()), error))
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>, LkError> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    rustls_pemfile::private_key(&mut reader)?
+        .ok_or_else(|| LkError::other_p("No private key found in", path.display()))
+}
@@ -53,6 +53,17 @@ impl RequestResponse {
         }
     }
 
+    /// Returns the originating message of an error in a multi-message request. See `LkError::request_index`.
+    pub fn get_request_message(&self, request_index: usize) -> Option<&String> {
+        match &self.request_type {
+            RequestType::Command { commands } |
+            RequestType::CommandDryRun { commands } |
+            RequestType::CommandFollowOutput { commands } |
+            RequestType::MonitorCommand { commands, .. } => commands.get(request_index),
+            _ => None,
+        }
+    }
+
     pub fn stop() -> RequestResponse {
         RequestResponse {
             stop: true,
@@ -4,6 +4,7 @@
  */
 
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::MutexGuard;
 use std::time::Duration;
 use std::sync::Mutex;
@@ -14,6 +15,10 @@ use std::{
     path::Path,
     io::Read,
     io::Write,
+    os::fd::OwnedFd,
+    os::unix::net::UnixStream,
+    process::Child,
+    process::Stdio,
 };
 
 use base64::Engine;
@@ -22,13 +27,24 @@ use chrono::Utc;
 use ssh2;
 use crate::{error::*, file_handler};
 use crate::file_handler::FileMetadata;
-use crate::utils::{sha256, strip_newline};
+use crate::utils::{sha256, strip_newline, shell_quote, Secret};
 use lightkeeper_module::connection_module;
 use crate::module::*;
 use crate::module::connection::*;
 
 static MODULE_NAME: &str = "ssh";
 const SESSION_WAIT_SLEEP: u64 = 200;
+const DOWNLOAD_CHUNK_SIZE: usize = 64 * 1024;
+/// Beginning of messages built with `ShellCommand` that has `use_sudo` set.
+const SUDO_PREFIX: &str = "'sudo' ";
+/// Messages from sudo when it can't proceed without a (correct) password.
+const SUDO_PASSWORD_ERRORS: [&str; 5] = [
+    "sudo: a password is required",
+    "sudo: a terminal is required",
+    "sudo: no tty present",
+    "sudo: no password was provided",
+    "incorrect password attempt",
+];
 
 
 #[connection_module(
@@ -45,58 +61,225 @@ const SESSION_WAIT_SLEEP: u64 = 200;
       agent_key_identifier => "Identifier for selecting key from ssh-agent. This is the comment part of the \
                                key (e.g. user@desktop). Default: empty (all keys are tried).",
       verify_host_key => "Whether to verify the host key using a known_hosts-file. Default: true.",
-      custom_known_hosts_path => "Path to a custom known_hosts file. Default: (inside configuration directory).",
+      custom_known_hosts_path => "Path to a custom known_hosts file. Default: (inside data directory).",
       parallel_sessions => "Number of parallel login sessions. Improves performance. Default: 2.",
+      sudo_password => "Password for sudo if it's not configured as passwordless. Passed to sudo through stdin, only when passwordless sudo doesn't work. Default: empty (not used).",
+      sudo_command => "Command used instead of sudo, with optional arguments (e.g. `doas` or `sudo -n`). \
+                       sudo_password only works with sudo without -n. Default: sudo.",
+      password_secret_ref => "Reads password from OS keyring instead. Used with `secret-tool lookup lightkeeper <value>`. Default: empty.",
+      password_secret_command => "Reads password from the output of a command instead. Default: empty.",
+      private_key_passphrase_secret_ref => "Like password_secret_ref, but for private_key_passphrase. Default: empty.",
+      private_key_passphrase_secret_command => "Like password_secret_command, but for private_key_passphrase. Default: empty.",
+      sudo_password_secret_ref => "Like password_secret_ref, but for sudo_password. Default: empty.",
+      sudo_password_secret_command => "Like password_secret_command, but for sudo_password. Default: empty.",
+      proxy_command => "Command to connect through, like ProxyCommand of OpenSSH. Its stdin and stdout are used as the connection. \
+                        %h and %p are replaced with the target address (shell-quoted) and port. Jump hosts can be used this way too \
+                        (e.g. `ssh -W %h:%p jumphost`). Can't be used together with socks_proxy. Default: empty.",
+      socks_proxy => "Address and port of a SOCKS5 proxy to connect through (e.g. proxy.example.com:1080). \
+                      Only proxies without authentication are supported. Default: empty.",
     }
 )]
 pub struct Ssh2 {
     address: Mutex<String>,
     port: Mutex<u16>,
     username: String,
-    password: Option<String>,
+    password: Secret,
     private_key_path: Option<String>,
-    private_key_passphrase: Option<String>,
+    private_key_passphrase: Secret,
     agent_key_identifier: Option<String>,
     connection_timeout: u16,
     verify_host_key: bool,
     custom_known_hosts_path: Option<PathBuf>,
+    sudo_password: Secret,
+    /// Program and arguments.
+    sudo_command: Vec<String>,
+    proxy_command: Option<String>,
+    socks_proxy: Option<String>,
+    /// Resolved on first connect and then shared by all sessions, so that keyring or commands aren't used on every reconnect.
+    resolved_secrets: Mutex<Option<ResolvedSecrets>>,
 
     available_sessions: Vec<Mutex<SharedSessionData>>,
 }
 
+#[derive(Clone)]
+struct ResolvedSecrets {
+    password: Option<String>,
+    private_key_passphrase: Option<String>,
+    sudo_password: Option<String>,
+}
+
 pub struct SharedSessionData {
     is_initialized: bool,
     session: ssh2::Session,
     open_channel: Option<ssh2::Channel>,
     // For incomplete invocations, tag with the invocation ID.
     invocation_id: u64,
+    /// Set when connecting. See `Ssh2::resolve_secrets`.
+    sudo_password: Option<String>,
+    /// Checked when sudo is first needed.
+    passwordless_sudo: Option<bool>,
+    /// Running proxy_command, if used.
+    proxy_process: Option<Child>,
+}
+
+impl SharedSessionData {
+    fn stop_proxy_process(&mut self) {
+        if let Some(mut process) = self.proxy_process.take() {
+            let _ = process.kill();
+            let _ = process.wait();
+        }
+    }
+}
+
+impl Drop for SharedSessionData {
+    /// Otherwise proxy processes would be left running after reloading configuration or exiting.
+    fn drop(&mut self) {
+        self.stop_proxy_process();
+    }
+}
+
+/// Typed and validated settings of the SSH connector. Also used when launching external SSH clients.
+#[derive(Clone)]
+pub struct SshConnectorSettings {
+    pub port: u16,
+    pub username: String,
+    /// Secrets can also be read from OS keyring or command output. See `Secret`.
+    pub password: Secret,
+    pub private_key_path: Option<String>,
+    pub private_key_passphrase: Secret,
+    pub agent_key_identifier: Option<String>,
+    /// In seconds.
+    pub connection_timeout: u16,
+    pub verify_host_key: bool,
+    pub custom_known_hosts_path: Option<PathBuf>,
+    pub parallel_sessions: u16,
+    pub sudo_password: Secret,
+    pub sudo_command: String,
+    pub proxy_command: Option<String>,
+    /// Address and port.
+    pub socks_proxy: Option<String>,
+}
+
+impl Default for SshConnectorSettings {
+    fn default() -> Self {
+        SshConnectorSettings {
+            port: 22,
+            username: String::from("root"),
+            password: Secret::None,
+            private_key_path: None,
+            private_key_passphrase: Secret::None,
+            agent_key_identifier: None,
+            connection_timeout: 15,
+            verify_host_key: true,
+            custom_known_hosts_path: None,
+            parallel_sessions: 2,
+            sudo_password: Secret::None,
+            sudo_command: String::from("sudo"),
+            proxy_command: None,
+            socks_proxy: None,
+        }
+    }
+}
+
+impl SshConnectorSettings {
+    /// Returns `ErrorKind::InvalidConfig` if a setting has an invalid value.
+    pub fn from_settings(settings: &HashMap<String, String>) -> Result<Self, LkError> {
+        let defaults = Self::default();
+
+        let parallel_sessions = Self::parse_setting(settings, "parallel_sessions", defaults.parallel_sessions)?;
+        if parallel_sessions == 0 {
+            return Err(LkError::new(ErrorKind::InvalidConfig, "SSH setting \"parallel_sessions\" has to be at least 1"));
+        }
+
+        let sudo_command = settings.get("sudo_command").cloned().unwrap_or(defaults.sudo_command);
+        if sudo_command.trim().is_empty() {
+            return Err(LkError::new(ErrorKind::InvalidConfig, "SSH setting \"sudo_command\" can't be empty"));
+        }
+
+        let sudo_password = Secret::from_settings(settings, "sudo_password");
+        if sudo_password.is_set() && !Self::supports_sudo_password(&sudo_command) {
+            return Err(LkError::new(ErrorKind::InvalidConfig,
+                "SSH setting \"sudo_password\" can only be used when \"sudo_command\" is sudo without -n"));
+        }
+
+        if settings.contains_key("proxy_command") && settings.contains_key("socks_proxy") {
+            return Err(LkError::new(ErrorKind::InvalidConfig, "SSH settings \"proxy_command\" and \"socks_proxy\" can't be used together"));
+        }
+
+        Ok(SshConnectorSettings {
+            port: Self::parse_setting(settings, "port", defaults.port)?,
+            username: settings.get("username").cloned().unwrap_or(defaults.username),
+            password: Secret::from_settings(settings, "password"),
+            private_key_path: settings.get("private_key_path").cloned(),
+            private_key_passphrase: Secret::from_settings(settings, "private_key_passphrase"),
+            agent_key_identifier: settings.get("agent_key_identifier").cloned(),
+            connection_timeout: Self::parse_setting(settings, "connection_timeout", defaults.connection_timeout)?,
+            verify_host_key: Self::parse_setting(settings, "verify_host_key", defaults.verify_host_key)?,
+            custom_known_hosts_path: settings.get("custom_known_hosts_path").map(PathBuf::from),
+            parallel_sessions: parallel_sessions,
+            sudo_password: sudo_password,
+            sudo_command: sudo_command,
+            proxy_command: settings.get("proxy_command").cloned(),
+            socks_proxy: settings.get("socks_proxy").cloned(),
+        })
+    }
+
+    /// The password is passed with sudo's -S and -p options, which other commands (e.g. doas) don't have.
+    /// With -n, sudo would never read the password.
+    fn supports_sudo_password(sudo_command: &str) -> bool {
+        let mut words = sudo_command.split_whitespace();
+        let program_name = words.next().and_then(|program| Path::new(program).file_name()).and_then(|name| name.to_str());
+        program_name == Some("sudo") && words.all(|argument| argument != "-n" && argument != "--non-interactive")
+    }
+
+    fn parse_setting<T: FromStr>(settings: &HashMap<String, String>, key: &str, default: T) -> Result<T, LkError> {
+        match settings.get(key) {
+            Some(value) => value.parse::<T>().map_err(|_| {
+                LkError::new(ErrorKind::InvalidConfig, format!("Invalid value for SSH setting \"{}\": {}", key, value))
+            }),
+            None => Ok(default),
+        }
+    }
 }
 
 impl Module for Ssh2 {
     fn new(settings: &HashMap<String, String>) -> Self {
-        let parallel_sessions = settings.get("parallel_sessions").unwrap_or(&String::from("2")).parse::<u16>().unwrap();
+        // Settings are already validated when reading configuration, so this shouldn't fail.
+        let settings = SshConnectorSettings::from_settings(settings).unwrap_or_else(|error| {
+            log::error!("{}", error);
+            SshConnectorSettings::default()
+        });
+
         let mut available_sessions = Vec::new();
 
-        for _ in 0..parallel_sessions {
+        for _ in 0..settings.parallel_sessions {
             available_sessions.push(Mutex::new(SharedSessionData {
                 is_initialized: false,
                 session: ssh2::Session::new().unwrap(),
                 open_channel: None,
                 invocation_id: 0,
+                sudo_password: None,
+                passwordless_sudo: None,
+                proxy_process: None,
             }));
         }
 
         Ssh2 {
             address: Mutex::new(String::from("0.0.0.0")),
-            port: Mutex::new(settings.get("port").unwrap_or(&String::from("22")).parse::<u16>().unwrap()),
-            username: settings.get("username").unwrap_or(&String::from("root")).clone(),
-            password: settings.get("password").cloned(),
-            private_key_path: settings.get("private_key_path").cloned(),
-            private_key_passphrase: settings.get("private_key_passphrase").cloned(),
-            agent_key_identifier: settings.get("agent_key_identifier").cloned(),
-            connection_timeout: settings.get("connection_timeout").unwrap_or(&String::from("15")).parse::<u16>().unwrap(),
-            verify_host_key: settings.get("verify_host_key").unwrap_or(&String::from("true")).parse::<bool>().unwrap(),
-            custom_known_hosts_path: settings.get("custom_known_hosts_path").map(|path| PathBuf::from(path)),
+            port: Mutex::new(settings.port),
+            username: settings.username,
+            password: settings.password,
+            private_key_path: settings.private_key_path,
+            private_key_passphrase: settings.private_key_passphrase,
+            agent_key_identifier: settings.agent_key_identifier,
+            connection_timeout: settings.connection_timeout,
+            verify_host_key: settings.verify_host_key,
+            custom_known_hosts_path: settings.custom_known_hosts_path,
+            sudo_password: settings.sudo_password,
+            sudo_command: settings.sudo_command.split_whitespace().map(String::from).collect(),
+            proxy_command: settings.proxy_command,
+            socks_proxy: settings.socks_proxy,
+            resolved_secrets: Mutex::new(None),
             available_sessions: available_sessions,
         }
     }
@@ -108,6 +291,18 @@ impl ConnectionModule for Ssh2 {
          *mutex_address = address.to_string();
     }
 
+    /// Connects all parallel sessions.
+    fn open_connection(&self) -> Result<(), LkError> {
+        let address = self.address.lock().unwrap().clone();
+        let port = *self.port.lock().unwrap();
+
+        for session in self.available_sessions.iter() {
+            let mut session_data = session.lock().unwrap();
+            self.connect(&mut session_data, &address, port)?;
+        }
+        Ok(())
+    }
+
     fn send_message(&self, message: &str) -> Result<ResponseMessage, LkError> {
         if message.is_empty() {
             return Ok(ResponseMessage::empty());
@@ -131,8 +326,11 @@ impl ConnectionModule for Ssh2 {
         // Merge stderr etc. to the same stream as stdout.
         channel.handle_extended_data(ssh2::ExtendedData::Merge).unwrap();
 
-        channel.exec(message)
-               .map_err(|error| format!("Error executing command '{}': {}", message, error))?;
+        let sudo_password = match self.check_sudo_password_needed(&mut session_data, message)? {
+            true => session_data.sudo_password.clone(),
+            false => None,
+        };
+        Self::exec(&mut channel, message, &self.sudo_command, sudo_password.as_ref())?;
 
         let mut output = String::new();
 
@@ -148,6 +346,7 @@ impl ConnectionModule for Ssh2 {
         channel.wait_close()
                .map_err(|error| format!("Error while closing channel: {}", error))?;
 
+        Self::check_sudo_errors(&output, exit_status)?;
         Ok(ResponseMessage::new(strip_newline(&output), exit_status))
     }
 
@@ -170,8 +369,11 @@ impl ConnectionModule for Ssh2 {
         // Merge stderr etc. to the same stream as stdout.
         channel.handle_extended_data(ssh2::ExtendedData::Merge).unwrap();
         
-        channel.exec(message)
-               .map_err(|error| format!("Error executing command '{}': {}", message, error))?;
+        let sudo_password = match self.check_sudo_password_needed(&mut session_data, message)? {
+            true => session_data.sudo_password.clone(),
+            false => None,
+        };
+        Self::exec(&mut channel, message, &self.sudo_command, sudo_password.as_ref())?;
 
         let mut buffer = [0u8; 256];
         let output = channel.read(&mut buffer)
@@ -183,6 +385,7 @@ impl ConnectionModule for Ssh2 {
             channel.wait_close()
                    .map_err(|error| format!("Error while closing channel: {}", error))?;
 
+            Self::check_sudo_errors(&output, exit_status)?;
             Ok(ResponseMessage::new(strip_newline(&output), exit_status))
         }
         else {
@@ -222,26 +425,48 @@ impl ConnectionModule for Ssh2 {
         }
     }
 
-    fn download_file(&self, source: &str) -> Result<(FileMetadata, Vec<u8>), LkError> {
+    fn download_file(&self, source: &str, destination: &mut dyn Write, max_size: u64) -> Result<FileMetadata, LkError> {
         let session_data = self.wait_for_session(0, true)?;
         let sftp = session_data.session.sftp()?;
 
         let mut file = sftp.open(Path::new(&source))?;
-        let mut contents = Vec::new();
-        let _bytes_written = file.read_to_end(&mut contents)?;
         let stat = file.stat()?;
+
+        // Size is checked beforehand when available, but also while reading since the file can grow.
+        if stat.size.unwrap_or(0) > max_size {
+            return Err(LkError::other(format!("File is larger than the maximum download size ({} bytes)", max_size)));
+        }
+
+        let mut hasher = sha256::Hasher::new();
+        let mut buffer = vec![0u8; DOWNLOAD_CHUNK_SIZE];
+        let mut total_bytes: u64 = 0;
+        loop {
+            let bytes_read = file.read(&mut buffer)?;
+            if bytes_read == 0 {
+                break;
+            }
+
+            total_bytes += bytes_read as u64;
+            if total_bytes > max_size {
+                return Err(LkError::other(format!("File is larger than the maximum download size ({} bytes)", max_size)));
+            }
+
+            sha256::Digest::update(&mut hasher, &buffer[..bytes_read]);
+            destination.write_all(&buffer[..bytes_read])?;
+        }
+
         let metadata = FileMetadata {
             download_time: Utc::now(),
             local_path: None,
             remote_path: source.to_string(),
-            remote_file_hash: sha256::hash(&contents),
+            remote_file_hash: sha256::finalize(hasher),
             owner_uid: stat.uid.unwrap(),
             owner_gid: stat.gid.unwrap(),
             permissions: stat.perm.unwrap(),
             temporary: true,
         };
 
-        Ok((metadata, contents))
+        Ok(metadata)
     }
 
     fn upload_file(&self, metadata: &FileMetadata, contents: Vec<u8>) -> Result<(), LkError> {
@@ -255,7 +480,8 @@ impl ConnectionModule for Ssh2 {
             ssh2::OpenType::File,
         );
 
-        file?.write(&contents)
+        // write() can return before all of the contents are written.
+        file?.write_all(&contents)
              .map(|_| Ok(()))?
     }
 
@@ -312,6 +538,117 @@ impl ConnectionModule for Ssh2 {
 }
 
 impl Ssh2 {
+    /// Executes the message. If sudo password is given, sudo is made to read it from stdin.
+    /// The password should only be given when sudo actually asks for it. Otherwise it ends up in stdin of the actual command.
+    fn exec(channel: &mut ssh2::Channel, message: &str, sudo_command: &[String], sudo_password: Option<&String>) -> Result<(), LkError> {
+        let sudo_message = Self::password_sudo_message(message, sudo_command);
+        let message = &Self::replace_sudo_command(message, sudo_command);
+
+        if let (Some(sudo_password), Some(sudo_message)) = (sudo_password, sudo_message) {
+            channel.exec(&sudo_message)
+                   .map_err(|error| format!("Error executing command '{}': {}", message, error))?;
+
+            channel.write_all(format!("{}\n", sudo_password).as_bytes())
+                   .map_err(|error| format!("Error sending sudo password: {}", error))?;
+            channel.send_eof()
+                   .map_err(|error| format!("Error sending sudo password: {}", error))?;
+        }
+        else {
+            channel.exec(message)
+                   .map_err(|error| format!("Error executing command '{}': {}", message, error))?;
+        }
+
+        Ok(())
+    }
+
+    /// Makes sudo read the password from stdin. Only possible when the message starts with sudo,
+    /// since piped sudo already gets its stdin from the pipe. Returns `None` for other messages.
+    fn password_sudo_message(message: &str, sudo_command: &[String]) -> Option<String> {
+        message.strip_prefix(SUDO_PREFIX).map(|sudo_arguments| {
+            format!("{}'-S' '-p' '' {}", Self::quote_sudo_command(sudo_command), sudo_arguments)
+        })
+    }
+
+    /// `ShellCommand` always uses plain sudo, so it's replaced with the configured command.
+    /// Sudo can appear at the beginning or after a pipe (e.g. when the command gets data through stdin).
+    fn replace_sudo_command(message: &str, sudo_command: &[String]) -> String {
+        let replacement = Self::quote_sudo_command(sudo_command);
+        if replacement == SUDO_PREFIX {
+            return message.to_string();
+        }
+
+        let message = match message.strip_prefix(SUDO_PREFIX) {
+            Some(rest) => format!("{}{}", replacement, rest),
+            None => message.to_string(),
+        };
+        message.replace(&format!("| {}", SUDO_PREFIX), &format!("| {}", replacement))
+    }
+
+    /// Quoted like `ShellCommand` does, including the trailing space.
+    fn quote_sudo_command(sudo_command: &[String]) -> String {
+        sudo_command.iter().map(|argument| format!("{} ", shell_quote(argument))).collect()
+    }
+
+    /// Returns true if sudo has to be given the configured password. Passwordless sudo is probed first,
+    /// so that the password is never sent when it's not needed (e.g. NOPASSWD rules or cached credentials).
+    /// Fails fast if sudo would require a password that isn't configured.
+    /// Otherwise the failure would only be visible as a vague command error.
+    fn check_sudo_password_needed(&self, session_data: &mut MutexGuard<SharedSessionData>, message: &str) -> Result<bool, LkError> {
+        let piped_sudo = message.contains(&format!("| {}", SUDO_PREFIX));
+        let uses_sudo = message.starts_with(SUDO_PREFIX) || piped_sudo;
+        if !uses_sudo {
+            return Ok(false);
+        }
+
+        let passwordless_sudo = match session_data.passwordless_sudo {
+            Some(passwordless_sudo) => passwordless_sudo,
+            None => {
+                let passwordless_sudo = self.probe_passwordless_sudo(&session_data.session)?;
+                session_data.passwordless_sudo = Some(passwordless_sudo);
+                passwordless_sudo
+            }
+        };
+
+        if passwordless_sudo {
+            Ok(false)
+        }
+        else if piped_sudo {
+            // See `ShellCommand::stdin`. The password can't be written to stdin that already contains the data.
+            Err(LkError::new(ErrorKind::SudoPasswordRequired, "Commands with stdin data require passwordless sudo."))
+        }
+        else if session_data.sudo_password.is_some() {
+            Ok(true)
+        }
+        else {
+            Err(LkError::new(ErrorKind::SudoPasswordRequired,
+                "Passwordless sudo is required. Configure it on the host or set the sudo_password setting of the SSH connector."))
+        }
+    }
+
+    fn probe_passwordless_sudo(&self, session: &ssh2::Session) -> Result<bool, LkError> {
+        let mut channel = session.channel_session()?;
+        channel.exec(&format!("{}'-n' 'true'", Self::quote_sudo_command(&self.sudo_command)))?;
+
+        let mut output = String::new();
+        channel.read_to_string(&mut output)?;
+        channel.wait_close()?;
+
+        // 127 means the command wasn't found. That is left for the actual command to report.
+        let exit_status = channel.exit_status()?;
+        log::debug!("Passwordless sudo probe exited with status {}", exit_status);
+        Ok(exit_status == 0 || exit_status == 127)
+    }
+
+    /// Recognizes sudo failures caused by a missing or incorrect password. Otherwise they would look like regular command failures.
+    fn check_sudo_errors(output: &str, exit_status: i32) -> Result<(), LkError> {
+        if exit_status != 0 && SUDO_PASSWORD_ERRORS.iter().any(|sudo_error| output.contains(sudo_error)) {
+            Err(LkError::new(ErrorKind::SudoPasswordRequired, "Sudo requires a password. Check the sudo_password setting of the SSH connector."))
+        }
+        else {
+            Ok(())
+        }
+    }
+
     fn wait_for_session(&self, invocation_id: u64, connect_automatically: bool) -> Result<MutexGuard<SharedSessionData>, LkError> {
         loop {
             for (index, session) in self.available_sessions.iter().enumerate() {
@@ -340,23 +677,50 @@ impl Ssh2 {
         }
     }
 
+    /// Resolves secrets only once per connector. Lock is held while resolving so that parallel sessions don't resolve them again.
+    /// Failures aren't stored, so resolving is retried on the next connect.
+    fn resolve_secrets(&self) -> Result<ResolvedSecrets, LkError> {
+        let mut resolved_secrets = self.resolved_secrets.lock().unwrap();
+        if resolved_secrets.is_none() {
+            *resolved_secrets = Some(ResolvedSecrets {
+                password: self.password.resolve()?,
+                private_key_passphrase: self.private_key_passphrase.resolve()?,
+                sudo_password: self.sudo_password.resolve()?,
+            });
+        }
+
+        Ok(resolved_secrets.clone().unwrap())
+    }
+
     fn connect(&self, session_data: &mut MutexGuard<SharedSessionData>, address: &str, port: u16) -> Result<(), LkError> {
         if session_data.is_initialized {
             return Ok(())
         }
 
-        let mut socket_addresses = format!("{}:{}", address, port).to_socket_addrs()?;
-        let socket_address = match socket_addresses.next() {
-            Some(address) => address,
-            None => return Err(LkError::other("Failed to resolve address")),
-        };
+        session_data.stop_proxy_process();
 
         let connection_timeout = std::time::Duration::from_secs(self.connection_timeout as u64);
-        let stream = TcpStream::connect_timeout(&socket_address, connection_timeout)?;
-        log::info!("Connected to {}:{}", address, port);
-
         session_data.session = ssh2::Session::new().unwrap();
-        session_data.session.set_tcp_stream(stream);
+
+        if let Some(proxy_command) = self.proxy_command.as_ref() {
+            let (stream, process) = spawn_proxy_command(proxy_command, address, port)?;
+            session_data.session.set_tcp_stream(stream);
+            session_data.proxy_process = Some(process);
+            log::info!("Connecting to {}:{} through proxy command", address, port);
+        }
+        else if let Some(socks_proxy) = self.socks_proxy.as_ref() {
+            let stream = connect_socks5(socks_proxy, address, port, connection_timeout)?;
+            session_data.session.set_tcp_stream(stream);
+            log::info!("Connected to {}:{} through SOCKS proxy {}", address, port, socks_proxy);
+        }
+        else {
+            let socket_address = resolve_address(&format!("{}:{}", address, port))?;
+            let stream = TcpStream::connect_timeout(&socket_address, connection_timeout)
+                .map_err(|error| LkError::new(ErrorKind::ConnectionFailed, error))?;
+            session_data.session.set_tcp_stream(stream);
+            log::info!("Connected to {}:{}", address, port);
+        }
+
         if let Err(error) = session_data.session.handshake() {
             log::debug!("Supported Kex algs: {:?}", session_data.session.supported_algs(ssh2::MethodType::Kex));
             log::debug!("Supported MacCs algs: {:?}", session_data.session.supported_algs(ssh2::MethodType::MacCs));
@@ -369,15 +733,20 @@ impl Ssh2 {
             self.check_known_hosts(&session_data, &address, port)?;
         }
 
-        if self.password.is_some() {
-            session_data.session.userauth_password(self.username.as_str(), self.password.as_ref().unwrap().as_str())
+        // Secrets are resolved here so that they're read from keyring or commands only when needed.
+        let secrets = self.resolve_secrets()?;
+        session_data.sudo_password = secrets.sudo_password;
+        session_data.passwordless_sudo = None;
+
+        if let Some(password) = secrets.password {
+            session_data.session.userauth_password(self.username.as_str(), password.as_str())
                 .map_err(|error| LkError::other(format!("Failed to authenticate with password: {}", error)))?;
         }
         else if self.private_key_path.is_some() {
             let path = Path::new(self.private_key_path.as_ref().unwrap());
-            let passphrase_option = self.private_key_passphrase.as_ref().map(|pass| pass.as_str());
+            let passphrase = secrets.private_key_passphrase;
 
-            session_data.session.userauth_pubkey_file(self.username.as_str(), None, path, passphrase_option)
+            session_data.session.userauth_pubkey_file(self.username.as_str(), None, path, passphrase.as_deref())
                 .map_err(|error| LkError::other(format!("Failed to authenticate with private key: {}", error)))?;
         }
         else {
@@ -463,8 +832,15 @@ impl Ssh2 {
             Ok(known_hosts_path.clone())
         }
         else {
-            let config_dir = file_handler::get_config_dir()?;
-            let known_hosts_path = config_dir.join("known_hosts");
+            // Earlier versions kept known_hosts in the configuration directory. Keep using it if it exists.
+            let legacy_known_hosts_path = file_handler::get_config_dir()?.join("known_hosts");
+            if legacy_known_hosts_path.exists() {
+                return Ok(legacy_known_hosts_path);
+            }
+
+            let data_dir = file_handler::get_data_dir()?;
+            std::fs::create_dir_all(&data_dir)?;
+            let known_hosts_path = data_dir.join("known_hosts");
 
             // Create known_hosts if it's missing.
             if !known_hosts_path.exists() {
@@ -500,4 +876,120 @@ impl From<ssh2::Error> for LkError {
             _ => LkError::new(ErrorKind::Other, error),
         }
     }
-}
\ No newline at end of file
+}
+
+fn resolve_address(address: &str) -> Result<std::net::SocketAddr, LkError> {
+    let mut socket_addresses = address.to_socket_addrs()
+        .map_err(|error| LkError::new(ErrorKind::ConnectionFailed, error))?;
+
+    match socket_addresses.next() {
+        Some(address) => Ok(address),
+        None => Err(LkError::new(ErrorKind::ConnectionFailed, "Failed to resolve address")),
+    }
+}
+
+/// Runs the command with a socket as its stdin and stdout. The other end of the socket is returned for the SSH session.
+fn spawn_proxy_command(proxy_command: &str, address: &str, port: u16) -> Result<(UnixStream, Child), LkError> {
+    let command = proxy_command.replace("%h", &shell_quote(address)).replace("%p", &port.to_string());
+    let (local_stream, proxy_stream) = UnixStream::pair()?;
+    let proxy_stdout = proxy_stream.try_clone()?;
+
+    let process = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .stdin(Stdio::from(OwnedFd::from(proxy_stream)))
+        .stdout(Stdio::from(OwnedFd::from(proxy_stdout)))
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|error| LkError::new(ErrorKind::ConnectionFailed, format!("Failed to start proxy command: {}", error)))?;
+
+    Ok((local_stream, process))
+}
+
+/// Minimal SOCKS5 client (RFC 1928) without authentication. The target address is resolved by the proxy.
+fn connect_socks5(proxy: &str, address: &str, port: u16, timeout: Duration) -> Result<TcpStream, LkError> {
+    if address.len() > u8::MAX as usize {
+        return Err(LkError::new(ErrorKind::ConnectionFailed, "Address is too long for SOCKS proxy"));
+    }
+
+    let proxy_address = resolve_address(proxy)?;
+    let mut stream = TcpStream::connect_timeout(&proxy_address, timeout)
+        .map_err(|error| LkError::new(ErrorKind::ConnectionFailed, error))?;
+    stream.set_read_timeout(Some(timeout))?;
+
+    // Version 5, one authentication method: no authentication.
+    stream.write_all(&[5, 1, 0])?;
+    let mut method_reply = [0u8; 2];
+    stream.read_exact(&mut method_reply)?;
+    if method_reply != [5, 0] {
+        return Err(LkError::new(ErrorKind::ConnectionFailed, "SOCKS proxy requires unsupported authentication"));
+    }
+
+    // CONNECT with a domain name address.
+    let mut request = vec![5, 1, 0, 3, address.len() as u8];
+    request.extend_from_slice(address.as_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request)?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply)?;
+    if reply[1] != 0 {
+        return Err(LkError::new(ErrorKind::ConnectionFailed, format!("SOCKS proxy failed to connect (reply code {})", reply[1])));
+    }
+
+    // Bound address and port are not needed.
+    let bound_address_length = match reply[3] {
+        1 => 4,
+        4 => 16,
+        3 => {
+            let mut length = [0u8; 1];
+            stream.read_exact(&mut length)?;
+            length[0] as usize
+        },
+        _ => return Err(LkError::new(ErrorKind::ConnectionFailed, "Invalid reply from SOCKS proxy")),
+    };
+    let mut bound_address = vec![0u8; bound_address_length + 2];
+    stream.read_exact(&mut bound_address)?;
+
+    stream.set_read_timeout(None)?;
+    Ok(stream)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ShellCommand;
+
+    fn sudo_message(stdin: Option<&str>) -> String {
+        let mut command = ShellCommand::new_from(vec!["cat", "/etc/hosts"]);
+        command.use_sudo = true;
+        if let Some(stdin) = stdin {
+            command.stdin(stdin.as_bytes().to_vec());
+        }
+        command.to_string()
+    }
+
+    #[test]
+    fn test_replace_sudo_command() {
+        let sudo = vec![String::from("sudo")];
+        let doas = vec![String::from("doas"), String::from("-u"), String::from("root")];
+
+        assert_eq!(Ssh2::replace_sudo_command(&sudo_message(None), &sudo), "'sudo' 'cat' '/etc/hosts'");
+        assert_eq!(Ssh2::replace_sudo_command(&sudo_message(None), &doas), "'doas' '-u' 'root' 'cat' '/etc/hosts'");
+
+        let piped = Ssh2::replace_sudo_command(&sudo_message(Some("data")), &doas);
+        assert!(piped.ends_with(" | 'doas' '-u' 'root' 'cat' '/etc/hosts'"));
+        assert!(!piped.contains("'sudo'"));
+    }
+
+    #[test]
+    fn test_password_sudo_message() {
+        let sudo = vec![String::from("sudo")];
+
+        assert_eq!(Ssh2::password_sudo_message(&sudo_message(None), &sudo).unwrap(), "'sudo' '-S' '-p' '' 'cat' '/etc/hosts'");
+        // Stdin of piped sudo is taken, so the password can't be passed.
+        assert!(Ssh2::password_sudo_message(&sudo_message(Some("data")), &sudo).is_none());
+        assert!(Ssh2::password_sudo_message("'cat' '/etc/hosts'", &sudo).is_none());
+    }
+}
@@ -5,6 +5,7 @@
 
 pub mod module_factory;
 pub use module_factory::ModuleFactory;
+pub use module_factory::ModuleCatalogEntry;
 
 pub mod metadata;
 pub use metadata::Metadata;
@@ -3,7 +3,10 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use serde_derive::Serialize;
+use crate::configuration::Hosts;
+use crate::error::{ErrorKind, LkError};
 
 use super::{
     command,
@@ -18,6 +21,22 @@ use super::{
     ModuleType,
 };
 
+/// Public information about a registered module. Used for listing modules and their settings.
+#[derive(Serialize, Clone, Debug)]
+pub struct ModuleCatalogEntry {
+    pub module_type: String,
+    pub id: String,
+    pub version: String,
+    pub description: String,
+    /// Setting key and description.
+    pub settings: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_module: Option<String>,
+    /// Connector used by monitors and commands.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connector: Option<String>,
+}
+
 #[derive(Default)]
 pub struct ModuleFactory {
     connector_modules: Vec<(Metadata, fn(&HashMap<String, String>) -> connection::Connector)>,
@@ -40,53 +59,78 @@ impl ModuleFactory {
     }
 
     pub fn new_connector(&self, module_spec: &ModuleSpecification, settings: &HashMap<String, String>) -> Option<connection::Connector> {
-        let mut normalized_spec = module_spec.clone();
-        if normalized_spec.latest_version() {
-            if let Some(latest_version) = self.get_latest_version_for_connector(&normalized_spec.id) {
-                normalized_spec.version = latest_version;
-            }
-            else {
-                log::error!("Connector module '{}' was not found.", normalized_spec.id);
+        let normalized_spec = match self.resolve_module_spec(&ModuleSpecification::connector(&module_spec.id, &module_spec.version)) {
+            Ok(spec) => spec,
+            Err(error) => {
+                log::error!("{}", error);
                 return None;
             }
-        }
+        };
 
         let constructor = self.connector_modules.iter().find(|(metadata, _ctor)| metadata.module_spec == normalized_spec).unwrap().1;
         Some(constructor(settings))
     }
 
     pub fn new_monitor(&self, module_spec: &ModuleSpecification, settings: &HashMap<String, String>) -> Option<monitoring::Monitor> {
-        let mut normalized_spec = module_spec.clone();
-        if normalized_spec.latest_version() {
-            if let Some(latest_version) = self.get_latest_version_for_monitor(&normalized_spec.id) {
-                normalized_spec.version = latest_version;
-            }
-            else {
-                log::error!("Monitoring module '{}' was not found.", normalized_spec.id);
+        let normalized_spec = match self.resolve_module_spec(&ModuleSpecification::monitor(&module_spec.id, &module_spec.version)) {
+            Ok(spec) => spec,
+            Err(error) => {
+                log::error!("{}", error);
                 return None;
             }
-        }
+        };
 
         let constructor = self.monitor_modules.iter().find(|(metadata, _ctor)| metadata.module_spec == normalized_spec).unwrap().1;
         Some(constructor(settings))
     }
 
     pub fn new_command(&self, module_spec: &ModuleSpecification, settings: &HashMap<String, String>) -> Option<command::Command> {
-        let mut normalized_spec = module_spec.clone();
-        if normalized_spec.latest_version() {
-            if let Some(latest_version) = self.get_latest_version_for_command(&normalized_spec.id) {
-                normalized_spec.version = latest_version
-            }
-            else {
-                log::error!("Command module '{}' was not found.", normalized_spec.id);
+        let normalized_spec = match self.resolve_module_spec(&ModuleSpecification::command(&module_spec.id, &module_spec.version)) {
+            Ok(spec) => spec,
+            Err(error) => {
+                log::error!("{}", error);
                 return None;
             }
-        }
+        };
 
         let constructor = self.command_modules.iter().find(|(metadata, _ctor)| metadata.module_spec == normalized_spec).unwrap().1;
         Some(constructor(settings))
     }
 
+    /// Resolves version "latest" to the newest registered version and checks that a pinned version is available.
+    /// Returns `ErrorKind::InvalidConfig` if the module or the requested version doesn't exist.
+    pub fn resolve_module_spec(&self, module_spec: &ModuleSpecification) -> Result<ModuleSpecification, LkError> {
+        let module_type_name = match module_spec.module_type {
+            ModuleType::Monitor => "Monitoring",
+            ModuleType::Command => "Command",
+            ModuleType::Connector => "Connector",
+            ModuleType::Unknown => "Unknown",
+        };
+
+        let mut available_versions = self.get_module_metadatas().into_iter()
+            .filter(|metadata| metadata.module_spec.module_type == module_spec.module_type && metadata.module_spec.id == module_spec.id)
+            .map(|metadata| metadata.module_spec.version)
+            .collect::<Vec<_>>();
+        available_versions.sort();
+
+        if available_versions.is_empty() {
+            return Err(LkError::new(ErrorKind::InvalidConfig, format!("{} module '{}' was not found.", module_type_name, module_spec.id)));
+        }
+
+        let mut resolved_spec = module_spec.clone();
+        if module_spec.latest_version() {
+            resolved_spec.version = available_versions.last().unwrap().clone();
+        }
+        else if !available_versions.contains(&module_spec.version) {
+            return Err(LkError::new(ErrorKind::InvalidConfig, format!(
+                "Version {} of {} module '{}' is not available. Available versions: {}",
+                module_spec.version, module_type_name.to_lowercase(), module_spec.id, available_versions.join(", ")
+            )));
+        }
+
+        Ok(resolved_spec)
+    }
+
     pub fn get_latest_version_for_command(&self, module_id: &String) -> Option<String> {
         let mut all_versions = self.command_modules.iter()
                                                    .filter(|(metadata, _)| &metadata.module_spec.id == module_id)
@@ -114,9 +158,9 @@ impl ModuleFactory {
         all_versions.last().cloned()
     }
 
-    pub fn get_connector_module_metadata(&self, module_spec: &ModuleSpecification) -> Metadata {
+    pub fn get_connector_module_metadata(&self, module_spec: &ModuleSpecification) -> Option<Metadata> {
         let module_spec = ModuleSpecification::new(module_spec.id.as_str(), module_spec.version.as_str(), ModuleType::Connector);
-        self.connector_modules.iter().find(|(metadata, _ctor)| metadata.module_spec == module_spec).unwrap().0.clone()
+        self.connector_modules.iter().find(|(metadata, _ctor)| metadata.module_spec == module_spec).map(|(metadata, _ctor)| metadata.clone())
     }
 
     pub fn get_module_metadatas(&self) -> Vec<Metadata> {
@@ -127,6 +171,25 @@ impl ModuleFactory {
         metadatas
     }
 
+    /// Metadata of all registered modules: connectors first, then monitors and commands.
+    pub fn catalog(&self) -> Vec<ModuleCatalogEntry> {
+        let new_entry = |metadata: &Metadata, connector: Option<ModuleSpecification>| ModuleCatalogEntry {
+            module_type: metadata.module_spec.module_type.to_string().to_lowercase(),
+            id: metadata.module_spec.id.clone(),
+            version: metadata.module_spec.version.clone(),
+            description: metadata.description.clone(),
+            settings: metadata.settings.clone().into_iter().collect(),
+            parent_module: metadata.parent_module.as_ref().map(|parent| parent.id.clone()),
+            connector: connector.map(|connector_spec| connector_spec.id),
+        };
+
+        let mut catalog = Vec::new();
+        catalog.extend(self.connector_modules.iter().map(|(metadata, _ctor)| new_entry(metadata, None)));
+        catalog.extend(self.monitor_modules.iter().map(|(metadata, ctor)| new_entry(metadata, ctor(&HashMap::new()).get_connector_spec())));
+        catalog.extend(self.command_modules.iter().map(|(metadata, ctor)| new_entry(metadata, ctor(&HashMap::new()).get_connector_spec())));
+        catalog
+    }
+
     pub fn validate_modules(&self) -> Result<(), String> {
         log::debug!("Validating modules");
 
@@ -184,6 +247,111 @@ impl ModuleFactory {
         Ok(())
     }
 
+    /// Checks that all modules referenced in effective host configurations exist.
+    pub fn validate_host_config(&self, hosts_config: &Hosts) -> Result<(), String> {
+        for (host_id, host_config) in hosts_config.hosts.iter() {
+            for (monitor_id, monitor_config) in host_config.effective.monitors.iter() {
+                if let Err(error) = self.resolve_module_spec(&ModuleSpecification::monitor(monitor_id, &monitor_config.version)) {
+                    return Err(format!("Host '{}': {}", host_id, error));
+                }
+            }
+
+            for (command_id, command_config) in host_config.effective.commands.iter() {
+                if let Err(error) = self.resolve_module_spec(&ModuleSpecification::command(command_id, &command_config.version)) {
+                    return Err(format!("Host '{}': {}", host_id, error));
+                }
+            }
+
+            for (connector_id, connector_config) in host_config.effective.connectors.iter() {
+                let module_id = connector_config.module_id(connector_id);
+                if self.get_latest_version_for_connector(&module_id).is_none() {
+                    return Err(format!("Connector module '{}' for host '{}' was not found.", module_id, host_id));
+                }
+            }
+
+            let selected_connectors = host_config.effective.monitors.values().filter_map(|config| config.connector.as_ref())
+                .chain(host_config.effective.commands.values().filter_map(|config| config.connector.as_ref()));
+
+            for connector_id in selected_connectors {
+                if !host_config.effective.connectors.contains_key(connector_id) {
+                    return Err(format!("Connector '{}' for host '{}' is not configured.", connector_id, host_id));
+                }
+            }
+
+            // Connector modules required by monitors and commands have to exist and selected instances have to be of the right type.
+            let monitor_requirements = host_config.effective.monitors.iter().filter_map(|(id, config)| {
+                let connector_spec = self.new_monitor(&ModuleSpecification::monitor(id, &config.version), &config.settings)?.get_connector_spec()?;
+                Some((format!("monitor '{}'", id), connector_spec, config.connector.clone()))
+            });
+            let command_requirements = host_config.effective.commands.iter().filter_map(|(id, config)| {
+                let connector_spec = self.new_command(&ModuleSpecification::command(id, &config.version), &config.settings)?.get_connector_spec()?;
+                Some((format!("command '{}'", id), connector_spec, config.connector.clone()))
+            });
+
+            for (source, connector_spec, instance_id) in monitor_requirements.chain(command_requirements) {
+                if self.get_latest_version_for_connector(&connector_spec.id).is_none() {
+                    return Err(format!("Host '{}' has no connector '{}' required by {}.", host_id, connector_spec.id, source));
+                }
+
+                if let Some(instance_id) = instance_id {
+                    let module_id = host_config.effective.connectors[&instance_id].module_id(&instance_id);
+                    if module_id != connector_spec.id {
+                        return Err(format!("Connector '{}' for host '{}' is not a '{}' connector required by {}.",
+                                           instance_id, host_id, connector_spec.id, source));
+                    }
+                }
+            }
+
+            let monitor_settings = host_config.effective.monitors.iter()
+                .map(|(id, config)| (ModuleSpecification::monitor(id, &config.version), &config.settings));
+            let command_settings = host_config.effective.commands.iter()
+                .map(|(id, config)| (ModuleSpecification::command(id, &config.version), &config.settings));
+            let connector_settings = host_config.effective.connectors.iter()
+                .map(|(id, config)| (ModuleSpecification::connector(&config.module_id(id), "latest"), &config.settings));
+
+            for (module_spec, settings) in monitor_settings.chain(command_settings).chain(connector_settings) {
+                if let Some(metadata) = self.get_module_metadata(&module_spec) {
+                    Self::validate_settings(&metadata, settings)
+                        .map_err(|error| format!("Host '{}': {}", host_id, error))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that settings only contain keys declared by the module.
+    /// Secret variants (`<setting>_secret_ref` and `<setting>_secret_command`) of declared settings are also accepted.
+    pub fn validate_settings(metadata: &Metadata, settings: &HashMap<String, String>) -> Result<(), LkError> {
+        let mut unknown_keys = settings.keys().filter(|key| {
+            let base_key = key.strip_suffix("_secret_ref").or_else(|| key.strip_suffix("_secret_command")).unwrap_or(key.as_str());
+            !metadata.settings.contains_key(*key) && !metadata.settings.contains_key(base_key)
+        }).cloned().collect::<Vec<_>>();
+
+        if unknown_keys.is_empty() {
+            Ok(())
+        }
+        else {
+            unknown_keys.sort();
+            let mut valid_keys = metadata.settings.keys().cloned().collect::<Vec<_>>();
+            valid_keys.sort();
+
+            Err(LkError::new(ErrorKind::InvalidConfig, format!(
+                "Unknown settings for {} module '{}': {}. Valid settings are: {}",
+                metadata.module_spec.module_type.to_string().to_lowercase(),
+                metadata.module_spec.id,
+                unknown_keys.join(", "),
+                if valid_keys.is_empty() { String::from("none") } else { valid_keys.join(", ") },
+            )))
+        }
+    }
+
+    /// Version "latest" resolves to the newest registered version.
+    fn get_module_metadata(&self, module_spec: &ModuleSpecification) -> Option<Metadata> {
+        let module_spec = self.resolve_module_spec(module_spec).ok()?;
+        self.get_module_metadatas().into_iter().find(|metadata| metadata.module_spec == module_spec)
+    }
+
     pub fn get_monitoring_module_info(&self) -> String {
         let mut documentation = String::from("Monitoring modules:\n");
 
@@ -198,7 +366,7 @@ impl ModuleFactory {
 
             documentation.push_str(&format!("  description: {}\n", &metadata.description.replace("    ", "  ")));
             documentation.push_str("  settings:\n");
-            if metadata.settings.is_empty() {
+            if !metadata.settings.is_empty() {
                 for (key, value) in metadata.settings.iter() {
                     documentation.push_str(&format!("    {}: {}\n", key, value));
                 }
@@ -262,7 +430,7 @@ impl ModuleFactory {
             documentation.push_str(&format!("{}:\n", metadata.module_spec));
             documentation.push_str(&format!("  description: {}\n", &metadata.description));
             documentation.push_str("  settings:\n");
-            if metadata.settings.is_empty() {
+            if !metadata.settings.is_empty() {
                 for (key, value) in metadata.settings.iter() {
                     documentation.push_str(&format!("    {}: {}\n", key, value));
                 }
@@ -282,6 +450,7 @@ impl ModuleFactory {
             (connection::HttpJwt::get_metadata(), connection::HttpJwt::new_connection_module),
             (connection::LocalCommand::get_metadata(), connection::LocalCommand::new_connection_module),
             (connection::Tcp::get_metadata(), connection::Tcp::new_connection_module),
+            (connection::DockerApi::get_metadata(), connection::DockerApi::new_connection_module),
         ];
 
         // Monitoring modules.
@@ -293,14 +462,20 @@ impl ModuleFactory {
             (monitoring::linux::Uptime::get_metadata(), monitoring::linux::Uptime::new_monitoring_module),
             (monitoring::linux::Load::get_metadata(), monitoring::linux::Load::new_monitoring_module),
             (monitoring::linux::Ram::get_metadata(), monitoring::linux::Ram::new_monitoring_module),
+            (monitoring::linux::Swap::get_metadata(), monitoring::linux::Swap::new_monitoring_module),
+            (monitoring::linux::ProcessWatch::get_metadata(), monitoring::linux::ProcessWatch::new_monitoring_module),
+            (monitoring::linux::Cron::get_metadata(), monitoring::linux::Cron::new_monitoring_module),
             (monitoring::linux::Who::get_metadata(), monitoring::linux::Who::new_monitoring_module),
             (monitoring::nixos::RebuildGenerations::get_metadata(), monitoring::nixos::RebuildGenerations::new_monitoring_module),
             (monitoring::storage::Filesystem::get_metadata(), monitoring::storage::Filesystem::new_monitoring_module),
             (monitoring::storage::Cryptsetup::get_metadata(),  monitoring::storage::Cryptsetup::new_monitoring_module),
+            (monitoring::storage::Btrfs::get_metadata(), monitoring::storage::Btrfs::new_monitoring_module),
             (monitoring::storage::lvm::LogicalVolume::get_metadata(), monitoring::storage::lvm::LogicalVolume::new_monitoring_module),
             (monitoring::storage::lvm::VolumeGroup::get_metadata(), monitoring::storage::lvm::VolumeGroup::new_monitoring_module),
             (monitoring::storage::lvm::PhysicalVolume::get_metadata(), monitoring::storage::lvm::PhysicalVolume::new_monitoring_module),
             (monitoring::systemd::Service::get_metadata(), monitoring::systemd::Service::new_monitoring_module),
+            (monitoring::systemd::Timer::get_metadata(), monitoring::systemd::Timer::new_monitoring_module),
+            (monitoring::hardware::Sensors::get_metadata(), monitoring::hardware::Sensors::new_monitoring_module),
             (monitoring::network::Oping::get_metadata(), monitoring::network::Oping::new_monitoring_module),
             (monitoring::network::Ping::get_metadata(), monitoring::network::Ping::new_monitoring_module),
             (monitoring::network::Ssh::get_metadata(), monitoring::network::Ssh::new_monitoring_module),
@@ -310,6 +485,10 @@ impl ModuleFactory {
             (monitoring::docker::Compose::get_metadata(), monitoring::docker::Compose::new_monitoring_module),
             (monitoring::docker::Containers::get_metadata(), monitoring::docker::Containers::new_monitoring_module),
             (monitoring::docker::Images::get_metadata(), monitoring::docker::Images::new_monitoring_module),
+            (monitoring::docker::Stats::get_metadata(), monitoring::docker::Stats::new_monitoring_module),
+            (monitoring::meta::Aggregate::get_metadata(), monitoring::meta::Aggregate::new_monitoring_module),
+            (monitoring::security::AuthorizedKeys::get_metadata(), monitoring::security::AuthorizedKeys::new_monitoring_module),
+            (monitoring::security::FileIntegrity::get_metadata(), monitoring::security::FileIntegrity::new_monitoring_module),
 
             // Monitoring extension modules.
             (monitoring::docker::ImageUpdates::get_metadata(), monitoring::docker::ImageUpdates::new_monitoring_module),
@@ -326,10 +505,14 @@ impl ModuleFactory {
             (command::linux::packages::UpdateAll::get_metadata(), command::linux::packages::UpdateAll::new_command_module),
             (command::linux::packages::Refresh::get_metadata(), command::linux::packages::Refresh::new_command_module),
             (command::linux::packages::Logs::get_metadata(), command::linux::packages::Logs::new_command_module),
+            (command::linux::packages::Install::get_metadata(), command::linux::packages::Install::new_command_module),
+            (command::linux::packages::Uninstall::get_metadata(), command::linux::packages::Uninstall::new_command_module),
+            (command::linux::packages::Search::get_metadata(), command::linux::packages::Search::new_command_module),
             (command::nixos::RebuildDryrun::get_metadata(), command::nixos::RebuildDryrun::new_command_module),
             (command::nixos::RebuildSwitch::get_metadata(), command::nixos::RebuildSwitch::new_command_module),
             (command::nixos::RebuildBoot::get_metadata(), command::nixos::RebuildBoot::new_command_module),
             (command::nixos::RebuildRollback::get_metadata(), command::nixos::RebuildRollback::new_command_module),
+            (command::nixos::SwitchGeneration::get_metadata(), command::nixos::SwitchGeneration::new_command_module),
             (command::nixos::CollectGarbage::get_metadata(), command::nixos::CollectGarbage::new_command_module),
             (command::nixos::ChannelUpdate::get_metadata(), command::nixos::ChannelUpdate::new_command_module),
             (command::storage::FileSpaceUsage::get_metadata(), command::storage::FileSpaceUsage::new_command_module),
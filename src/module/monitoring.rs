@@ -26,4 +26,10 @@ pub mod docker;
 
 pub mod storage;
 
-pub mod nixos;
\ No newline at end of file
+pub mod nixos;
+
+pub mod hardware;
+
+pub mod meta;
+
+pub mod security;
\ No newline at end of file
@@ -4,6 +4,7 @@
  */
 
 use std::fmt;
+use chrono::{DateTime, Utc};
 use serde_derive::{Serialize, Deserialize};
 use crate::enums::Criticality;
 
@@ -14,6 +15,12 @@ pub struct DataPoint {
     pub value: String,
     /// Pure integer value, currently used with charts and progress bars (DisplayStyle::ProgressBar).
     pub value_float: f32,
+    /// Optional measured value for monitors that produce measurements. Unlike `value`, this doesn't need to be parsed.
+    #[serde(default)]
+    pub numeric_value: Option<f64>,
+    /// Unit of `numeric_value`, e.g. "%", "B" or "°C".
+    #[serde(default)]
+    pub unit: String,
     /// Optional. Used with multivalue-data and usually filled programmatically.
     pub label: String,
     /// Optional description for label.
@@ -27,6 +34,9 @@ pub struct DataPoint {
     // TODO: rename to children?
     pub multivalue: Vec<DataPoint>,
     pub criticality: Criticality,
+    /// When the data point was produced. Can be used to tell how stale the value is.
+    #[serde(default)]
+    pub time: DateTime<Utc>,
 }
 
 impl DataPoint {
@@ -116,6 +126,14 @@ impl DataPoint {
         self
     }
 
+    /// Sets the measured value and its unit. Also sets `value_float` so the value can be used with charts.
+    pub fn with_numeric_value(mut self, value: f64, unit: &str) -> Self {
+        self.numeric_value = Some(value);
+        self.unit = unit.to_string();
+        self.value_float = value as f32;
+        self
+    }
+
     pub fn with_tags(mut self, tags: Vec<String>) -> Self {
         self.tags = tags;
         self
@@ -125,6 +143,14 @@ impl DataPoint {
         self.value == "_platform_info"
     }
 
+    /// Sorts children recursively by label. Sort is stable so rows with the same label keep their order.
+    pub fn sort_multivalue_by_label(&mut self) {
+        self.multivalue.sort_by(|left, right| left.label.cmp(&right.label));
+        for child in self.multivalue.iter_mut() {
+            child.sort_multivalue_by_label();
+        }
+    }
+
     pub fn update_criticality_from_children(&mut self) {
         if let Some(most_critical) = self.multivalue.iter().max_by_key(|datapoint| datapoint.criticality) {
             self.criticality = std::cmp::max(self.criticality, most_critical.criticality);
@@ -143,6 +169,9 @@ impl Default for DataPoint {
             multivalue: Vec::new(),
             criticality: Criticality::Normal,
             value_float: 0.0,
+            numeric_value: None,
+            unit: String::new(),
+            time: Utc::now(),
         }
     }
 }
@@ -14,4 +14,38 @@ pub mod images;
 pub use images::Images;
 
 pub mod image_updates;
-pub use image_updates::ImageUpdates;
\ No newline at end of file
+pub use image_updates::ImageUpdates;
+
+pub mod stats;
+pub use stats::Stats;
+
+use crate::host::{Host, HostSetting};
+use crate::module::platform_info::ContainerRuntime;
+
+const DOCKER_SOCKET_PATH: &str = "/var/run/docker.sock";
+const PODMAN_SOCKET_PATH: &str = "/run/podman/podman.sock";
+
+/// Podman is used if forced with the host setting or if it was detected instead of Docker.
+fn uses_podman(host: &Host) -> bool {
+    host.settings.contains(&HostSetting::UsePodman) || host.platform.container_runtime == ContainerRuntime::Podman
+}
+
+/// Podman provides a Docker-compatible API, so only the socket differs.
+pub fn get_api_socket_path(host: &Host) -> &'static str {
+    if uses_podman(host) {
+        PODMAN_SOCKET_PATH
+    }
+    else {
+        DOCKER_SOCKET_PATH
+    }
+}
+
+/// Podman CLI is compatible with the Docker CLI for the commands used (including `compose`).
+pub fn get_cli_command(host: &Host) -> &'static str {
+    if uses_podman(host) {
+        "podman"
+    }
+    else {
+        "docker"
+    }
+}
@@ -16,6 +16,7 @@ use lightkeeper_module::monitoring_module;
 use crate::module::monitoring::docker::containers::ContainerDetails;
 use crate::module::*;
 use crate::module::monitoring::*;
+use crate::module::monitoring::docker::get_api_socket_path;
 use crate::utils::ShellCommand;
 
 #[monitoring_module(
@@ -23,31 +24,44 @@ use crate::utils::ShellCommand;
     version="0.0.1",
     description="Provides information about docker-compose projects.",
     settings={
-        compose_file_name => "Name of the docker-compose file. Default: docker-compose.yml",
+        compose_file_name => "Name of the compose file. Only used if containers don't have the config_files label (older Docker Compose versions). Default: docker-compose.yml",
         working_dir => "This is only needed with older docker-compose versions that don't include working_dir label on the container,
  so th  is can be used instead. Should be the parent directory of project directories. Currently, a single directory is supported.",
         local_image_prefix => "Image name prefix indicating that image was built locally. Default: localhost",
+        use_docker_api => "Use the docker connector to query Docker API directly instead of running curl over SSH. Default: false.",
     }
 )]
 pub struct Compose {
     pub compose_file_name: String,
     pub working_dir: String, 
     pub local_image_prefix: String,
+    pub use_docker_api: bool,
 }
 
 impl Module for Compose {
     fn new(settings: &HashMap<String, String>) -> Self {
         Compose {
-            compose_file_name: String::from("docker-compose.yml"),
+            compose_file_name: settings.get("compose_file_name").unwrap_or(&String::from("docker-compose.yml")).clone(),
             working_dir: settings.get("working_dir").unwrap_or(&String::new()).clone(),
             local_image_prefix: settings.get("local_image_prefix").unwrap_or(&String::from("localhost")).clone(),
+            use_docker_api: settings.get("use_docker_api").and_then(|value| value.parse().ok()).unwrap_or(false),
         }
     }
 }
 
 impl MonitoringModule for Compose {
     fn get_connector_spec(&self) -> Option<ModuleSpecification> {
-        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+        if self.use_docker_api {
+            Some(ModuleSpecification::connector("docker", "0.0.1"))
+        }
+        else {
+            Some(ModuleSpecification::connector("ssh", "0.0.1"))
+        }
+    }
+
+    fn get_cache_key(&self) -> Option<String> {
+        // Container list is shared by docker-containers and docker-compose.
+        Some(String::from("docker-container-list"))
     }
 
     fn get_display_options(&self) -> frontend::DisplayOptions {
@@ -61,13 +75,17 @@ impl MonitoringModule for Compose {
     }
 
     fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
+        if self.use_docker_api {
+            return Ok(String::from("/containers/json?all=true"));
+        }
+
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
 
         if host.platform.os == platform_info::OperatingSystem::Linux {
             // Docker API is much better suited for this than using the docker-compose CLI. More effective too.
             // TODO: find down-status compose-projects with find-command?
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "http://localhost/containers/json?all=true"]);
+            command.arguments(vec!["curl", "-s", "--unix-socket", get_api_socket_path(&host), "http://localhost/containers/json?all=true"]);
             Ok(command.to_string())
         }
         else {
@@ -121,8 +139,14 @@ impl MonitoringModule for Compose {
             };
 
             let service = container.labels.get("com.docker.compose.service").unwrap().clone();
+
+            // Label contains the files that were actually used (comma-separated if there are overrides), so compose.yaml etc. work too.
+            let compose_file_name = container.labels.get("com.docker.compose.project.config_files")
+                                                    .and_then(|files| files.split(',').next())
+                                                    .filter(|file| !file.is_empty())
+                                                    .unwrap_or(self.compose_file_name.as_str());
             let compose_file = Path::new(&working_dir)
-                                    .join(&self.compose_file_name).to_string_lossy().to_string();
+                                    .join(compose_file_name).to_string_lossy().to_string();
 
             let mut data_point = DataPoint::labeled_value_with_level(service.clone(), container.status.to_string(), container.get_criticality());
 
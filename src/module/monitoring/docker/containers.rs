@@ -15,6 +15,7 @@ use crate::{ Host, enums::Criticality, frontend };
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
+use crate::module::monitoring::docker::get_api_socket_path;
 use crate::utils::ShellCommand;
 
 #[monitoring_module(
@@ -22,25 +23,38 @@ use crate::utils::ShellCommand;
     version="0.0.1",
     description="Provides information about Docker containers.",
     settings={
-      ignore_compose_managed => "Ignore containers that are managed by docker-compose. Default: true."
+      ignore_compose_managed => "Ignore containers that are managed by docker-compose. Default: true.",
+      use_docker_api => "Use the docker connector to query Docker API directly instead of running curl over SSH. Default: false."
     }
 )]
 pub struct Containers {
     // Ignore containers that are managed by docker-compose.
     ignore_compose_managed: bool,
+    use_docker_api: bool,
 }
 
 impl Module for Containers {
     fn new(settings: &HashMap<String, String>) -> Self {
         Containers {
             ignore_compose_managed: settings.get("ignore_compose_managed").and_then(|value| Some(value == "true")).unwrap_or(true),
+            use_docker_api: settings.get("use_docker_api").and_then(|value| value.parse().ok()).unwrap_or(false),
         }
     }
 }
 
 impl MonitoringModule for Containers {
     fn get_connector_spec(&self) -> Option<ModuleSpecification> {
-        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+        if self.use_docker_api {
+            Some(ModuleSpecification::connector("docker", "0.0.1"))
+        }
+        else {
+            Some(ModuleSpecification::connector("ssh", "0.0.1"))
+        }
+    }
+
+    fn get_cache_key(&self) -> Option<String> {
+        // Container list is shared by docker-containers and docker-compose.
+        Some(String::from("docker-container-list"))
     }
 
     fn get_display_options(&self) -> frontend::DisplayOptions {
@@ -49,17 +63,21 @@ impl MonitoringModule for Containers {
             display_text: String::from("Containers"),
             category: String::from("docker-containers"),
             use_multivalue: true,
+            sort_multivalue: true,
             ..Default::default()
         }
     }
 
     fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
+        if self.use_docker_api {
+            return Ok(String::from("/containers/json?all=true"));
+        }
+
         let mut command = ShellCommand::new();
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
 
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            // TODO: somehow connect directly to the unix socket instead of using curl?
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "http://localhost/containers/json?all=true"]);
+            command.arguments(vec!["curl", "-s", "--unix-socket", get_api_socket_path(&host), "http://localhost/containers/json?all=true"]);
             Ok(command.to_string())
         }
         else {
@@ -17,6 +17,7 @@ use crate::{ Host, frontend };
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
+use crate::module::monitoring::docker::get_api_socket_path;
 use crate::utils::ShellCommand;
 
 
@@ -60,6 +61,7 @@ impl MonitoringModule for Images {
             display_text: String::from("Docker images"),
             category: String::from("docker-images"),
             use_multivalue: true,
+            sort_multivalue: true,
             ..Default::default()
         }
     }
@@ -69,7 +71,7 @@ impl MonitoringModule for Images {
         command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
 
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec!["curl", "-s", "--unix-socket", "/var/run/docker.sock", "http://localhost/images/json"]);
+            command.arguments(vec!["curl", "-s", "--unix-socket", get_api_socket_path(&host), "http://localhost/images/json"]);
             Ok(command.to_string())
         }
         else {
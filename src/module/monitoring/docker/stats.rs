@@ -0,0 +1,240 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+
+use std::collections::HashMap;
+use serde_derive::Deserialize;
+use serde_json;
+
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::{ Host, enums::Criticality, frontend };
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+use crate::module::monitoring::docker::containers::cleanup_name;
+use crate::module::monitoring::docker::get_api_socket_path;
+use crate::utils::ShellCommand;
+
+#[monitoring_module(
+    name="docker-stats",
+    version="0.0.1",
+    description="Provides CPU and memory usage of running Docker containers. Uses the container list from docker-containers.",
+    settings={
+      cpu_warning_threshold => "CPU usage warning threshold in percent of all CPUs. Default: 80",
+      cpu_error_threshold => "CPU usage error threshold in percent of all CPUs. Default: 95",
+      memory_warning_threshold => "Memory usage warning threshold in percent of the container's memory limit. Default: 80",
+      memory_error_threshold => "Memory usage error threshold in percent of the container's memory limit. Default: 95",
+      use_docker_api => "Use the docker connector to query Docker API directly instead of running curl over SSH. Default: false."
+    }
+)]
+pub struct Stats {
+    cpu_threshold_warning: f64,
+    cpu_threshold_error: f64,
+    memory_threshold_warning: f64,
+    memory_threshold_error: f64,
+    use_docker_api: bool,
+}
+
+impl Module for Stats {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Stats {
+            cpu_threshold_warning: settings.get("cpu_warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+            cpu_threshold_error: settings.get("cpu_error_threshold").and_then(|value| value.parse().ok()).unwrap_or(95.0),
+            memory_threshold_warning: settings.get("memory_warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+            memory_threshold_error: settings.get("memory_error_threshold").and_then(|value| value.parse().ok()).unwrap_or(95.0),
+            use_docker_api: settings.get("use_docker_api").and_then(|value| value.parse().ok()).unwrap_or(false),
+        }
+    }
+}
+
+impl MonitoringModule for Stats {
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        if self.use_docker_api {
+            Some(ModuleSpecification::connector("docker", "0.0.1"))
+        }
+        else {
+            Some(ModuleSpecification::connector("ssh", "0.0.1"))
+        }
+    }
+
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("Resource usage"),
+            category: String::from("docker-containers"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_monitor_dependencies(&self) -> Vec<String> {
+        vec![String::from("docker-containers")]
+    }
+
+    /// Sends one stats request per running container. Containers are taken from the docker-containers result.
+    /// When both are refreshed together, this waits for docker-containers to complete first (see `get_monitor_dependencies`).
+    fn get_connector_messages(&self, host: Host, parent_result: DataPoint) -> Result<Vec<String>, LkError> {
+        if host.platform.os != platform_info::OperatingSystem::Linux {
+            return Err(LkError::unsupported_platform());
+        }
+
+        let running_containers = parent_result.multivalue.first()
+            .map(|containers| containers.multivalue.iter()
+                .filter(|container| container.value == "running")
+                .filter_map(|container| container.command_params.first().cloned())
+                .collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let messages = running_containers.iter().map(|container_name| {
+            let path = format!("/containers/{}/stats?stream=false", container_name);
+
+            if self.use_docker_api {
+                path
+            }
+            else {
+                let mut command = ShellCommand::new();
+                command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
+                command.arguments(vec!["curl", "-s", "--unix-socket", get_api_socket_path(&host), &format!("http://localhost{}", path)]);
+                command.to_string()
+            }
+        }).collect();
+
+        Ok(messages)
+    }
+
+    fn process_responses(&self, _host: Host, responses: Vec<ResponseMessage>, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        let mut parent_data = DataPoint::empty();
+
+        for response in responses {
+            if response.return_code == 7 {
+                // Coudldn't connect. Daemon is probably not available.
+                return Ok(DataPoint::value_with_level(String::from("Couldn't connect to Docker daemon."), Criticality::Critical));
+            }
+
+            let stats: ContainerStats = match serde_json::from_str(&response.message) {
+                Ok(stats) => stats,
+                Err(error) => {
+                    // Container may have stopped between the requests.
+                    log::debug!("Failed to parse container stats: {}", error);
+                    continue;
+                }
+            };
+
+            let cpu_percent = stats.cpu_percent();
+            let memory_percent = stats.memory_percent();
+
+            let cpu_criticality = if cpu_percent >= self.cpu_threshold_error {
+                Criticality::Error
+            }
+            else if cpu_percent >= self.cpu_threshold_warning {
+                Criticality::Warning
+            }
+            else {
+                Criticality::Normal
+            };
+
+            let memory_criticality = if memory_percent >= self.memory_threshold_error {
+                Criticality::Error
+            }
+            else if memory_percent >= self.memory_threshold_warning {
+                Criticality::Warning
+            }
+            else {
+                Criticality::Normal
+            };
+
+            let value = format!("CPU {:.1} %, memory {:.0} MiB ({:.1} %)",
+                                cpu_percent, stats.memory_usage() as f64 / 1024.0 / 1024.0, memory_percent);
+            let mut point = DataPoint::value_with_level(value, std::cmp::max(cpu_criticality, memory_criticality));
+            point.label = cleanup_name(&stats.name);
+            point.command_params = vec![cleanup_name(&stats.name)];
+            parent_data.multivalue.push(point);
+        }
+
+        parent_data.multivalue.sort_by(|a, b| a.label.cmp(&b.label));
+        parent_data.criticality = parent_data.multivalue.iter().map(|point| point.criticality).max().unwrap_or(Criticality::Normal);
+        Ok(parent_data)
+    }
+
+    fn process_independent(&self, _host: Host, parent_result: DataPoint) -> Result<DataPoint, String> {
+        // No requests were sent, so either there are no running containers or docker-containers hasn't finished yet.
+        if parent_result.multivalue.is_empty() {
+            Ok(DataPoint::pending())
+        }
+        else {
+            Ok(DataPoint::empty())
+        }
+    }
+}
+
+
+#[derive(Deserialize)]
+struct ContainerStats {
+    #[serde(default)]
+    name: String,
+    cpu_stats: CpuStats,
+    precpu_stats: CpuStats,
+    memory_stats: MemoryStats,
+}
+
+#[derive(Deserialize, Default)]
+struct CpuStats {
+    #[serde(default)]
+    cpu_usage: CpuUsage,
+    #[serde(default)]
+    system_cpu_usage: u64,
+}
+
+#[derive(Deserialize, Default)]
+struct CpuUsage {
+    #[serde(default)]
+    total_usage: u64,
+}
+
+#[derive(Deserialize, Default)]
+struct MemoryStats {
+    #[serde(default)]
+    usage: u64,
+    #[serde(default)]
+    limit: u64,
+    #[serde(default)]
+    stats: HashMap<String, u64>,
+}
+
+impl ContainerStats {
+    /// Unlike `docker stats`, this isn't multiplied by the number of online CPUs, so 100 % means all CPUs are fully used.
+    /// That way the thresholds work the same regardless of the CPU count.
+    fn cpu_percent(&self) -> f64 {
+        let cpu_delta = self.cpu_stats.cpu_usage.total_usage.saturating_sub(self.precpu_stats.cpu_usage.total_usage);
+        let system_delta = self.cpu_stats.system_cpu_usage.saturating_sub(self.precpu_stats.system_cpu_usage);
+
+        if system_delta == 0 || cpu_delta == 0 {
+            0.0
+        }
+        else {
+            cpu_delta as f64 / system_delta as f64 * 100.0
+        }
+    }
+
+    /// Page cache is excluded like in `docker stats`. Key depends on cgroup version.
+    fn memory_usage(&self) -> u64 {
+        let cache = self.memory_stats.stats.get("inactive_file")
+            .or_else(|| self.memory_stats.stats.get("total_inactive_file"))
+            .cloned()
+            .unwrap_or(0);
+
+        self.memory_stats.usage.saturating_sub(cache)
+    }
+
+    fn memory_percent(&self) -> f64 {
+        if self.memory_stats.limit == 0 {
+            0.0
+        }
+        else {
+            self.memory_usage() as f64 / self.memory_stats.limit as f64 * 100.0
+        }
+    }
+}
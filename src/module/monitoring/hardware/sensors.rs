@@ -0,0 +1,150 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use serde_json;
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::utils::ShellCommand;
+use crate::utils::units::convert_temperature;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+/// Fallback for hosts without lm-sensors. Outputs lines like `x86_pkg_temp 45000` (millidegrees).
+const THERMAL_ZONE_SCRIPT: &str = r#"for zone in /sys/class/thermal/thermal_zone*; do echo "$(cat "$zone/type") $(cat "$zone/temp")"; done"#;
+
+#[monitoring_module(
+    name="hardware-sensors",
+    version="0.0.1",
+    description="Shows hardware temperatures using lm-sensors. Falls back to kernel thermal zones if lm-sensors is not installed.",
+    settings={
+        warning_threshold => "Warning threshold in degrees Celsius. Default: 70",
+        critical_threshold => "Critical threshold in degrees Celsius. Default: 90",
+    }
+)]
+pub struct Sensors {
+    threshold_critical: f32,
+    threshold_warning: f32,
+}
+
+impl Module for Sensors {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Sensors {
+            threshold_critical: settings.get("critical_threshold").and_then(|value| value.parse().ok()).unwrap_or(90.0),
+            threshold_warning: settings.get("warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(70.0),
+        }
+    }
+}
+
+impl MonitoringModule for Sensors {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("Temperatures"),
+            category: String::from("host"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_messages(&self, host: Host, _parent_result: DataPoint) -> Result<Vec<String>, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            let command_sensors = ShellCommand::new_from(vec!["sensors", "-j"]);
+            let mut command_thermal = ShellCommand::new_from(vec!["sh", "-c", THERMAL_ZONE_SCRIPT]);
+            command_thermal.ignore_stderr = true;
+            Ok(vec![command_sensors.to_string(), command_thermal.to_string()])
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_responses(&self, _host: Host, responses: Vec<ResponseMessage>, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        let mut result = DataPoint::empty();
+
+        let temperatures = match responses.first() {
+            Some(response) if response.is_success() && !response.message.is_empty() => Self::parse_sensors(&response.message)?,
+            _ => match responses.get(1) {
+                Some(response) if !response.message.is_empty() => Self::parse_thermal_zones(&response.message),
+                _ => Vec::new(),
+            }
+        };
+
+        if temperatures.is_empty() {
+            result.multivalue.push(DataPoint::not_available("No temperature sensors available"));
+            return Ok(result);
+        }
+
+        for (label, temperature) in temperatures {
+            let criticality = if temperature >= self.threshold_critical {
+                Criticality::Critical
+            }
+            else if temperature >= self.threshold_warning {
+                Criticality::Warning
+            }
+            else {
+                Criticality::Normal
+            };
+
+            // Thresholds and the numeric value (used by metrics and charts) are always in Celsius. Only the display string is converted.
+            let (display_temperature, unit) = convert_temperature(temperature);
+            let data_point = DataPoint::labeled_value_with_level(label, format!("{:.1} {}", display_temperature, unit), criticality)
+                                       .with_numeric_value(temperature as f64, "°C");
+            result.multivalue.push(data_point);
+        }
+
+        result.update_criticality_from_children();
+        Ok(result)
+    }
+}
+
+impl Sensors {
+    /// Parses output of `sensors -j`. Chip name is the first level and feature (e.g. "Core 0") is the second level.
+    /// Only temperature inputs (`tempN_input`) are returned.
+    fn parse_sensors(output: &str) -> Result<Vec<(String, f32)>, String> {
+        let chips: serde_json::Map<String, serde_json::Value> = serde_json::from_str(output)
+            .map_err(|error| format!("Failed to parse output: {}", error))?;
+
+        let mut temperatures = Vec::new();
+        for (chip_name, chip) in chips.iter() {
+            let features = match chip.as_object() {
+                Some(features) => features,
+                None => continue,
+            };
+
+            // Values that are not objects, such as "Adapter", are skipped.
+            for (feature_name, feature) in features.iter().filter_map(|(name, value)| value.as_object().map(|object| (name, object))) {
+                let input = feature.iter()
+                    .find(|(key, _)| key.starts_with("temp") && key.ends_with("_input"))
+                    .and_then(|(_, value)| value.as_f64());
+
+                if let Some(temperature) = input {
+                    temperatures.push((format!("{} {}", chip_name, feature_name), temperature as f32));
+                }
+            }
+        }
+
+        Ok(temperatures)
+    }
+
+    fn parse_thermal_zones(output: &str) -> Vec<(String, f32)> {
+        output.lines().filter_map(|line| {
+            let (zone_type, temperature) = line.rsplit_once(' ')?;
+            let millidegrees = temperature.trim().parse::<f32>().ok()?;
+            Some((zone_type.to_string(), millidegrees / 1000.0))
+        }).collect()
+    }
+}
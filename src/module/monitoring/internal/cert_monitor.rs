@@ -19,7 +19,8 @@ use crate::module::monitoring::*;
     description="Monitor TLS (HTTPS) certificate validity.",
     settings={
         threshold_warning => "Warning if certificate age is less than this many days. Default: 21",
-        threshold_error => "Error if certificate age is less than this many days. Default: 14"
+        threshold_error => "Error if certificate age is less than this many days. Default: 14",
+        addresses => "Comma-separated list of addresses to monitor. Default: (empty)."
     }
 )]
 pub struct CertMonitor {
@@ -9,11 +9,14 @@ use std::collections::HashMap;
 use crate::error::LkError;
 use crate::module::connection::ResponseMessage;
 use crate::Host;
-use crate::utils::{VersionNumber, string_manipulation};
+use crate::utils::{ShellCommand, VersionNumber, string_manipulation};
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
 
+/// Docker is preferred if both are available. Podman's socket is only available if podman.socket is enabled.
+const CONTAINER_RUNTIME_SCRIPT: &str = r#"if [ -S /var/run/docker.sock ] || command -v docker >/dev/null; then echo docker; elif [ -S /run/podman/podman.sock ] || command -v podman >/dev/null; then echo podman; fi"#;
+
 #[monitoring_module(
     name="_internal-platform-info-ssh",
     version="0.0.1",
@@ -38,6 +41,7 @@ impl MonitoringModule for PlatformInfoSsh {
         Ok(vec![
             String::from("cat /etc/os-release"),
             String::from("uname -m"),
+            ShellCommand::new_from(vec!["sh", "-c", CONTAINER_RUNTIME_SCRIPT]).to_string(),
         ])
     }
 
@@ -59,12 +63,20 @@ impl MonitoringModule for PlatformInfoSsh {
             return Err(String::from("No response for architecture"));
         }
 
+        // Not required, since most hosts don't have any container runtime.
+        platform.container_runtime = match response.get(2).map(|third| third.message.trim()) {
+            Some("docker") => platform_info::ContainerRuntime::Docker,
+            Some("podman") => platform_info::ContainerRuntime::Podman,
+            _ => platform_info::ContainerRuntime::Unknown,
+        };
+
         // Special kind of datapoint for internal use.
         let mut datapoint = DataPoint::new(String::from("_platform_info"));
         datapoint.multivalue.push(DataPoint::labeled_value(String::from("os"), platform.os.to_string()));
         datapoint.multivalue.push(DataPoint::labeled_value(String::from("os_version"), platform.os_version.to_string()));
         datapoint.multivalue.push(DataPoint::labeled_value(String::from("os_flavor"), platform.os_flavor.to_string()));
         datapoint.multivalue.push(DataPoint::labeled_value(String::from("architecture"), platform.architecture.to_string()));
+        datapoint.multivalue.push(DataPoint::labeled_value(String::from("container_runtime"), platform.container_runtime.to_string()));
         datapoint.multivalue.push(DataPoint::labeled_value(String::from("ip_address"), host.ip_address.to_string()));
         Ok(datapoint)
     }
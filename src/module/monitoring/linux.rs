@@ -22,4 +22,13 @@ pub mod load;
 pub use load::Load;
 
 pub mod ram;
-pub use ram::Ram;
\ No newline at end of file
+pub use ram::Ram;
+
+pub mod cron;
+pub use cron::Cron;
+
+pub mod swap;
+pub use swap::Swap;
+
+pub mod process_watch;
+pub use process_watch::ProcessWatch;
\ No newline at end of file
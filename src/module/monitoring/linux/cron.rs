@@ -0,0 +1,108 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::{
+    Host,
+    frontend,
+};
+
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+use crate::utils::ShellCommand;
+use crate::host::HostSetting;
+
+/// System-wide crontabs include the user field.
+const SYSTEM_CRONTABS: [&str; 2] = ["/etc/crontab", "/etc/cron.d/"];
+/// Prints non-empty, non-comment lines prefixed with the file name, e.g. `/etc/cron.d/certbot:0 */12 * * * root certbot -q renew`.
+/// User crontabs are in /var/spool/cron/crontabs on Debian-based and in /var/spool/cron on RedHat-based distributions.
+const SCRIPT: &str = r#"grep -s -H -v -d skip -e '^[[:space:]]*#' -e '^[[:space:]]*$' /etc/crontab /etc/cron.d/* /var/spool/cron/crontabs/* /var/spool/cron/*"#;
+
+#[monitoring_module(
+    name="cron",
+    version="0.0.1",
+    description="Lists scheduled cron jobs from system and user crontabs. Reading user crontabs requires root privileges.",
+    settings={
+    }
+)]
+pub struct Cron {
+}
+
+impl Module for Cron {
+    fn new(_settings: &HashMap<String, String>) -> Self {
+        Cron {
+        }
+    }
+}
+
+impl MonitoringModule for Cron {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::Text,
+            display_text: String::from("Cron jobs"),
+            category: String::from("host"),
+            use_multivalue: true,
+            use_without_summary: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            let mut command = ShellCommand::new_from(vec!["sh", "-c", SCRIPT]);
+            command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
+            Ok(command.to_string())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
+        let mut result = DataPoint::empty();
+
+        // Grep returns 1 if there are no matching lines and 2 on errors, such as missing files, which are normal here.
+        for line in response.message.lines() {
+            let (file_path, entry) = match line.split_once(':') {
+                Some(parts) => parts,
+                None => continue,
+            };
+
+            let is_system_crontab = SYSTEM_CRONTABS.iter().any(|path| file_path.starts_with(path));
+            let fields = entry.split_whitespace().collect::<Vec<_>>();
+
+            // Special schedules like @daily replace the 5 time fields.
+            let schedule_length = if fields.first().map(|field| field.starts_with('@')).unwrap_or(false) { 1 } else { 5 };
+            let command_start = if is_system_crontab { schedule_length + 1 } else { schedule_length };
+
+            // Environment variable assignments like `SHELL=/bin/sh`.
+            if fields.len() <= command_start || fields[0].contains('=') {
+                continue;
+            }
+
+            let schedule = fields[..schedule_length].join(" ");
+            let user = if is_system_crontab {
+                fields[schedule_length].to_string()
+            }
+            else {
+                file_path.rsplit('/').next().unwrap_or_default().to_string()
+            };
+            let command = fields[command_start..].join(" ");
+
+            let data_point = DataPoint::labeled_value(command, schedule)
+                                       .with_description(format!("{} | {}", user, file_path));
+            result.multivalue.push(data_point);
+        }
+
+        Ok(result)
+    }
+}
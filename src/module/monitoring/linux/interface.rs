@@ -20,7 +20,7 @@ use crate::module::monitoring::*;
 #[monitoring_module(
     name="interface",
     version="0.0.1",
-    description="Provides information about network interfaces.",
+    description="Provides information about network interfaces, their addresses and MTU. Interfaces that are up but have no address are flagged.",
     settings={
         ignored_interfaces => "Comma-separated list of interface names to ignore. Default: empty."
     }
@@ -63,7 +63,8 @@ impl MonitoringModule for Interface {
             Ok(String::from("/sbin/ip -j addr show"))
         }
         else if host.platform.os_flavor == platform_info::Flavor::Alpine {
-            Err(LkError::unsupported_platform())
+            // BusyBox ip doesn't support JSON output.
+            Ok(String::from("ifconfig -a"))
         }
         else if host.platform.os == platform_info::OperatingSystem::Linux {
             Ok(String::from("ip -j addr show"))
@@ -73,14 +74,19 @@ impl MonitoringModule for Interface {
         }
     }
 
-    fn process_response(&self, _host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
+    fn process_response(&self, host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
         if response.is_error() {
             return Err(response.message);
         }
 
         let mut result = DataPoint::empty();
 
-        let mut interfaces: Vec<InterfaceDetails> = serde_json::from_str(response.message.as_str()).map_err(|e| e.to_string())?;
+        let mut interfaces: Vec<InterfaceDetails> = if host.platform.os_flavor == platform_info::Flavor::Alpine {
+            parse_ifconfig(&response.message)
+        }
+        else {
+            serde_json::from_str(response.message.as_str()).map_err(|e| e.to_string())?
+        };
 
         for interface in interfaces.iter_mut() {
             if self.ignored_interfaces.iter().any(|item| interface.ifname.starts_with(item)) {
@@ -88,9 +94,16 @@ impl MonitoringModule for Interface {
             }
 
             let mut data_point = DataPoint::labeled_value(interface.ifname.clone(), interface.operstate.clone().to_lowercase());
+
+            let mut description_parts = Vec::new();
             if let Some(address) = &interface.address {
-                data_point.description = format!("{}", address);
+                description_parts.push(address.clone());
+            }
+            if let Some(mtu) = interface.mtu {
+                description_parts.push(format!("MTU {}", mtu));
             }
+            description_parts.extend(interface.addr_info.iter().map(|address| format!("{}/{}", address.local, address.prefixlen)));
+            data_point.description = description_parts.join(", ");
 
             if interface.flags.contains(&String::from("NO-CARRIER")) {
                 data_point.tags.push(String::from("NO-CARRIER"));
@@ -103,6 +116,11 @@ impl MonitoringModule for Interface {
             if interface.operstate == "DOWN" {
                 data_point.criticality = enums::Criticality::Error;
             }
+            else if interface.operstate == "UP" && interface.addr_info.is_empty() && !interface.flags.contains(&String::from("SLAVE")) {
+                // Bond and bridge members don't need addresses of their own.
+                data_point.criticality = enums::Criticality::Warning;
+                data_point.tags.push(String::from("NO-ADDRESS"));
+            }
             else if interface.operstate == "UP" {
                 data_point.criticality = enums::Criticality::Normal;
             }
@@ -129,8 +147,10 @@ pub struct InterfaceDetails {
     pub ifname: String,
     pub flags: Vec<String>,
     pub operstate: String,
+    #[serde(default)]
     pub link_type: String,
     pub address: Option<String>,
+    pub mtu: Option<u32>,
     pub addr_info: Vec<InterfaceAddress>,
 }
 
@@ -140,3 +160,76 @@ pub struct InterfaceAddress {
     pub local: String,
     pub prefixlen: u8,
 }
+
+/// Parses BusyBox `ifconfig -a` output into the same structure as `ip -j addr show`.
+fn parse_ifconfig(output: &str) -> Vec<InterfaceDetails> {
+    let mut interfaces = Vec::<InterfaceDetails>::new();
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // New interface block starts without indentation.
+        if !line.starts_with(char::is_whitespace) {
+            let mut parts = line.split_whitespace();
+            let ifname = parts.next().unwrap_or_default().to_string();
+            let address = line.split_once("HWaddr ").map(|(_, mac)| mac.trim().to_lowercase());
+
+            interfaces.push(InterfaceDetails {
+                ifname: ifname,
+                flags: Vec::new(),
+                operstate: String::from("DOWN"),
+                link_type: line.split_once("encap:").map(|(_, encap)| encap.split_whitespace().next().unwrap_or_default().to_lowercase()).unwrap_or_default(),
+                address: address,
+                mtu: None,
+                addr_info: Vec::new(),
+            });
+            continue;
+        }
+
+        let interface = match interfaces.last_mut() {
+            Some(interface) => interface,
+            None => continue,
+        };
+
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("inet addr:") {
+            let local = rest.split_whitespace().next().unwrap_or_default().to_string();
+            let prefixlen = rest.split_once("Mask:")
+                .and_then(|(_, mask)| mask.trim().parse::<std::net::Ipv4Addr>().ok())
+                .map(|mask| u32::from(mask).count_ones() as u8)
+                .unwrap_or(32);
+
+            interface.addr_info.push(InterfaceAddress { family: String::from("inet"), local: local, prefixlen: prefixlen });
+        }
+        else if let Some(rest) = line.strip_prefix("inet6 addr:") {
+            let address = rest.split_whitespace().next().unwrap_or_default();
+            let (local, prefixlen) = address.split_once('/').unwrap_or((address, "128"));
+
+            interface.addr_info.push(InterfaceAddress {
+                family: String::from("inet6"),
+                local: local.to_string(),
+                prefixlen: prefixlen.parse().unwrap_or(128),
+            });
+        }
+        else if line.contains("MTU:") {
+            interface.flags = line.split_whitespace().take_while(|flag| !flag.contains(':')).map(|flag| flag.to_string()).collect();
+            interface.mtu = line.split_once("MTU:").and_then(|(_, mtu)| mtu.split_whitespace().next()).and_then(|mtu| mtu.parse().ok());
+
+            // Same states as reported by iproute2.
+            if interface.flags.contains(&String::from("LOOPBACK")) {
+                interface.operstate = String::from("UNKNOWN");
+            }
+            else if interface.flags.contains(&String::from("RUNNING")) {
+                interface.operstate = String::from("UP");
+            }
+            else if interface.flags.contains(&String::from("UP")) {
+                // Administratively up, but no carrier.
+                interface.flags.push(String::from("NO-CARRIER"));
+            }
+        }
+    }
+
+    interfaces
+}
@@ -4,6 +4,7 @@
  */
 
 
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use lightkeeper_module::monitoring_module;
@@ -16,11 +17,12 @@ use crate::{
 };
 use crate::module::*;
 use crate::module::monitoring::*;
+use crate::utils::ShellCommand;
 
 #[monitoring_module(
     name="kernel",
     version="0.0.1",
-    description="Provides kernel version and architecture information.",
+    description="Provides kernel version and architecture information. Warns if a newer kernel is installed and a reboot is needed.",
 )]
 pub struct Kernel;
 
@@ -45,20 +47,73 @@ impl MonitoringModule for Kernel {
         Some(ModuleSpecification::connector("ssh", "0.0.1"))
     }
 
-    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
-        if host.platform.os == platform_info::OperatingSystem::Linux {
-            Ok(String::from("uname -r -m"))
+    fn get_connector_messages(&self, host: Host, _result: DataPoint) -> Result<Vec<String>, LkError> {
+        if host.platform.os != platform_info::OperatingSystem::Linux {
+            return Err(LkError::unsupported_platform());
         }
-        else {
-            Err(LkError::unsupported_platform())
+
+        let mut messages = vec![String::from("uname -r -m")];
+
+        match host.platform.os_flavor {
+            platform_info::Flavor::Debian | platform_info::Flavor::Ubuntu => {
+                messages.push(ShellCommand::new_from(vec!["dpkg-query", "-W", "-f", "${Status} ${Package}\\n", "linux-image-[0-9]*"]).to_string());
+            },
+            platform_info::Flavor::RedHat | platform_info::Flavor::CentOS | platform_info::Flavor::Fedora => {
+                messages.push(ShellCommand::new_from(vec!["rpm", "-q", "kernel", "--qf", "%{VERSION}-%{RELEASE}.%{ARCH}\\n"]).to_string());
+            },
+            // Installed kernels can't be reliably detected.
+            _ => {},
         }
+
+        Ok(messages)
     }
 
-    fn process_response(&self, _host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
-        if response.is_error() {
-            return Ok(DataPoint::value_with_level(response.message, Criticality::Critical))
+    fn process_responses(&self, host: Host, responses: Vec<ResponseMessage>, _result: DataPoint) -> Result<DataPoint, String> {
+        let running_response = match responses.first() {
+            Some(response) => response,
+            None => return Ok(DataPoint::empty()),
+        };
+
+        if running_response.is_error() {
+            return Ok(DataPoint::value_with_level(running_response.message.clone(), Criticality::Critical))
         }
 
-        Ok(DataPoint::new(response.message.replace(" ", " (") + ")"))
+        let mut result = DataPoint::new(running_response.message.replace(" ", " (") + ")");
+        let running_release = running_response.message.split_whitespace().next().unwrap_or_default();
+
+        let installed_releases = match responses.get(1) {
+            Some(response) if response.is_success() => {
+                if host.platform.os_flavor == platform_info::Flavor::Debian || host.platform.os_flavor == platform_info::Flavor::Ubuntu {
+                    response.message.lines()
+                        .filter(|line| line.starts_with("install ok installed "))
+                        .filter_map(|line| line.split_whitespace().last())
+                        .filter_map(|package| package.strip_prefix("linux-image-"))
+                        .map(|release| release.to_string())
+                        .collect::<Vec<_>>()
+                }
+                else {
+                    response.message.lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect()
+                }
+            },
+            _ => Vec::new(),
+        };
+
+        if let Some(newest_release) = installed_releases.iter().max_by(|a, b| compare_releases(a, b)) {
+            if compare_releases(newest_release, running_release) == Ordering::Greater {
+                result.criticality = Criticality::Warning;
+                result.description = format!("Reboot required to activate kernel {}", newest_release);
+            }
+        }
+
+        Ok(result)
     }
-}
\ No newline at end of file
+}
+
+/// Compares kernel release strings (e.g. "6.1.0-18-amd64") by their numeric parts.
+fn compare_releases(a: &str, b: &str) -> Ordering {
+    let numbers = |release: &str| release.split(|character: char| !character.is_ascii_digit())
+                                         .filter(|part| !part.is_empty())
+                                         .map(|part| part.parse::<u64>().unwrap_or_default())
+                                         .collect::<Vec<_>>();
+    numbers(a).cmp(&numbers(b))
+}
@@ -40,7 +40,7 @@ impl Module for Load {
 impl MonitoringModule for Load {
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
-            display_style: frontend::DisplayStyle::Text,
+            display_style: frontend::DisplayStyle::Sparkline,
             display_text: String::from("Loads"),
             category: String::from("host"),
             use_with_charts: true,
@@ -72,8 +72,8 @@ impl MonitoringModule for Load {
 
         let loads = parts[1].split(", ").collect::<Vec<&str>>();
         if loads.len() == 3 {
-            let load_1 = loads[0].replace(",", ".").parse::<f32>().unwrap_or(0.0);
-            data_point.value_float = load_1;
+            let load_1 = loads[0].replace(",", ".").parse::<f64>().unwrap_or(0.0);
+            data_point = data_point.with_numeric_value(load_1, "");
         }
 
         Ok(data_point)
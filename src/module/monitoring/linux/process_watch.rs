@@ -0,0 +1,127 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+
+use std::collections::HashMap;
+use regex::Regex;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::utils::{format_bytes, ShellCommand};
+use crate::module::platform_info;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+#[monitoring_module(
+    name="process-watch",
+    version="0.0.1",
+    description="Reports whether specific processes are running and how much CPU and memory they use.",
+    settings={
+        processes => "Comma-separated list of process names to watch. Names are regular expressions matched against the whole process name (truncated to 15 characters by the kernel), e.g. \"nginx,postgres,php-fpm.*\". Default: (empty).",
+        required => "Missing processes are critical. If false, they are only shown. Default: true",
+    }
+)]
+pub struct ProcessWatch {
+    /// Original pattern and the compiled regex.
+    processes: Vec<(String, Regex)>,
+    required: bool,
+}
+
+impl Module for ProcessWatch {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        ProcessWatch {
+            processes: settings.get("processes").unwrap_or(&String::new())
+                               .split(',')
+                               .map(|value| value.trim())
+                               .filter(|value| !value.is_empty())
+                               .filter_map(|value| match Regex::new(&format!("^(?:{})$", value)) {
+                                   Ok(regex) => Some((value.to_string(), regex)),
+                                   Err(error) => {
+                                       log::error!("Invalid process pattern \"{}\": {}", value, error);
+                                       None
+                                   }
+                               })
+                               .collect(),
+            required: settings.get("required").and_then(|value| value.parse().ok()).unwrap_or(true),
+        }
+    }
+}
+
+impl MonitoringModule for ProcessWatch {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("Processes"),
+            category: String::from("host"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
+        // BusyBox ps doesn't support the needed output fields.
+        if host.platform.os == platform_info::OperatingSystem::Linux && host.platform.os_flavor != platform_info::Flavor::Alpine {
+            // Name is last since it can contain spaces.
+            Ok(ShellCommand::new_from(vec!["ps", "-e", "-o", "pcpu=,rss=,comm="]).to_string())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        if response.is_error() {
+            return Err(response.message);
+        }
+
+        let processes = response.message.lines().filter_map(|line| {
+            let (cpu_percent, rest) = line.trim().split_once(char::is_whitespace)?;
+            let (rss, name) = rest.trim_start().split_once(char::is_whitespace)?;
+            // CPU usage is the average over the process lifetime, as reported by ps. RSS is in kilobytes.
+            Some((name.trim(), cpu_percent.parse::<f32>().ok()?, rss.parse::<u64>().ok()?))
+        }).collect::<Vec<_>>();
+
+        let mut result = DataPoint::empty();
+
+        for (pattern, regex) in self.processes.iter() {
+            let matching = processes.iter().filter(|(name, _, _)| regex.is_match(name)).collect::<Vec<_>>();
+
+            let data_point = if matching.is_empty() {
+                let criticality = if self.required { Criticality::Critical } else { Criticality::Ignore };
+                DataPoint::labeled_value_with_level(pattern.clone(), String::from("not running"), criticality)
+            }
+            else {
+                // Multiple PIDs (e.g. worker processes) are aggregated.
+                let cpu_total = matching.iter().map(|(_, cpu_percent, _)| cpu_percent).sum::<f32>();
+                let rss_total = matching.iter().map(|(_, _, rss)| rss).sum::<u64>() * 1024;
+
+                let value = if matching.len() == 1 {
+                    String::from("running")
+                }
+                else {
+                    format!("running ({} processes)", matching.len())
+                };
+
+                let mut data_point = DataPoint::labeled_value_with_level(pattern.clone(), value, Criticality::Normal);
+                data_point.description = format!("CPU {:.1} %, memory {}", cpu_total, format_bytes(rss_total));
+                data_point
+            };
+
+            result.multivalue.push(data_point);
+        }
+
+        result.update_criticality_from_children();
+        Ok(result)
+    }
+}
@@ -8,6 +8,7 @@ use std::collections::HashMap;
 use crate::enums::Criticality;
 use crate::error::LkError;
 use crate::module::connection::ResponseMessage;
+use crate::utils::format_bytes;
 use crate::module::platform_info;
 use crate::{
     Host,
@@ -50,6 +51,8 @@ impl MonitoringModule for Ram {
             display_text: String::from("RAM usage"),
             category: String::from("host"),
             unit: String::from("%"),
+            value_min: 0.0,
+            value_max: 100.0,
             use_with_charts: true,
             ..Default::default()
         }
@@ -59,9 +62,14 @@ impl MonitoringModule for Ram {
         Some(ModuleSpecification::connector("ssh", "0.0.1"))
     }
 
+    fn get_cache_key(&self) -> Option<String> {
+        // Memory figures are shared by ram and swap.
+        Some(String::from("free"))
+    }
+
     fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            Ok(String::from("free -m"))
+            Ok(String::from("free -b"))
         }
         else {
             Err(LkError::unsupported_platform())
@@ -76,12 +84,12 @@ impl MonitoringModule for Ram {
         let line = response.message.lines().filter(|line| line.contains("Mem:")).collect::<Vec<&str>>();
         let parts = line[0].split_whitespace().collect::<Vec<&str>>();
 
-        let total = parts[1].parse::<u32>().map_err(|_| String::from("Unsupported platform"))?;
+        let total = parts[1].parse::<u64>().map_err(|_| String::from("Unsupported platform"))?;
         // used
         // free
         // shared
         // cache
-        let available = parts[6].parse::<u32>().map_err(|_| String::from("Unsupported platform"))?;
+        let available = parts[6].parse::<u64>().map_err(|_| String::from("Unsupported platform"))?;
 
         let usage_percent = (total - available) as f32 / total as f32 * 100.0;
 
@@ -98,9 +106,9 @@ impl MonitoringModule for Ram {
             Criticality::Normal
         };
 
-        let value = format!("{:.0} % of {} M", usage_percent, total);
-        let mut data_point = DataPoint::value_with_level(value, criticality);
-        data_point.value_float = usage_percent;
+        let value = format!("{:.0} % of {}", usage_percent, format_bytes(total));
+        let data_point = DataPoint::value_with_level(value, criticality)
+                                   .with_numeric_value(usage_percent as f64, "%");
         Ok(data_point)
     }
 }
\ No newline at end of file
@@ -0,0 +1,161 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::utils::{format_bytes, ShellCommand};
+use crate::module::platform_info;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+#[monitoring_module(
+    name="swap",
+    version="0.0.1",
+    description="Provides swap usage information and reports recent OOM killer events from the kernel log.",
+    settings={
+        warning_threshold => "Swap usage warning threshold in percent. Default: 50",
+        error_threshold => "Swap usage error threshold in percent. Default: 80",
+        oom_window_hours => "How many hours of kernel log to scan for OOM killer events. Not supported with dmesg. Default: 24",
+        oom_critical_count => "Number of OOM kills in the window that is considered critical. A single OOM kill is a warning. Default: 3",
+    }
+)]
+pub struct Swap {
+    threshold_warning: f32,
+    threshold_error: f32,
+    oom_window_hours: u32,
+    oom_critical_count: usize,
+}
+
+impl Module for Swap {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Swap {
+            threshold_warning: settings.get("warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(50.0),
+            threshold_error: settings.get("error_threshold").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+            oom_window_hours: settings.get("oom_window_hours").and_then(|value| value.parse().ok()).unwrap_or(24),
+            oom_critical_count: settings.get("oom_critical_count").and_then(|value| value.parse().ok()).unwrap_or(3),
+        }
+    }
+}
+
+impl MonitoringModule for Swap {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::ProgressBar,
+            display_text: String::from("Swap usage"),
+            category: String::from("host"),
+            unit: String::from("%"),
+            value_min: 0.0,
+            value_max: 100.0,
+            use_with_charts: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_cache_key(&self) -> Option<String> {
+        // Memory figures are shared by ram and swap. Only the first message is cached, so OOM events are always read.
+        Some(String::from("free"))
+    }
+
+    fn get_connector_messages(&self, host: Host, _parent_result: DataPoint) -> Result<Vec<String>, LkError> {
+        if host.platform.os != platform_info::OperatingSystem::Linux {
+            return Err(LkError::unsupported_platform());
+        }
+
+        let mut oom_command = ShellCommand::new();
+        oom_command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
+
+        if host.platform.os_flavor == platform_info::Flavor::Alpine {
+            oom_command.arguments(vec!["dmesg"]);
+        }
+        else {
+            let since = format!("-{}h", self.oom_window_hours);
+            oom_command.arguments(vec!["journalctl", "-k", "-q", "--no-pager", "--since", &since]);
+        }
+
+        // Bounded so that a host in an OOM loop doesn't produce huge responses.
+        oom_command.pipe_to(vec!["grep", "Out of memory: Killed process"])
+                   .pipe_to(vec!["tail", "-n", "100"]);
+
+        // Same as the ram-monitor uses, so the response is shared (see `get_cache_key`).
+        Ok(vec![String::from("free -b"), oom_command.to_string()])
+    }
+
+    fn process_responses(&self, _host: Host, responses: Vec<ResponseMessage>, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        let free_response = match responses.first() {
+            Some(response) => response,
+            None => return Ok(DataPoint::empty()),
+        };
+
+        if free_response.is_error() {
+            return Err(free_response.message.clone());
+        }
+
+        let swap_line = free_response.message.lines().find(|line| line.starts_with("Swap:")).ok_or(String::from("Unsupported platform"))?;
+        let parts = swap_line.split_whitespace().collect::<Vec<&str>>();
+        let total = parts.get(1).and_then(|value| value.parse::<u64>().ok()).ok_or(String::from("Unsupported platform"))?;
+        let used = parts.get(2).and_then(|value| value.parse::<u64>().ok()).ok_or(String::from("Unsupported platform"))?;
+
+        let mut data_point = if total == 0 {
+            DataPoint::value_with_level(String::from("No swap"), Criticality::Normal)
+                      .with_numeric_value(0.0, "%")
+        }
+        else {
+            let usage_percent = used as f32 / total as f32 * 100.0;
+
+            let criticality = if usage_percent >= self.threshold_error {
+                Criticality::Error
+            }
+            else if usage_percent >= self.threshold_warning {
+                Criticality::Warning
+            }
+            else {
+                Criticality::Normal
+            };
+
+            let value = format!("{:.0} % of {}", usage_percent, format_bytes(total));
+            DataPoint::value_with_level(value, criticality)
+                      .with_numeric_value(usage_percent as f64, "%")
+        };
+
+        // Lines look like: "Out of memory: Killed process 1234 (java) total-vm:..."
+        let killed_processes = match responses.get(1) {
+            Some(response) => response.message.lines()
+                .filter_map(|line| line.split_once("Killed process ").map(|(_, rest)| rest))
+                .filter_map(|rest| rest.split_once('(').and_then(|(_, rest)| rest.split_once(')')).map(|(name, _)| name.to_string()))
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+
+        if !killed_processes.is_empty() {
+            let oom_criticality = if killed_processes.len() >= self.oom_critical_count {
+                Criticality::Critical
+            }
+            else {
+                Criticality::Warning
+            };
+
+            let mut process_names = killed_processes.clone();
+            process_names.sort();
+            process_names.dedup();
+
+            data_point.criticality = std::cmp::max(data_point.criticality, oom_criticality);
+            data_point.description = format!("OOM killer: {} processes killed ({})", killed_processes.len(), process_names.join(", "));
+        }
+
+        Ok(data_point)
+    }
+}
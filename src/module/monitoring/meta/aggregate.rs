@@ -0,0 +1,129 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+#[monitoring_module(
+    name="aggregate",
+    version="0.0.1",
+    description="Combines the latest results of other monitors into a single health value. Doesn't require a connection.",
+    settings={
+        monitors => "Comma-separated list of monitor IDs with optional weights, e.g. \"filesystem:2,ram,load\". Default: filesystem,ram,load",
+        mode => "\"worst\" shows the worst state of the monitors. \"score\" shows a weighted health score from 0 to 100. Default: worst",
+        warning_threshold => "Warning threshold for the score. Default: 80",
+        error_threshold => "Error threshold for the score. Default: 50",
+    }
+)]
+pub struct Aggregate {
+    /// Monitor IDs and their weights.
+    monitors: Vec<(String, f64)>,
+    use_score: bool,
+    threshold_warning: f64,
+    threshold_error: f64,
+}
+
+impl Module for Aggregate {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        let monitors = settings.get("monitors").cloned().unwrap_or_else(|| String::from("filesystem,ram,load"));
+
+        Aggregate {
+            monitors: monitors.split(',').map(str::trim).filter(|entry| !entry.is_empty()).map(|entry| {
+                match entry.split_once(':') {
+                    Some((monitor_id, weight)) => (monitor_id.to_string(), weight.parse().unwrap_or(1.0)),
+                    None => (entry.to_string(), 1.0),
+                }
+            }).collect(),
+            use_score: settings.get("mode").map(|mode| mode == "score").unwrap_or(false),
+            threshold_warning: settings.get("warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+            threshold_error: settings.get("error_threshold").and_then(|value| value.parse().ok()).unwrap_or(50.0),
+        }
+    }
+}
+
+impl MonitoringModule for Aggregate {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::Text,
+            display_text: String::from("Health"),
+            category: String::from("host"),
+            // Would otherwise count the same problems twice.
+            use_without_summary: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_monitor_dependencies(&self) -> Vec<String> {
+        self.monitors.iter().map(|(monitor_id, _)| monitor_id.clone()).collect()
+    }
+
+    fn process_independent(&self, _host: Host, parent_result: DataPoint) -> Result<DataPoint, String> {
+        // Monitors without a result yet are left out.
+        let results = parent_result.multivalue.into_iter()
+            .filter(|data_point| !matches!(data_point.criticality, Criticality::NoData | Criticality::Ignore | Criticality::NotAvailable))
+            .collect::<Vec<_>>();
+
+        if results.is_empty() {
+            return Ok(DataPoint::pending());
+        }
+
+        let worst = results.iter().max_by_key(|data_point| data_point.criticality).unwrap();
+        let problems = results.iter()
+            .filter(|data_point| data_point.criticality >= Criticality::Warning)
+            .map(|data_point| format!("{}: {}", data_point.label, data_point.criticality))
+            .collect::<Vec<_>>();
+
+        let result = if self.use_score {
+            let (weighted_sum, total_weight) = results.iter().fold((0.0, 0.0), |(sum, total), data_point| {
+                let weight = self.get_weight(&data_point.label);
+                (sum + Self::get_score(data_point.criticality) * weight, total + weight)
+            });
+            let score = if total_weight > 0.0 { weighted_sum / total_weight } else { 100.0 };
+
+            let criticality = if score < self.threshold_error {
+                Criticality::Error
+            }
+            else if score < self.threshold_warning {
+                Criticality::Warning
+            }
+            else {
+                Criticality::Normal
+            };
+
+            DataPoint::value_with_level(format!("{:.0}", score), criticality).with_numeric_value(score, "")
+        }
+        else if worst.criticality >= Criticality::Warning {
+            DataPoint::value_with_level(worst.criticality.to_string(), worst.criticality)
+        }
+        else {
+            DataPoint::new("OK")
+        };
+
+        Ok(result.with_description(problems.join(", ")))
+    }
+}
+
+impl Aggregate {
+    fn get_weight(&self, monitor_id: &str) -> f64 {
+        self.monitors.iter().find(|(id, _)| id == monitor_id).map(|(_, weight)| *weight).unwrap_or(1.0)
+    }
+
+    fn get_score(criticality: Criticality) -> f64 {
+        match criticality {
+            Criticality::Warning => 50.0,
+            Criticality::Error => 20.0,
+            Criticality::Critical => 0.0,
+            _ => 100.0,
+        }
+    }
+}
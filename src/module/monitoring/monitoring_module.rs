@@ -26,6 +26,32 @@ pub trait MonitoringModule: BoxCloneableMonitor + MetadataSupport + Module {
         None
     }
 
+    /// Independent monitors don't use a connector. They compute their value locally (e.g. from platform info)
+    /// with `process_independent` and connector messages are never requested from them.
+    fn is_independent(&self) -> bool {
+        self.get_connector_spec().is_none()
+    }
+
+    /// IDs of other monitors of the same host whose data points this monitor needs. If a dependency is being refreshed too,
+    /// this monitor is refreshed only after it completes. Otherwise the latest data point is used.
+    /// They are passed to `process_independent` as children of the parent data point, labeled with the monitor ID.
+    fn get_monitor_dependencies(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Identifies the data that the first connector message returns, regardless of the exact command.
+    /// Monitors of the same host that use the same connector and return the same key share a cached response for a short while.
+    /// Any further messages are specific to the monitor and always executed.
+    fn get_cache_key(&self) -> Option<String> {
+        None
+    }
+
+    /// Monitors that don't need platform info are also refreshed while platform info is missing,
+    /// e.g. when the host can't be connected to with SSH.
+    fn requires_platform_info(&self) -> bool {
+        true
+    }
+
     fn new_monitoring_module(settings: &HashMap<String, String>) -> Monitor
     where
         Self: Sized + 'static + Send + Sync,
@@ -56,6 +82,12 @@ pub trait MonitoringModule: BoxCloneableMonitor + MetadataSupport + Module {
     fn process_responses(&self, _host: Host, _responses: Vec<ResponseMessage>, _parent_result: DataPoint) -> Result<DataPoint, String> {
         Err(String::new())
     }
+
+    /// Used instead of `process_response` for independent monitors.
+    /// Defaults to calling `process_response` with an empty response for backwards compatibility.
+    fn process_independent(&self, host: Host, parent_result: DataPoint) -> Result<DataPoint, String> {
+        self.process_response(host, ResponseMessage::empty(), parent_result)
+    }
 }
 
 // Implemented by the macro.
@@ -16,7 +16,7 @@ use crate::module::monitoring::*;
 #[monitoring_module(
     name="ping",
     version="0.0.1",
-    description="Measures average latency to host with ICMP echo request. Does not work with flatpak.",
+    description="Measures average latency to host with ICMP echo request using the system ping. Works even if SSH is down. Does not work with flatpak.",
     settings={
         count => "Amount of echo requests to send. Default: 2.",
         timeout => "Timeout in seconds. Default: 10."
@@ -28,10 +28,10 @@ pub struct Ping {
 }
 
 impl Module for Ping {
-    fn new(_settings: &HashMap<String, String>) -> Self {
+    fn new(settings: &HashMap<String, String>) -> Self {
         Ping {
-            count: _settings.get("count").and_then(|value| value.parse().ok()).unwrap_or(2),
-            timeout: _settings.get("timeout").and_then(|value| value.parse().ok()).unwrap_or(10),
+            count: settings.get("count").and_then(|value| value.parse().ok()).unwrap_or(2),
+            timeout: settings.get("timeout").and_then(|value| value.parse().ok()).unwrap_or(10),
         }
     }
 }
@@ -41,41 +41,44 @@ impl MonitoringModule for Ping {
         Some(ModuleSpecification::connector("local-command", "0.0.1"))
     }
 
+    fn requires_platform_info(&self) -> bool {
+        false
+    }
+
     fn get_display_options(&self) -> frontend::DisplayOptions {
         frontend::DisplayOptions {
             display_style: frontend::DisplayStyle::Text,
             display_text: String::from("Ping"),
             category: String::from("network"),
             unit: String::from("ms"),
+            is_reachability_check: true,
             ..Default::default()
         }
     }
 
+    /// Ping is run locally, so this doesn't depend on the SSH connection or the platform info of the host.
     fn get_connector_message(&self, host: Host, _parent_result: DataPoint) -> Result<String, LkError> {
         let mut command = ShellCommand::new();
-
-        if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "9") ||
-           host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") ||
-           host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") ||
-           host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") {
-            command.arguments(vec![
-                "ping", "-c", self.count.to_string().as_str(), "-W", self.timeout.to_string().as_str(), host.ip_address.to_string().as_str()
-            ]);
-        }
-        else {
-            return Err(LkError::unsupported_platform());
-        }
+        command.arguments(vec![
+            "ping", "-c", self.count.to_string().as_str(), "-W", self.timeout.to_string().as_str(), host.get_address().as_str()
+        ]);
 
         Ok(command.to_string())
     }
 
     fn process_response(&self, _host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
-        if response.is_success() {
-            let average_latency = response.message.lines().last().unwrap().split('/').nth(4).unwrap();
-            Ok(DataPoint::value_with_level(average_latency.to_string(), Criticality::Normal))
-        }
-        else {
-            Ok(DataPoint::value_with_level(response.message, Criticality::Critical))
+        // Summary line looks like: "rtt min/avg/max/mdev = 0.031/0.042/0.054/0.011 ms".
+        let average_latency = response.message.lines().last()
+            .and_then(|line| line.split('/').nth(4))
+            .filter(|_| response.is_success());
+
+        match average_latency {
+            Some(average_latency) => Ok(DataPoint::value_with_level(average_latency.to_string(), Criticality::Normal)),
+            None => {
+                let mut data_point = DataPoint::value_with_level(String::from("unreachable"), Criticality::Critical);
+                data_point.description = response.message;
+                Ok(data_point)
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -86,6 +86,7 @@ impl MonitoringModule for RebuildGenerations {
             let mut data_point = DataPoint::empty();
             data_point.label = format!("#{} @ {}", generation.generation, date_string);
             data_point.description = format!("NixOS {} | Kernel {}", generation.nixosVersion, generation.kernelVersion);
+            data_point.command_params.push(generation.generation.to_string());
 
             if generation.current {
                 data_point.tags.push(String::from("Current"));
@@ -7,7 +7,6 @@
 use std::collections::HashMap;
 
 use lightkeeper_module::monitoring_module;
-use crate::module::connection::ResponseMessage;
 use crate::{
     Host,
     frontend,
@@ -39,7 +38,7 @@ impl MonitoringModule for Os {
         }
     }
 
-    fn process_response(&self, host: Host, _response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
+    fn process_independent(&self, host: Host, _parent_result: DataPoint) -> Result<DataPoint, String> {
         Ok(DataPoint::new(format!("{} ({} {})", host.platform.os, host.platform.os_flavor, host.platform.os_version)))
     }
 }
\ No newline at end of file
@@ -0,0 +1,10 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+pub mod authorized_keys;
+pub use authorized_keys::AuthorizedKeys;
+
+pub mod file_integrity;
+pub use file_integrity::FileIntegrity;
@@ -0,0 +1,145 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::{
+    Host,
+    frontend,
+};
+
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+use crate::utils::ShellCommand;
+use crate::host::HostSetting;
+
+/// RSA keys shorter than this are considered weak.
+const MIN_RSA_BITS: u32 = 3072;
+/// Prints fingerprints (never the key material) prefixed with the file name,
+/// e.g. `/home/user/.ssh/authorized_keys:3072 SHA256:abc... user@laptop (RSA)`.
+const SCRIPT: &str = r#"for file in /root/.ssh/authorized_keys /home/*/.ssh/authorized_keys; do [ -f "$file" ] && ssh-keygen -l -f "$file" 2>/dev/null | sed "s|^|$file:|"; done; true"#;
+
+#[monitoring_module(
+    name="security-authorized-keys",
+    version="0.0.1",
+    description="Lists keys in users' authorized_keys files and flags weak key types. Reading other users' files requires root privileges.",
+    settings={
+        expected_fingerprints => "Comma-separated list of allowed key fingerprints (e.g. SHA256:...). If set, other keys are shown as critical. Default: empty",
+    }
+)]
+pub struct AuthorizedKeys {
+    expected_fingerprints: Vec<String>,
+}
+
+impl Module for AuthorizedKeys {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        AuthorizedKeys {
+            expected_fingerprints: settings.get("expected_fingerprints").map(|value| {
+                value.split(',').map(|fingerprint| fingerprint.trim().to_string()).filter(|fingerprint| !fingerprint.is_empty()).collect()
+            }).unwrap_or_default(),
+        }
+    }
+}
+
+impl MonitoringModule for AuthorizedKeys {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("Authorized SSH keys"),
+            category: String::from("host"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            let mut command = ShellCommand::new_from(vec!["sh", "-c", SCRIPT]);
+            command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
+            Ok(command.to_string())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
+        if response.is_error() {
+            return Err(response.message);
+        }
+
+        let mut result = DataPoint::empty();
+
+        for line in response.message.lines() {
+            // Paths don't contain colons, but fingerprints do.
+            let (file_path, key_info) = match line.split_once(':') {
+                Some(parts) => parts,
+                None => continue,
+            };
+
+            // Format: <bits> <fingerprint> <comment, can contain spaces> (<type>)
+            let fields = key_info.split_whitespace().collect::<Vec<_>>();
+            if fields.len() < 3 {
+                continue;
+            }
+
+            let bits = fields[0].parse::<u32>().unwrap_or_default();
+            let fingerprint = fields[1];
+            let key_type = fields[fields.len() - 1].trim_start_matches('(').trim_end_matches(')');
+            let comment = fields[2..fields.len() - 1].join(" ");
+
+            let user = if file_path.starts_with("/root/") {
+                "root"
+            }
+            else {
+                file_path.trim_start_matches("/home/").split('/').next().unwrap_or_default()
+            };
+
+            let mut problems = Vec::new();
+            if !self.expected_fingerprints.is_empty() && !self.expected_fingerprints.iter().any(|expected| expected == fingerprint) {
+                problems.push(String::from("unknown key"));
+            }
+            if key_type == "DSA" || (key_type == "RSA" && bits < MIN_RSA_BITS) {
+                problems.push(String::from("weak key"));
+            }
+
+            let criticality = if problems.iter().any(|problem| problem == "unknown key") {
+                Criticality::Critical
+            }
+            else if !problems.is_empty() {
+                Criticality::Warning
+            }
+            else {
+                Criticality::Normal
+            };
+
+            let label = if comment.is_empty() || comment == "no comment" {
+                format!("{}: {}", user, fingerprint)
+            }
+            else {
+                format!("{}: {}", user, comment)
+            };
+
+            let mut description = format!("{} | {}", fingerprint, file_path);
+            if !problems.is_empty() {
+                description = format!("{} | {}", problems.join(", "), description);
+            }
+
+            let data_point = DataPoint::labeled_value_with_level(label, format!("{} {}", key_type, bits), criticality)
+                                       .with_description(description);
+            result.multivalue.push(data_point);
+        }
+
+        result.update_criticality_from_children();
+        Ok(result)
+    }
+}
@@ -0,0 +1,145 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::{
+    Host,
+    frontend,
+};
+
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+use crate::utils::ShellCommand;
+use crate::host::HostSetting;
+
+/// Output is capped on the remote host since a broken system can report a huge amount of files.
+const MAX_OUTPUT_LINES: usize = 10000;
+/// How many modified files are listed individually.
+const MAX_LISTED_FILES: usize = 20;
+
+#[monitoring_module(
+    name="security-file-integrity",
+    version="0.0.1",
+    description="Counts package files that have been modified since installation, using debsums or rpm. Can take a long time to run, so use a long refresh interval. Requires root privileges.",
+    settings={
+        ignored_paths => "Comma-separated list of path prefixes to ignore. Default: empty",
+        warning_threshold => "Number of modified files for warning level. Default: 1",
+        critical_threshold => "Number of modified files for critical level. Default: 10",
+    }
+)]
+pub struct FileIntegrity {
+    ignored_paths: Vec<String>,
+    threshold_warning: usize,
+    threshold_critical: usize,
+}
+
+impl Module for FileIntegrity {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        FileIntegrity {
+            ignored_paths: settings.get("ignored_paths").map(|value| {
+                value.split(',').map(|path| path.trim().to_string()).filter(|path| !path.is_empty()).collect()
+            }).unwrap_or_default(),
+            threshold_warning: settings.get("warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(1),
+            threshold_critical: settings.get("critical_threshold").and_then(|value| value.parse().ok()).unwrap_or(10),
+        }
+    }
+}
+
+impl MonitoringModule for FileIntegrity {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::Text,
+            display_text: String::from("Modified package files"),
+            category: String::from("host"),
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
+        // Exit code 127 is used for missing tools so they can be reported as not available.
+        // debsums only checks non-configuration files with -c. For rpm, configuration files are filtered in `process_response`.
+        let script = if uses_debsums(&host) {
+            format!("command -v debsums >/dev/null || exit 127; debsums -c 2>/dev/null | head -n {}", MAX_OUTPUT_LINES)
+        }
+        else if host.platform.os_flavor == platform_info::Flavor::CentOS ||
+                host.platform.os_flavor == platform_info::Flavor::RedHat ||
+                host.platform.os_flavor == platform_info::Flavor::Fedora {
+            format!("command -v rpm >/dev/null || exit 127; rpm -Va 2>/dev/null | head -n {}", MAX_OUTPUT_LINES)
+        }
+        else {
+            return Err(LkError::unsupported_platform());
+        };
+
+        let mut command = ShellCommand::new_from(vec!["sh", "-c", &script]);
+        command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
+        Ok(command.to_string())
+    }
+
+    fn process_response(&self, host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
+        if response.return_code == 127 {
+            let missing_tool = if uses_debsums(&host) { "debsums" } else { "rpm" };
+            return Ok(DataPoint::not_available(&format!("{} is not installed", missing_tool)));
+        }
+
+        let modified_files = response.message.lines().filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() {
+                None
+            }
+            // debsums lists only file paths.
+            else if line.starts_with('/') {
+                Some(line)
+            }
+            // rpm: "S.5....T.  c /etc/file". Type marker is optional. Missing files start with "missing".
+            else {
+                let fields = line.split_whitespace().collect::<Vec<_>>();
+                match fields.as_slice() {
+                    [_, "c", _] => None,
+                    [_, _, path] | [_, path] => Some(*path),
+                    _ => None,
+                }
+            }
+        })
+        .filter(|path| !self.ignored_paths.iter().any(|ignored| path.starts_with(ignored)))
+        .collect::<Vec<_>>();
+
+        let count = modified_files.len();
+        let criticality = if count >= self.threshold_critical {
+            Criticality::Critical
+        }
+        else if count >= self.threshold_warning {
+            Criticality::Warning
+        }
+        else {
+            Criticality::Normal
+        };
+
+        let mut value = count.to_string();
+        if response.message.lines().count() >= MAX_OUTPUT_LINES {
+            value.push('+');
+        }
+
+        let mut description = modified_files.iter().take(MAX_LISTED_FILES).cloned().collect::<Vec<_>>().join(", ");
+        if count > MAX_LISTED_FILES {
+            description.push_str(", ...");
+        }
+
+        Ok(DataPoint::value_with_level(value, criticality).with_description(description))
+    }
+}
+
+/// Debian-based hosts are checked with debsums, others with rpm. See `get_connector_message`.
+fn uses_debsums(host: &Host) -> bool {
+    host.platform.os_flavor == platform_info::Flavor::Debian ||
+    host.platform.os_flavor == platform_info::Flavor::Ubuntu
+}
@@ -9,4 +9,7 @@ pub mod filesystem;
 pub use filesystem::Filesystem;
 
 pub mod cryptsetup;
-pub use cryptsetup::Cryptsetup;
\ No newline at end of file
+pub use cryptsetup::Cryptsetup;
+
+pub mod btrfs;
+pub use btrfs::Btrfs;
\ No newline at end of file
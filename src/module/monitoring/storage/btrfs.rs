@@ -0,0 +1,179 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use crate::enums::Criticality;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::utils::{format_bytes, ShellCommand};
+use crate::{
+    Host,
+    frontend,
+};
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+
+/// Prefix for lines that start the output of a new filesystem.
+const MOUNTPOINT_MARKER: &str = "@@ ";
+/// Multiple subvolumes of the same filesystem can be mounted, so only one mountpoint per filesystem UUID is checked.
+const SCRIPT: &str = r#"findmnt -t btrfs -n -l -o UUID,TARGET | sort -u -k1,1 | while read -r uuid mountpoint; do
+    echo "@@ $mountpoint"
+    btrfs filesystem usage -b "$mountpoint" 2>&1
+    btrfs device stats "$mountpoint" 2>&1
+done"#;
+
+#[monitoring_module(
+    name="storage-btrfs",
+    version="0.0.1",
+    description="Shows usage and device errors of mounted btrfs filesystems.",
+    settings={
+        warning_threshold => "Usage warning threshold in percent. Default: 80",
+        error_threshold => "Usage error threshold in percent. Default: 90",
+        critical_threshold => "Usage critical threshold in percent. Default: 95",
+    }
+)]
+pub struct Btrfs {
+    threshold_critical: f32,
+    threshold_error: f32,
+    threshold_warning: f32,
+}
+
+impl Module for Btrfs {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Btrfs {
+            threshold_critical: settings.get("critical_threshold").and_then(|value| value.parse().ok()).unwrap_or(95.0),
+            threshold_error: settings.get("error_threshold").and_then(|value| value.parse().ok()).unwrap_or(90.0),
+            threshold_warning: settings.get("warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+        }
+    }
+}
+
+impl MonitoringModule for Btrfs {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("Btrfs filesystems"),
+            category: String::from("storage"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
+        if host.platform.os == platform_info::OperatingSystem::Linux {
+            let mut command = ShellCommand::new_from(vec!["sh", "-c", SCRIPT]);
+            command.use_sudo = host.settings.contains(&crate::host::HostSetting::UseSudo);
+            Ok(command.to_string())
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_response(&self, _host: Host, response: ResponseMessage, _result: DataPoint) -> Result<DataPoint, String> {
+        let mut result = DataPoint::empty();
+
+        // No btrfs filesystems mounted.
+        if response.message.is_empty() {
+            return Ok(result);
+        }
+
+        let sections = response.message.split(MOUNTPOINT_MARKER).filter(|section| !section.is_empty());
+        for section in sections {
+            let mut lines = section.lines();
+            let mountpoint = lines.next().unwrap_or_default().to_string();
+            let lines = lines.collect::<Vec<_>>();
+
+            if lines.iter().any(|line| line.contains("btrfs: not found") || line.contains("btrfs: command not found")) {
+                result.multivalue.push(DataPoint::not_available("btrfs not available"));
+                break;
+            }
+
+            result.multivalue.push(self.parse_usage(&mountpoint, &lines));
+            result.multivalue.extend(Self::parse_device_stats(&mountpoint, &lines));
+        }
+
+        result.update_criticality_from_children();
+        Ok(result)
+    }
+}
+
+impl Btrfs {
+    /// Parses the overall section of `btrfs filesystem usage -b`.
+    fn parse_usage(&self, mountpoint: &String, lines: &[&str]) -> DataPoint {
+        // First occurrences are from the overall section.
+        let parse_value = |key: &str| {
+            lines.iter()
+                 .find_map(|line| line.trim().strip_prefix(key))
+                 .and_then(|value| value.split_whitespace().next())
+                 .and_then(|value| value.parse::<u64>().ok())
+        };
+
+        let (device_size, used) = match (parse_value("Device size:"), parse_value("Used:")) {
+            (Some(device_size), Some(used)) if device_size > 0 => (device_size, used),
+            _ => {
+                ::log::debug!("Invalid btrfs usage output for {}", mountpoint);
+                return DataPoint::labeled_value_with_level(mountpoint.clone(), String::from("Invalid response"), Criticality::Error);
+            }
+        };
+
+        let used_percent = used as f32 / device_size as f32 * 100.0;
+        let criticality = if used_percent >= self.threshold_critical {
+            Criticality::Critical
+        }
+        else if used_percent >= self.threshold_error {
+            Criticality::Error
+        }
+        else if used_percent >= self.threshold_warning {
+            Criticality::Warning
+        }
+        else {
+            Criticality::Normal
+        };
+
+        DataPoint::labeled_value_with_level(mountpoint.clone(), format!("{:.0} %", used_percent), criticality)
+                  .with_numeric_value(used_percent as f64, "%")
+                  .with_description(format!("{} / {} used", format_bytes(used), format_bytes(device_size)))
+    }
+
+    /// Parses lines in format `[/dev/sda1].write_io_errs    0`. Only non-zero counters are returned.
+    fn parse_device_stats(mountpoint: &String, lines: &[&str]) -> Vec<DataPoint> {
+        let mut data_points = Vec::new();
+
+        for line in lines.iter().filter(|line| line.starts_with('[')) {
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            if parts.len() != 2 {
+                continue;
+            }
+
+            let (device, counter) = match parts[0].rsplit_once("].") {
+                Some((device, counter)) => (device.trim_start_matches('['), counter),
+                None => continue,
+            };
+
+            let count = parts[1].parse::<u64>().unwrap_or_default();
+            if count == 0 {
+                continue;
+            }
+
+            let criticality = match counter {
+                "write_io_errs" | "read_io_errs" | "corruption_errs" => Criticality::Critical,
+                _ => Criticality::Error,
+            };
+
+            data_points.push(
+                DataPoint::labeled_value_with_level(format!("{} {}", device, counter), count.to_string(), criticality)
+                          .with_description(mountpoint)
+            );
+        }
+
+        data_points
+    }
+}
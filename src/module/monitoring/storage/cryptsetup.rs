@@ -17,13 +17,9 @@ use crate::{
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
+use crate::utils::format_bytes;
 
 
-const KB_BYTES: u64 = 1000;
-const MB_BYTES: u64 = 1000 * KB_BYTES;
-const GB_BYTES: u64 = 1000 * MB_BYTES;
-const TB_BYTES: u64 = 1000 * GB_BYTES;
-
 #[monitoring_module(
     name="storage-cryptsetup",
     version="0.0.1",
@@ -121,29 +117,10 @@ impl MonitoringModule for Cryptsetup {
                         Vec::new()
                     };
 
-                    // Change to SI units.
-                    let size_bytes = block_device.size;
-                    // match doesn't yet support exclusive ranges so this is simpler.
-                    let si_size = if size_bytes < KB_BYTES {
-                        format!("{} B", size_bytes)
-                    }
-                    else if size_bytes < MB_BYTES {
-                        format!("{:.2} K", size_bytes as f64 / KB_BYTES as f64)
-                    }
-                    else if size_bytes < GB_BYTES {
-                        format!("{:.2} M", size_bytes as f64 / MB_BYTES as f64)
-                    }
-                    else if size_bytes < TB_BYTES {
-                        format!("{:.2} G", size_bytes as f64 / GB_BYTES as f64)
-                    }
-                    else {
-                        format!("{:.2} T", size_bytes as f64 / TB_BYTES as f64)
-                    };
-
                     let short_name = block_device.name.split('/').last().unwrap_or(&block_device.name);
                     DataPoint::labeled_value_with_level(short_name.to_string(), String::from(""), Criticality::Normal)
                               .with_tags(tags)
-                              .with_description(format!("{}", si_size))
+                              .with_description(format_bytes(block_device.size))
 
                 })
                 .collect();
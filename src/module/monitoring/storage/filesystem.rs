@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use crate::enums::Criticality;
 use crate::error::LkError;
 use crate::module::connection::ResponseMessage;
+use crate::utils::format_bytes;
 use crate::{
     Host,
     frontend,
@@ -20,32 +21,48 @@ use crate::module::monitoring::*;
     version="0.0.1",
     description="Shows filesystem usage in a progress bar.",
     settings={
-        ignored_filesystems => "Comma-separated list of filesystems to ignore. Default: /run,/dev,/dev/shm,/sys/fs/cgroup",
+        ignored_filesystems => "Comma-separated list of mountpoints to ignore. Also ignores mountpoints under them. Default: /run,/dev,/dev/shm,/sys/fs,/var/lib/docker",
+        ignored_types => "Comma-separated list of filesystem types to ignore, e.g. tmpfs,overlay. Default: empty",
         warning_threshold => "Warning threshold in percent. Default: 80",
         error_threshold => "Error threshold in percent. Default: 90",
         critical_threshold => "Critical threshold in percent. Default: 95",
+        warning_thresholds => "Comma-separated per-mountpoint warning thresholds, e.g. /=90,/var=85. Other mountpoints use warning_threshold.",
+        error_thresholds => "Comma-separated per-mountpoint error thresholds, e.g. /=95,/var=90. Other mountpoints use error_threshold.",
+        critical_thresholds => "Comma-separated per-mountpoint critical thresholds, e.g. /=98. Other mountpoints use critical_threshold.",
     }
 )]
 pub struct Filesystem {
     ignored_filesystems: Vec<String>,
+    ignored_types: Vec<String>,
     threshold_critical: f32,
     threshold_error: f32,
     threshold_warning: f32,
+    /// Per-mountpoint thresholds. Mountpoint is the key.
+    mount_thresholds_critical: HashMap<String, f32>,
+    mount_thresholds_error: HashMap<String, f32>,
+    mount_thresholds_warning: HashMap<String, f32>,
 }
 
 impl Module for Filesystem {
     fn new(settings: &HashMap<String, String>) -> Self {
         Filesystem {
-            ignored_filesystems: vec![
-                String::from("/run"),
-                String::from("/dev"),
-                String::from("/dev/shm"),
-                String::from("/sys/fs"),
-                String::from("/var/lib/docker"),
-            ],
+            ignored_filesystems: match settings.get("ignored_filesystems") {
+                Some(value) => parse_list(value),
+                None => vec![
+                    String::from("/run"),
+                    String::from("/dev"),
+                    String::from("/dev/shm"),
+                    String::from("/sys/fs"),
+                    String::from("/var/lib/docker"),
+                ],
+            },
+            ignored_types: parse_list(settings.get("ignored_types").unwrap_or(&String::new())),
             threshold_critical: settings.get("critical_threshold").and_then(|value| value.parse().ok()).unwrap_or(95.0),
             threshold_error: settings.get("error_threshold").and_then(|value| value.parse().ok()).unwrap_or(90.0),
             threshold_warning: settings.get("warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+            mount_thresholds_critical: parse_mount_thresholds(settings.get("critical_thresholds").unwrap_or(&String::new())),
+            mount_thresholds_error: parse_mount_thresholds(settings.get("error_thresholds").unwrap_or(&String::new())),
+            mount_thresholds_warning: parse_mount_thresholds(settings.get("warning_thresholds").unwrap_or(&String::new())),
         }
     }
 }
@@ -57,6 +74,8 @@ impl MonitoringModule for Filesystem {
             display_text: String::from("Filesystem usage"),
             category: String::from("storage"),
             unit: String::from("%"),
+            value_min: 0.0,
+            value_max: 100.0,
             use_multivalue: true,
             use_with_charts: true,
             ..Default::default()
@@ -69,7 +88,7 @@ impl MonitoringModule for Filesystem {
 
     fn get_connector_message(&self, host: Host, _result: DataPoint) -> Result<String, LkError> {
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            Ok(String::from("df -hPT"))
+            Ok(String::from("df -PT -B1"))
         }
         else {
             Err(LkError::unsupported_platform())
@@ -91,9 +110,9 @@ impl MonitoringModule for Filesystem {
 
             // let _source = parts[0].to_string();
             let fs_type = parts[1].to_string();
-            let size_h = parts[2].to_string();
-            let used_h = parts[3].to_string();
-            // let _available_h = parts[4].to_string();
+            let size = parts[2].parse::<u64>().unwrap_or_default();
+            let used = parts[3].parse::<u64>().unwrap_or_default();
+            // let _available = parts[4].to_string();
 
             let mut used_percent = parts[5].to_string();
             // Remove percent symbol from the end.
@@ -102,25 +121,30 @@ impl MonitoringModule for Filesystem {
 
             let mountpoint = parts[6].to_string();
 
-            if self.ignored_filesystems.iter().any(|item| mountpoint.starts_with(item)) {
+            if self.ignored_filesystems.iter().any(|item| mountpoint.starts_with(item)) ||
+               self.ignored_types.contains(&fs_type) {
                 continue;
             }
 
-            let mut data_point = DataPoint::labeled_value(mountpoint.clone(), format!("{} %", used_percent));
-            data_point.value_float = used_percent_float;
-            data_point.criticality = if used_percent_float >= self.threshold_critical {
+            let threshold_critical = self.mount_thresholds_critical.get(&mountpoint).copied().unwrap_or(self.threshold_critical);
+            let threshold_error = self.mount_thresholds_error.get(&mountpoint).copied().unwrap_or(self.threshold_error);
+            let threshold_warning = self.mount_thresholds_warning.get(&mountpoint).copied().unwrap_or(self.threshold_warning);
+
+            let mut data_point = DataPoint::labeled_value(mountpoint.clone(), format!("{} %", used_percent))
+                                           .with_numeric_value(used_percent_float as f64, "%");
+            data_point.criticality = if used_percent_float >= threshold_critical {
                 Criticality::Critical
             }
-            else if used_percent_float >= self.threshold_error {
+            else if used_percent_float >= threshold_error {
                 Criticality::Error
             }
-            else if used_percent_float >= self.threshold_warning {
+            else if used_percent_float >= threshold_warning {
                 Criticality::Warning
             }
             else {
                 Criticality::Normal
             };
-            data_point.description = format!("{} | {} / {} used", fs_type, used_h, size_h);
+            data_point.description = format!("{} | {} / {} used", fs_type, format_bytes(used), format_bytes(size));
             data_point.command_params.push(mountpoint);
             result.multivalue.push(data_point);
         }
@@ -129,4 +153,27 @@ impl MonitoringModule for Filesystem {
 
         Ok(result)
     }
-}
\ No newline at end of file
+}
+
+fn parse_list(value: &str) -> Vec<String> {
+    value.split(',').map(|item| item.trim().to_string()).filter(|item| !item.is_empty()).collect()
+}
+
+/// Parses thresholds in format `/=90,/var=80`. Invalid entries are skipped.
+fn parse_mount_thresholds(value: &str) -> HashMap<String, f32> {
+    let mut thresholds = HashMap::new();
+
+    for entry in parse_list(value) {
+        let threshold = entry.rsplit_once('=')
+            .and_then(|(mountpoint, threshold)| Some((mountpoint.trim().to_string(), threshold.trim().parse::<f32>().ok()?)));
+
+        match threshold {
+            Some((mountpoint, threshold)) => {
+                thresholds.insert(mountpoint, threshold);
+            },
+            None => ::log::warn!("Invalid filesystem threshold \"{}\"", entry),
+        }
+    }
+
+    thresholds
+}
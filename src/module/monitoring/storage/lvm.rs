@@ -11,4 +11,14 @@ pub mod volume_group;
 pub use volume_group::VolumeGroup;
 
 pub mod physical_volume;
-pub use physical_volume::PhysicalVolume;
\ No newline at end of file
+pub use physical_volume::PhysicalVolume;
+
+use crate::utils::units::{self, ByteUnits};
+
+/// Value for the `--units` argument of LVM reporting commands. Uppercase means powers of 1000.
+pub fn get_units_argument() -> &'static str {
+    match units::get_unit_system().byte_units {
+        ByteUnits::Binary => "h",
+        ByteUnits::Si => "H",
+    }
+}
@@ -16,6 +16,7 @@ use crate::{
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
+use crate::module::monitoring::storage::lvm::get_units_argument;
 use crate::utils::ShellCommand;
 use crate::host::HostSetting;
 
@@ -36,8 +37,8 @@ pub struct LogicalVolume {
 impl Module for LogicalVolume {
     fn new(settings: &HashMap<String, String>) -> Self {
         LogicalVolume {
-            threshold_warning: settings.get("warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(80.0),
-            threshold_error: settings.get("error_threshold").and_then(|value| value.parse().ok()).unwrap_or(90.0),
+            threshold_warning: settings.get("snapshot_warning_threshold").and_then(|value| value.parse().ok()).unwrap_or(80.0),
+            threshold_error: settings.get("snapshot_error_threshold").and_then(|value| value.parse().ok()).unwrap_or(90.0),
         }
     }
 }
@@ -63,7 +64,7 @@ impl MonitoringModule for LogicalVolume {
 
         if host.platform.os == platform_info::OperatingSystem::Linux {
             command.arguments(vec![
-                "lvs", "--separator", "|", "--options", "lv_path,lv_name,vg_name,lv_size,lv_attr,sync_percent,raid_mismatch_count,snap_percent", "--units", "h"
+                "lvs", "--separator", "|", "--options", "lv_path,lv_name,vg_name,lv_size,lv_attr,sync_percent,raid_mismatch_count,snap_percent", "--units", get_units_argument()
             ]);
 
             Ok(command.to_string())
@@ -15,6 +15,7 @@ use crate::{
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
+use crate::module::monitoring::storage::lvm::get_units_argument;
 use crate::utils::ShellCommand;
 use crate::host::HostSetting;
 
@@ -54,7 +55,7 @@ impl MonitoringModule for PhysicalVolume {
         command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
 
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec!["pvs", "--separator", "|", "--options", "pv_name,pv_attr,pv_size,pv_free", "--units", "h"]);
+            command.arguments(vec!["pvs", "--separator", "|", "--options", "pv_name,pv_attr,pv_size,pv_free", "--units", get_units_argument()]);
             Ok(command.to_string())
         }
         else {
@@ -15,6 +15,7 @@ use crate::{
 use lightkeeper_module::monitoring_module;
 use crate::module::*;
 use crate::module::monitoring::*;
+use crate::module::monitoring::storage::lvm::get_units_argument;
 use crate::utils::ShellCommand;
 use crate::host::HostSetting;
 
@@ -54,7 +55,7 @@ impl MonitoringModule for VolumeGroup {
         command.use_sudo = host.settings.contains(&HostSetting::UseSudo);
 
         if host.platform.os == platform_info::OperatingSystem::Linux {
-            command.arguments(vec![ "vgs", "--separator", "|", "--options", "vg_name,vg_attr,vg_size,vg_free", "--units", "h" ]);
+            command.arguments(vec![ "vgs", "--separator", "|", "--options", "vg_name,vg_attr,vg_size,vg_free", "--units", get_units_argument() ]);
             Ok(command.to_string())
         }
         else {
@@ -5,4 +5,7 @@
 
 
 pub mod service;
-pub use service::Service;
\ No newline at end of file
+pub use service::Service;
+
+pub mod timer;
+pub use timer::Timer;
\ No newline at end of file
@@ -0,0 +1,145 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+
+use regex::Regex;
+use std::collections::HashMap;
+use crate::error::LkError;
+use crate::module::connection::ResponseMessage;
+use crate::{
+    Host,
+    frontend,
+};
+
+use crate::enums;
+use lightkeeper_module::monitoring_module;
+use crate::module::*;
+use crate::module::monitoring::*;
+use crate::utils::ShellCommand;
+
+#[monitoring_module(
+    name="systemd-timer",
+    version="0.0.1",
+    description="Provides information about SystemD timers. Timers whose last run failed are shown as errors.",
+    settings={
+      excluded_timers => "Comma-separated list of timers to exclude. Default: empty"
+    }
+)]
+pub struct Timer {
+    excluded_timers: Vec<String>,
+    /// Matches lines like: `Mon 2025-01-06 00:00:00 UTC 2h 5min left Sun 2025-01-05 00:00:00 UTC 21h ago logrotate.timer logrotate.service`.
+    /// Timestamps can also be "n/a" or "-" and the time left and passed fields can contain multiple words.
+    regex_timer: Regex,
+}
+
+impl Module for Timer {
+    fn new(settings: &HashMap<String, String>) -> Self {
+        Timer {
+            excluded_timers: settings.get("excluded_timers").unwrap_or(&String::from(""))
+                                     .split(',')
+                                     .filter(|value| !value.is_empty())
+                                     .map(|value| value.to_string())
+                                     .collect(),
+            regex_timer: Regex::new(r"^(n/a|-|\w+ \d{4}-\d{2}-\d{2} \S+ \S+)\s+(.+?)\s+(n/a|-|\w+ \d{4}-\d{2}-\d{2} \S+ \S+)\s+(.+?)\s+(\S+\.timer)\s+(\S+)$").unwrap(),
+        }
+    }
+}
+
+impl MonitoringModule for Timer {
+    fn get_display_options(&self) -> frontend::DisplayOptions {
+        frontend::DisplayOptions {
+            display_style: frontend::DisplayStyle::CriticalityLevel,
+            display_text: String::from("Timers"),
+            category: String::from("systemd"),
+            use_multivalue: true,
+            ..Default::default()
+        }
+    }
+
+    fn get_connector_spec(&self) -> Option<ModuleSpecification> {
+        Some(ModuleSpecification::connector("ssh", "0.0.1"))
+    }
+
+    fn get_connector_messages(&self, host: Host, _parent_result: DataPoint) -> Result<Vec<String>, LkError> {
+        if host.platform.is_same_or_greater(platform_info::Flavor::Debian, "10") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::Ubuntu, "20") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::NixOS, "20") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::CentOS, "8") ||
+           host.platform.is_same_or_greater(platform_info::Flavor::RedHat, "8") {
+
+            let command_timers = ShellCommand::new_from(vec!["systemctl", "list-timers", "--all", "--no-legend", "--no-pager"]);
+            // Result of the last run is found from the activated service.
+            let command_failed = ShellCommand::new_from(vec!["systemctl", "list-units", "--type=service", "--state=failed",
+                                                              "--no-legend", "--no-pager", "--plain"]);
+            Ok(vec![command_timers.to_string(), command_failed.to_string()])
+        }
+        else {
+            Err(LkError::unsupported_platform())
+        }
+    }
+
+    fn process_responses(&self, _host: Host, responses: Vec<ResponseMessage>, _parent_result: DataPoint) -> Result<DataPoint, String> {
+        let timers_response = match responses.first() {
+            Some(response) => response,
+            None => return Ok(DataPoint::empty()),
+        };
+
+        if timers_response.is_command_not_found() {
+            return Ok(DataPoint::value_with_level("SystemD not available".to_string(), enums::Criticality::NotAvailable));
+        }
+        if timers_response.is_error() {
+            return Err(timers_response.message.clone());
+        }
+
+        let failed_services = match responses.get(1) {
+            Some(response) if response.is_success() => {
+                response.message.lines()
+                                .filter_map(|line| line.split_whitespace().next())
+                                .map(|service| service.to_string())
+                                .collect::<Vec<_>>()
+            },
+            _ => Vec::new(),
+        };
+
+        let mut result = DataPoint::empty();
+
+        for line in timers_response.message.lines() {
+            let captures = match self.regex_timer.captures(line.trim()) {
+                Some(captures) => captures,
+                None => {
+                    ::log::debug!("Invalid line in response: {}", line);
+                    continue;
+                }
+            };
+
+            let next = &captures[1];
+            let last = &captures[3];
+            let passed = &captures[4];
+            let timer = captures[5].to_string();
+            let service = &captures[6];
+
+            if self.excluded_timers.contains(&timer) {
+                continue;
+            }
+
+            let (value, criticality) = if failed_services.iter().any(|failed| failed == service) {
+                (format!("{} failed", service), enums::Criticality::Error)
+            }
+            else if last == "n/a" || last == "-" {
+                (String::from("never run"), enums::Criticality::Normal)
+            }
+            else {
+                (format!("last run {}", passed), enums::Criticality::Normal)
+            };
+
+            let data_point = DataPoint::labeled_value_with_level(timer.clone(), value, criticality)
+                                       .with_description(format!("Next: {} | Last: {} | Activates: {}", next, last, service));
+            result.multivalue.push(data_point);
+        }
+
+        result.update_criticality_from_children();
+        Ok(result)
+    }
+}
@@ -22,6 +22,10 @@ pub struct PlatformInfo {
     pub os_flavor: Flavor,
 
     pub architecture: Architecture,
+
+    /// Detected container runtime, if any.
+    #[serde(default)]
+    pub container_runtime: ContainerRuntime,
 }
 
 impl PlatformInfo {
@@ -95,6 +99,19 @@ impl Default for Architecture {
     }
 }
 
+#[derive(Clone, PartialEq, Eq, EnumString, Display, Serialize, Deserialize)]
+pub enum ContainerRuntime {
+    Unknown,
+    Docker,
+    Podman,
+}
+
+impl Default for ContainerRuntime {
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
 impl<Stringable: ToString> From<&Stringable> for Architecture {
     fn from(value: &Stringable) -> Self {
         match value.to_string().to_lowercase().as_str() {
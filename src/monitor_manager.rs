@@ -5,22 +5,29 @@
 
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc;
 use std::thread;
+use std::time::{Duration, Instant};
 
+use crate::enums::HostStatus;
 use crate::error::*;
 use crate::module::connection::RequestResponse;
 use crate::Host;
-use crate::configuration::Hosts;
-use crate::module::connection::ResponseMessage;
+use crate::configuration::{Configuration, Hosts, MonitorConfig, Preferences};
 use crate::module::{monitoring::*, ModuleSpecification};
 use crate::module::ModuleFactory;
 use crate::host_manager::{StateUpdateMessage, HostManager};
 use crate::connection_manager::{ ConnectorRequest, RequestType };
+use crate::frontend::UIUpdate;
+use crate::refresh_scheduler::RefreshScheduler;
+use crate::utils::{next_invocation_id, secret};
 
 pub const CERT_MONITOR_HOST_ID: &str = "_cert-monitor";
+/// Refreshes without a response for this long are considered lost (e.g. dropped during reload), so they don't block new refreshes.
+/// Well above the default connection timeouts, since monitor commands can take a while too.
+const IN_FLIGHT_TIMEOUT: Duration = Duration::from_secs(120);
 
 
 // Default needs to be implemented because of Qt QObject requirements.
@@ -33,8 +40,17 @@ pub struct MonitorManager {
     request_sender: Option<mpsc::Sender<ConnectorRequest>>,
     // Channel to send state updates to HostManager.
     state_update_sender: Option<mpsc::Sender<StateUpdateMessage>>,
-    /// Every refresh operation gets an invocation ID. Valid ID numbers begin from 1.
-    invocation_id_counter: u64,
+    /// Effective host configurations. Used for re-enabling monitors at runtime.
+    hosts_config: Hosts,
+    /// For persisting runtime changes to configuration.
+    config_dir: String,
+    /// For automatic refreshing of monitors.
+    refresh_scheduler: RefreshScheduler,
+    /// Refreshes that haven't completed yet. Used to avoid sending duplicate requests.
+    /// Host name is the first key and base monitor id is the second key.
+    in_flight: Arc<Mutex<HashMap<String, HashMap<String, InFlightRefresh>>>>,
+    /// Refreshes of monitors that are waiting for their dependencies to complete first.
+    waiting_dependents: Arc<Mutex<Vec<DependentRefresh>>>,
 
     // Shared resources. Only used for fetching up-to-date data.
     host_manager: Rc<RefCell<HostManager>>,
@@ -46,9 +62,10 @@ pub struct MonitorManager {
 }
 
 impl MonitorManager {
-    pub fn new(host_manager: Rc<RefCell<HostManager>>, module_factory: Arc<ModuleFactory>) -> Self {
+    pub fn new(config_dir: &String, host_manager: Rc<RefCell<HostManager>>, module_factory: Arc<ModuleFactory>) -> Self {
 
         MonitorManager {
+            config_dir: config_dir.clone(),
             host_manager: host_manager.clone(),
             module_factory: module_factory,
             ..Default::default()
@@ -57,6 +74,7 @@ impl MonitorManager {
 
     pub fn configure(&mut self,
                      hosts_config: &Hosts,
+                     preferences: &Preferences,
                      request_sender: mpsc::Sender<ConnectorRequest>,
                      state_update_sender: mpsc::Sender<StateUpdateMessage>) {
 
@@ -69,8 +87,12 @@ impl MonitorManager {
             Err(error) => error.into_inner().clear(),
         };
 
+        self.in_flight.lock().unwrap().clear();
+        self.waiting_dependents.lock().unwrap().clear();
         self.request_sender = Some(request_sender);
         self.state_update_sender = Some(state_update_sender);
+        self.hosts_config = hosts_config.clone();
+        self.refresh_scheduler.configure(hosts_config, preferences);
 
         // Certificate monitors.
         if hosts_config.certificate_monitors.len() > 0 {
@@ -91,6 +113,11 @@ impl MonitorManager {
 
             let mut new_monitors = Vec::<Monitor>::new();
             for (monitor_id, monitor_config) in host_config.effective.monitors.iter() {
+                if !MonitorConfig::is_enabled(&monitor_config.enabled) {
+                    log::debug!("[{}] Monitor \"{}\" is disabled", host_id, monitor_id);
+                    continue;
+                }
+
                 let monitor_spec = ModuleSpecification::monitor(monitor_id, &monitor_config.version);
                 let monitor = match self.module_factory.new_monitor(&monitor_spec, &monitor_config.settings) {
                     Some(monitor) => monitor,
@@ -115,6 +142,8 @@ impl MonitorManager {
     }
 
     pub fn stop(&mut self) {
+        self.refresh_scheduler.stop();
+
         if let Some(thread) = self.response_receiver_thread.take() {
             self.new_response_sender()
                 .send(RequestResponse::stop())
@@ -124,6 +153,11 @@ impl MonitorManager {
         }
     }
 
+    /// Due refreshes are sent to the UI thread since MonitorManager lives there.
+    pub fn start_refresh_scheduler(&mut self, update_sender: mpsc::Sender<UIUpdate>) {
+        self.refresh_scheduler.start(update_sender);
+    }
+
     pub fn new_response_sender(&self) -> mpsc::Sender<RequestResponse> {
         self.response_sender_prototype.clone().unwrap()
     }
@@ -160,15 +194,134 @@ impl MonitorManager {
         }
     }
 
-    /// Intended to be run only once in the beginning when possibly refreshing all host data.
-    /// Returns list of host IDs that were refreshed.
-    pub fn refresh_platform_info_all(&mut self) -> Vec<String> {
+    /// Starts using a monitor that was disabled. The change is persisted to host configuration.
+    pub fn enable_monitor(&mut self, host_id: &String, monitor_id: &String) -> Result<(), LkError> {
+        self.check_persistable(host_id)?;
+
+        let monitor_config = self.hosts_config.hosts.get(host_id)
+            .and_then(|host_config| host_config.effective.monitors.get(monitor_id))
+            .cloned()
+            .ok_or_else(|| LkError::other_p("Monitor is not configured for host", monitor_id))?;
+
+        let monitor_spec = ModuleSpecification::monitor(monitor_id, &monitor_config.version);
+        let monitor = self.module_factory.new_monitor(&monitor_spec, &monitor_config.settings)
+            .ok_or_else(|| LkError::other_p("Failed to create monitor", monitor_id))?;
+
+        // Base modules of extension modules don't get the initial NoData data point.
+        let is_base = self.monitors.lock().unwrap().get(host_id)
+            .map(|monitors| monitors.values().any(|existing| existing.get_metadata_self().parent_module == Some(monitor.get_module_spec())))
+            .unwrap_or(false);
+
+        // Persisted first so that runtime state doesn't diverge from the configuration if writing fails.
+        self.persist_monitor_override(host_id, monitor_id, |config| config.enabled = Some(true))?;
+        self.add_monitor(host_id.clone(), monitor, !is_base);
+        log::info!("[{}] Enabled monitor \"{}\"", host_id, monitor_id);
+        Ok(())
+    }
+
+    /// Stops refreshing a monitor and clears its data. The change is persisted to host configuration.
+    pub fn disable_monitor(&mut self, host_id: &String, monitor_id: &String) -> Result<(), LkError> {
+        self.check_persistable(host_id)?;
+
+        let is_enabled = self.monitors.lock().unwrap().get(host_id)
+            .map(|monitors| monitors.contains_key(monitor_id))
+            .unwrap_or(false);
+
+        if !is_enabled {
+            return Err(LkError::other_p("Monitor is not enabled for host", monitor_id));
+        }
+
+        self.persist_monitor_override(host_id, monitor_id, |config| config.enabled = Some(false))?;
+
+        let monitor = self.monitors.lock().unwrap().get_mut(host_id)
+            .and_then(|monitors| monitors.remove(monitor_id))
+            .ok_or_else(|| LkError::other_p("Monitor is not enabled for host", monitor_id))?;
+
+        // Refresh in progress is abandoned and its response will be ignored.
+        let invocation_id = self.in_flight.lock().unwrap().get(host_id)
+            .and_then(|host_in_flight| host_in_flight.get(monitor_id))
+            .map(|refresh| refresh.invocation_id)
+            .unwrap_or(0);
+        self.waiting_dependents.lock().unwrap().retain(|dependent| !(&dependent.host.name == host_id && &dependent.monitor_id == monitor_id));
+
+        self.state_update_sender.as_ref().unwrap().send(StateUpdateMessage {
+            host_name: host_id.clone(),
+            display_options: monitor.get_display_options(),
+            module_spec: monitor.get_module_spec(),
+            remove_monitor_data: true,
+            invocation_id: invocation_id,
+            ..Default::default()
+        }).unwrap();
+
+        if invocation_id != 0 {
+            // Clears the in-flight entry and releases monitors that were waiting for this one.
+            Self::complete_refresh(&self.monitors.lock().unwrap(), &self.in_flight, &self.waiting_dependents,
+                                   self.request_sender.as_ref().unwrap(), self.state_update_sender.as_ref().unwrap(),
+                                   &self.new_response_sender(), host_id, invocation_id, DataPoint::empty());
+        }
+
+        log::info!("[{}] Disabled monitor \"{}\"", host_id, monitor_id);
+        Ok(())
+    }
+
+    /// Sets whether the monitor affects the host status (i.e. host is considered down if the monitor is critical).
+    /// Takes effect on the next state update. The change is persisted to host configuration.
+    pub fn set_critical(&mut self, host_id: &String, monitor_id: &String, is_critical: bool) -> Result<(), LkError> {
+        self.check_persistable(host_id)?;
+
+        self.persist_monitor_override(host_id, monitor_id, |config| config.is_critical = Some(is_critical))?;
+        self.host_manager.borrow().set_monitor_critical(host_id, monitor_id, is_critical);
+        log::info!("[{}] Monitor \"{}\" is {}critical", host_id, monitor_id, if is_critical { "" } else { "no longer " });
+        Ok(())
+    }
+
+    /// Included host files are never written to, so changes to their hosts can't be persisted.
+    fn check_persistable(&self, host_id: &String) -> Result<(), LkError> {
+        if self.hosts_config.included_hosts.contains(host_id) {
+            Err(LkError::other_p("Host is from an included file, so monitor changes can't be saved", host_id))
+        }
+        else {
+            Ok(())
+        }
+    }
+
+    /// Writes the change as a host-level override to hosts.yml and then applies it to the current configuration.
+    /// Current configuration is left unchanged if writing fails.
+    fn persist_monitor_override(&mut self, host_id: &String, monitor_id: &String, update: impl Fn(&mut MonitorConfig)) -> Result<(), LkError> {
+        // Only hosts.yml is read from disk so that unrelated changes aren't lost and other files aren't inlined.
+        let mut hosts_config = Configuration::read_hosts_config(&self.config_dir)?;
+        let host_config = hosts_config.hosts.get_mut(host_id)
+            .ok_or_else(|| LkError::other_p("Host not found in configuration", host_id))?;
+
+        let overrides = &mut host_config.overrides.monitors;
+        let monitor_override = overrides.entry(monitor_id.clone()).or_default();
+        update(monitor_override);
+
+        // Don't leave behind empty overrides.
+        if *monitor_override == MonitorConfig::default() {
+            overrides.remove(monitor_id);
+        }
+
+        Configuration::write_hosts_config(&self.config_dir, &hosts_config)?;
+
+        if let Some(monitor_config) = self.hosts_config.hosts.get_mut(host_id).and_then(|host| host.effective.monitors.get_mut(monitor_id)) {
+            update(monitor_config);
+        }
+        Ok(())
+    }
+
+    /// Refreshes platform info of all hosts that don't have it yet. Probes are processed concurrently by ConnectionManager.
+    /// Returns the host IDs that were probed and the host IDs that were skipped since platform info is already known.
+    pub fn refresh_missing_platform_info(&mut self) -> (Vec<String>, Vec<String>) {
         let host_ids = self.monitors.lock().unwrap().keys().cloned().collect::<Vec<_>>();
-        for host_id in &host_ids {
+        let (initialized, uninitialized): (Vec<_>, Vec<_>) = host_ids.into_iter()
+            .partition(|host_id| self.host_manager.borrow().get_host(host_id).platform.is_set());
+
+        for host_id in &uninitialized {
             self.refresh_platform_info(host_id);
         }
 
-        host_ids
+        (uninitialized, initialized)
     }
 
     /// Refreshes platform info and such in preparation for actual monitor refresh.
@@ -202,7 +355,7 @@ impl MonitorManager {
                     }
                 };
 
-                self.invocation_id_counter += 1;
+                let invocation_id = next_invocation_id();
 
                 // Notify host state manager about new pending monitor invocation.
                 self.state_update_sender.as_ref().unwrap().send(StateUpdateMessage {
@@ -210,7 +363,7 @@ impl MonitorManager {
                     display_options: info_provider.get_display_options(),
                     module_spec: info_provider.get_module_spec(),
                     data_point: Some(DataPoint::pending()),
-                    invocation_id: self.invocation_id_counter,
+                    invocation_id: invocation_id,
                     ..Default::default()
                 }).unwrap();
 
@@ -218,12 +371,13 @@ impl MonitorManager {
                     connector_spec: info_provider.get_connector_spec(),
                     source_id: info_provider.get_module_spec().id,
                     host: host.clone(),
-                    invocation_id: self.invocation_id_counter,
+                    invocation_id: invocation_id,
                     response_sender: self.new_response_sender(),
                     request_type: RequestType::MonitorCommand {
                         parent_datapoint: None,
                         extension_monitors: Vec::new(),
                         commands: commands,
+                        cache_key: info_provider.get_cache_key(),
                     },
                 }).unwrap();
             }
@@ -239,6 +393,49 @@ impl MonitorManager {
         categories
     }
 
+    /// Refreshes monitors of all hosts at once, optionally only for a single category.
+    /// Requests are processed concurrently by ConnectionManager. Returned structure can be used to track progress.
+    pub fn refresh_all(&mut self, category: Option<&String>) -> RefreshProgress {
+        let host_ids = self.monitors.lock().unwrap().keys()
+                                    .filter(|host_id| !host_id.starts_with("_"))
+                                    .cloned()
+                                    .collect::<Vec<_>>();
+
+        self.refresh_hosts(host_ids, category)
+    }
+
+    /// Like `refresh_all`, but only for hosts matching the selector. See `Hosts::select_hosts` for the syntax.
+    pub fn refresh_matching(&mut self, host_selector: &str, category: Option<&String>) -> RefreshProgress {
+        let monitors = self.monitors.lock().unwrap();
+        let host_ids = self.hosts_config.select_hosts(host_selector).into_iter()
+                                        .filter(|host_id| monitors.contains_key(host_id))
+                                        .collect::<Vec<_>>();
+        drop(monitors);
+
+        self.refresh_hosts(host_ids, category)
+    }
+
+    fn refresh_hosts(&mut self, host_ids: Vec<String>, category: Option<&String>) -> RefreshProgress {
+        let mut progress = RefreshProgress::default();
+        for host_id in host_ids {
+            let invocation_ids = match category {
+                Some(category) => self.refresh_monitors_of_category(&host_id, category),
+                None => {
+                    self.get_all_host_categories(&host_id).iter()
+                        .flat_map(|category| self.refresh_monitors_of_category(&host_id, category))
+                        .collect()
+                }
+            };
+
+            if !invocation_ids.is_empty() {
+                progress.invocation_ids.insert(host_id, invocation_ids);
+            }
+        }
+
+        log::debug!("Refreshing {} monitors on {} hosts", progress.total(), progress.invocation_ids.len());
+        progress
+    }
+
     pub fn refresh_certificate_monitors(&mut self) -> Vec<u64> {
         let monitors = self.monitors.lock().unwrap();
         let certificate_monitors = monitors[CERT_MONITOR_HOST_ID].iter().collect();
@@ -254,9 +451,7 @@ impl MonitorManager {
                                                     .filter(|(_, monitor)| &monitor.get_display_options().category == category)
                                                     .collect();
 
-        let invocation_ids = self.refresh_monitors(host, monitors_by_category);
-        self.invocation_id_counter += invocation_ids.len() as u64;
-        invocation_ids
+        self.refresh_monitors(host, monitors_by_category)
     }
 
     /// Refresh by monitor ID.
@@ -268,30 +463,101 @@ impl MonitorManager {
                                        .filter(|(_, monitor)| &monitor.get_module_spec().id == monitor_id)
                                        .collect();
 
-        let invocation_ids = self.refresh_monitors(host, monitor);
-        self.invocation_id_counter += invocation_ids.len() as u64;
-        invocation_ids
+        self.refresh_monitors(host, monitor)
+    }
+
+    /// Refreshes monitors that are due according to the refresh schedule. Host name is the key.
+    /// Returns the host IDs that didn't have platform info yet and were initialized instead.
+    pub fn refresh_scheduled(&mut self, due_refreshes: HashMap<String, Vec<String>>) -> Vec<String> {
+        let mut initialized_hosts = Vec::new();
+
+        for (host_id, monitor_ids) in due_refreshes.iter() {
+            // Monitors are refreshed after initialization anyway.
+            if !self.host_manager.borrow().get_host(host_id).platform.is_set() {
+                self.refresh_platform_info(host_id);
+                initialized_hosts.push(host_id.clone());
+
+                // E.g. ping can tell if the host is reachable even though platform info can't be fetched.
+                let platform_independent_ids = self.monitors.lock().unwrap().get(host_id)
+                    .map(|host_monitors| host_monitors.iter()
+                        .filter(|(monitor_id, monitor)| monitor_ids.contains(monitor_id) && !monitor.requires_platform_info())
+                        .map(|(monitor_id, _)| monitor_id.clone())
+                        .collect::<Vec<_>>())
+                    .unwrap_or_default();
+
+                if !platform_independent_ids.is_empty() {
+                    self.refresh_monitors_by_ids(host_id, &platform_independent_ids);
+                }
+            }
+            else {
+                let invocation_ids = self.refresh_monitors_by_ids(host_id, monitor_ids);
+                log::debug!("[{}] Refreshing {} scheduled monitors", host_id, invocation_ids.len());
+            }
+
+            // Down and unreachable hosts are still polled to notice recovery, but less often.
+            if matches!(self.host_manager.borrow().get_host_status(host_id), HostStatus::Down | HostStatus::Unreachable) {
+                self.refresh_scheduler.back_off(host_id);
+            }
+            else {
+                self.refresh_scheduler.reset_backoff(host_id);
+            }
+        }
+
+        initialized_hosts
+    }
+
+    /// Refresh multiple monitors by ID. Related base and extension modules are refreshed too.
+    /// Returns the invocation IDs of the refresh operations.
+    pub fn refresh_monitors_by_ids(&mut self, host_id: &String, monitor_ids: &[String]) -> Vec<u64> {
+        let host = self.host_manager.borrow().get_host(host_id);
+        let monitors = self.monitors.lock().unwrap();
+        let host_monitors = match monitors.get(host_id) {
+            Some(host_monitors) => host_monitors,
+            None => return Vec::new(),
+        };
+
+        let is_selected = |monitor: &Monitor| monitor_ids.contains(&monitor.get_module_spec().id);
+        let selected_monitors = host_monitors.iter().filter(|(_, monitor)| {
+            is_selected(monitor) ||
+            // Extension of a selected base module.
+            monitor.get_metadata_self().parent_module.map(|parent| monitor_ids.contains(&parent.id)).unwrap_or(false) ||
+            // Base module of a selected extension module.
+            host_monitors.values().any(|other| other.get_metadata_self().parent_module == Some(monitor.get_module_spec()) && is_selected(other))
+        }).collect();
+
+        self.refresh_monitors(host, selected_monitors)
     }
 
     fn refresh_monitors(&self, host: Host, monitors: HashMap<&String, &Monitor>) -> Vec<u64> {
-        if !host.platform.is_set() && monitors.values().any(|monitor| !monitor.is_internal()) {
+        if !host.platform.is_set() && monitors.values().any(|monitor| !monitor.is_internal() && monitor.requires_platform_info()) {
             log::warn!("[{}] Refreshing monitors despite missing platform info", host.name);
         }
 
-        let mut current_invocation_id = self.invocation_id_counter;
         let mut invocation_ids = Vec::new();
+        let mut in_flight = self.in_flight.lock().unwrap();
+        let host_in_flight = in_flight.entry(host.name.clone()).or_default();
+        self.evict_lost_refreshes(&host.name, host_in_flight);
 
         // Split into 2: base modules and extension modules.
-        let (extensions, bases): (Vec<&Monitor>, Vec<&Monitor>) = 
+        let (extensions, mut bases): (Vec<&Monitor>, Vec<&Monitor>) = 
             monitors.values().partition(|monitor| monitor.get_metadata_self().parent_module.is_some());
+        // Monitors depending on other monitors are refreshed last.
+        bases.sort_by_key(|monitor| !monitor.get_monitor_dependencies().is_empty());
 
         for monitor in bases {
-            current_invocation_id += 1;
+            // Identical refresh is already in progress, so its result can be waited for instead.
+            if let Some(refresh) = host_in_flight.get(&monitor.get_module_spec().id) {
+                log::debug!("[{}][{}] Refresh already in progress", host.name, monitor.get_module_spec().id);
+                invocation_ids.push(refresh.invocation_id);
+                continue;
+            }
+
+            let current_invocation_id = next_invocation_id();
             invocation_ids.push(current_invocation_id);
 
             let extension_ids = extensions.iter()
                 .filter(|ext| ext.get_metadata_self().parent_module.unwrap() == monitor.get_module_spec())
-                .map(|ext| ext.get_module_spec().id.clone()).collect();
+                .map(|ext| ext.get_module_spec().id.clone()).collect::<Vec<_>>();
 
             // Notify host state manager about new pending monitor invocation.
             self.state_update_sender.as_ref().unwrap().send(StateUpdateMessage {
@@ -303,7 +569,49 @@ impl MonitorManager {
                 ..Default::default()
             }).unwrap();
 
-            let messages = match get_monitor_connector_messages(&host, &monitor, &DataPoint::empty()) {
+            let dependencies = monitor.get_monitor_dependencies();
+            // Dependencies that are being refreshed are waited for. Otherwise the data would be from the previous refresh.
+            let waiting_for = dependencies.iter()
+                .filter(|dependency| host_in_flight.contains_key(*dependency))
+                .cloned()
+                .collect::<HashSet<_>>();
+            let available_dependencies = dependencies.iter()
+                .filter(|dependency| !waiting_for.contains(*dependency))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if !waiting_for.is_empty() {
+                log::debug!("[{}][{}] Waiting for dependencies: {:?}", host.name, monitor.get_module_spec().id, waiting_for);
+                host_in_flight.insert(monitor.get_module_spec().id, InFlightRefresh::new(current_invocation_id));
+
+                self.waiting_dependents.lock().unwrap().push(DependentRefresh {
+                    host: host.clone(),
+                    monitor_id: monitor.get_module_spec().id,
+                    invocation_id: current_invocation_id,
+                    extension_monitors: extension_ids,
+                    waiting_for: waiting_for,
+                    dependency_data: self.host_manager.borrow().get_latest_data_points(&host.name, &available_dependencies),
+                });
+                continue;
+            }
+
+            let parent_datapoint = if dependencies.is_empty() {
+                None
+            }
+            else {
+                let mut dependency_data = DataPoint::empty();
+                dependency_data.multivalue = self.host_manager.borrow().get_latest_data_points(&host.name, &available_dependencies);
+                Some(dependency_data)
+            };
+
+            let messages_result = if monitor.is_independent() {
+                Ok(Vec::new())
+            }
+            else {
+                get_monitor_connector_messages(&host, &monitor, parent_datapoint.as_ref().unwrap_or(&DataPoint::empty()))
+            };
+
+            let messages = match messages_result {
                 Ok(messages) => messages,
                 Err(error) => {
                     log::error!("Monitor failed: {}", error);
@@ -321,6 +629,8 @@ impl MonitorManager {
                 }
             };
 
+            host_in_flight.insert(monitor.get_module_spec().id, InFlightRefresh::new(current_invocation_id));
+
             self.request_sender.as_ref().unwrap().send(ConnectorRequest {
                 connector_spec: monitor.get_connector_spec(),
                 source_id: monitor.get_module_spec().id,
@@ -328,9 +638,10 @@ impl MonitorManager {
                 invocation_id: current_invocation_id,
                 response_sender: self.new_response_sender(),
                 request_type: RequestType::MonitorCommand {
-                    parent_datapoint: None,
+                    parent_datapoint: parent_datapoint,
                     extension_monitors: extension_ids,
                     commands: messages,
+                    cache_key: monitor.get_cache_key(),
                 },
             }).unwrap();
         }
@@ -338,6 +649,34 @@ impl MonitorManager {
         invocation_ids
     }
 
+    /// Removes refreshes that have waited for a response for too long, so that the monitors can be refreshed again.
+    /// Dependents waiting for the lost refreshes become lost too, but they are evicted later in the same way.
+    fn evict_lost_refreshes(&self, host_id: &String, host_in_flight: &mut HashMap<String, InFlightRefresh>) {
+        let mut lost_invocation_ids = Vec::new();
+
+        host_in_flight.retain(|monitor_id, refresh| {
+            if refresh.started.elapsed() < IN_FLIGHT_TIMEOUT {
+                return true;
+            }
+
+            log::warn!("[{}][{}] No response received for refresh, giving up", host_id, monitor_id);
+            lost_invocation_ids.push(refresh.invocation_id);
+
+            // Update without a data point only clears the pending invocation.
+            self.state_update_sender.as_ref().unwrap().send(StateUpdateMessage {
+                host_name: host_id.clone(),
+                module_spec: ModuleSpecification::monitor(monitor_id, "latest"),
+                invocation_id: refresh.invocation_id,
+                ..Default::default()
+            }).unwrap();
+            false
+        });
+
+        if !lost_invocation_ids.is_empty() {
+            self.waiting_dependents.lock().unwrap().retain(|dependent| !lost_invocation_ids.contains(&dependent.invocation_id));
+        }
+    }
+
 
     //
     // RESPONSE HANDLING
@@ -347,6 +686,8 @@ impl MonitorManager {
         let thread = Self::_start_processing_responses(
             self.monitors.clone(),
             self.platform_info_providers.clone(),
+            self.in_flight.clone(),
+            self.waiting_dependents.clone(),
             self.request_sender.as_ref().unwrap().clone(),
             self.state_update_sender.as_ref().unwrap().clone(),
             self.response_sender_prototype.as_ref().unwrap().clone(),
@@ -356,9 +697,12 @@ impl MonitorManager {
         self.response_receiver_thread = Some(thread);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn _start_processing_responses(
         monitors: Arc<Mutex<HashMap<String, HashMap<String, Monitor>>>>,
         platform_info_providers: Arc<Mutex<HashMap<String, Monitor>>>,
+        in_flight: Arc<Mutex<HashMap<String, HashMap<String, InFlightRefresh>>>>,
+        waiting_dependents: Arc<Mutex<Vec<DependentRefresh>>>,
         request_sender: mpsc::Sender<ConnectorRequest>,
         state_update_sender: mpsc::Sender<StateUpdateMessage>,
         response_sender: mpsc::Sender<RequestResponse>,
@@ -369,7 +713,7 @@ impl MonitorManager {
             log::debug!("Started processing responses");
 
             loop {
-                let response = match response_receiver.recv() {
+                let mut response = match response_receiver.recv() {
                     Ok(response) => response,
                     Err(error) => {
                         log::error!("Stopped response receiver thread: {}", error);
@@ -382,19 +726,37 @@ impl MonitorManager {
                     return;
                 }
 
-                let results_len = response.responses.len();
-                let (responses, errors): (Vec<_>, Vec<_>) =  response.responses.into_iter().partition(Result::is_ok);
+                let results = std::mem::take(&mut response.responses);
+                let results_len = results.len();
+                let (responses, errors): (Vec<_>, Vec<_>) = results.into_iter().partition(Result::is_ok);
                 let responses = responses.into_iter().map(Result::unwrap).collect::<Vec<_>>();
                 let errors = errors.into_iter().map(Result::unwrap_err).collect::<Vec<_>>();
 
+                // Logged before the request type is taken apart below, since the failed message is looked up from it.
+                for error in errors.iter() {
+                    match error.request_index.and_then(|request_index| response.get_request_message(request_index)) {
+                        Some(request_message) => log::error!("[{}][{}] Error in \"{}\": {}",
+                            response.host.name, response.source_id, secret::redact(request_message), error.message),
+                        None => log::error!("[{}][{}] Error: {}", response.host.name, response.source_id, error.message),
+                    }
+                }
+
                 let monitors = monitors.lock().unwrap();
                 let platform_info_providers = platform_info_providers.lock().unwrap();
                 let monitor_id = &response.source_id;
                 // Search from internal monitors first.
-                let monitor = platform_info_providers.get(monitor_id).unwrap_or_else(|| {
-                    &monitors[&response.host.name][monitor_id]
-                });
-                
+                let monitor = match platform_info_providers.get(monitor_id)
+                    .or_else(|| monitors.get(&response.host.name).and_then(|host_monitors| host_monitors.get(monitor_id))) {
+
+                    Some(monitor) => monitor,
+                    None => {
+                        // Monitor was disabled or reconfigured while the refresh was in progress.
+                        Self::cancel_refresh(&monitors, &in_flight, &waiting_dependents, &request_sender, &state_update_sender,
+                                             &response_sender, &response.host.name, monitor_id, response.invocation_id);
+                        continue;
+                    }
+                };
+
                 let (parent_datapoint, mut extension_monitors) = match response.request_type {
                     RequestType::MonitorCommand { parent_datapoint, extension_monitors, .. } => {
                         (parent_datapoint, extension_monitors)
@@ -407,10 +769,10 @@ impl MonitorManager {
 
 
                 let mut datapoint_result;
-                if results_len == 0 {
-                    // Some special modules require no connectors and receive no response messages, such
+                if monitor.is_independent() || results_len == 0 {
+                    // Independent modules require no connectors and receive no response messages, such
                     // as `os`, which only uses existing platform info.
-                    datapoint_result = monitor.process_response(response.host.clone(), ResponseMessage::empty(), DataPoint::empty());
+                    datapoint_result = monitor.process_independent(response.host.clone(), parent_datapoint.clone().unwrap_or_default());
                 }
                 else if responses.len() > 0 {
                     datapoint_result = monitor.process_responses(response.host.clone(), responses.clone(), parent_datapoint.clone().unwrap_or_default());
@@ -433,7 +795,10 @@ impl MonitorManager {
                 }
 
                 let new_data_point = match datapoint_result {
-                    Ok(data_point) => {
+                    Ok(mut data_point) => {
+                        if monitor.get_display_options().sort_multivalue {
+                            data_point.sort_multivalue_by_label();
+                        }
                         log::debug!("[{}][{}] Data point received: {} {}", response.host.name, monitor_id, data_point.label, data_point);
                         data_point
                     },
@@ -443,14 +808,17 @@ impl MonitorManager {
                     }
                 };
 
-                for error in errors.iter() {
-                    log::error!("[{}][{}] Error: {}", response.host.name, monitor_id, error.message);
-                }
-
                 if extension_monitors.len() > 0 {
                     // Process extension modules until the final result is reached.
                     let next_monitor_id = extension_monitors.remove(0);
-                    let next_monitor = &monitors[&response.host.name][&next_monitor_id];
+                    let next_monitor = match monitors.get(&response.host.name).and_then(|host_monitors| host_monitors.get(&next_monitor_id)) {
+                        Some(next_monitor) => next_monitor,
+                        None => {
+                            Self::cancel_refresh(&monitors, &in_flight, &waiting_dependents, &request_sender, &state_update_sender,
+                                                 &response_sender, &response.host.name, &next_monitor_id, response.invocation_id);
+                            continue;
+                        }
+                    };
                     let next_parent_datapoint = parent_datapoint.unwrap_or_else(|| new_data_point.clone());
 
                     let messages = match get_monitor_connector_messages(&response.host, &next_monitor, &next_parent_datapoint) {
@@ -467,7 +835,9 @@ impl MonitorManager {
                                 ..Default::default()
                             }).unwrap();
 
-                            return;
+                            Self::complete_refresh(&monitors, &in_flight, &waiting_dependents, &request_sender, &state_update_sender,
+                                                   &response_sender, &response.host.name, response.invocation_id, DataPoint::empty());
+                            continue;
                         }
                     };
 
@@ -481,6 +851,7 @@ impl MonitorManager {
                             parent_datapoint: Some(new_data_point.clone()),
                             extension_monitors: extension_monitors,
                             commands: messages,
+                            cache_key: next_monitor.get_cache_key(),
                         },
                     }).unwrap();
                 }
@@ -489,16 +860,214 @@ impl MonitorManager {
                         host_name: response.host.name.clone(),
                         display_options: monitor.get_display_options(),
                         module_spec: monitor.get_module_spec(),
-                        data_point: Some(new_data_point),
+                        data_point: Some(new_data_point.clone()),
                         errors: errors,
                         invocation_id: response.invocation_id,
                         ..Default::default()
                     }).unwrap();
+
+                    Self::complete_refresh(&monitors, &in_flight, &waiting_dependents, &request_sender, &state_update_sender,
+                                           &response_sender, &response.host.name, response.invocation_id, new_data_point);
                 }
             }
         })
     }
 
+    /// Marks the refresh as completed and sends the refreshes of dependent monitors that are no longer waiting for anything.
+    #[allow(clippy::too_many_arguments)]
+    fn complete_refresh(
+        monitors: &HashMap<String, HashMap<String, Monitor>>,
+        in_flight: &Mutex<HashMap<String, HashMap<String, InFlightRefresh>>>,
+        waiting_dependents: &Mutex<Vec<DependentRefresh>>,
+        request_sender: &mpsc::Sender<ConnectorRequest>,
+        state_update_sender: &mpsc::Sender<StateUpdateMessage>,
+        response_sender: &mpsc::Sender<RequestResponse>,
+        host_id: &String,
+        invocation_id: u64,
+        data_point: DataPoint) {
+
+        // Failing to send a dependent refresh completes it too, which may release other dependents.
+        let mut completed = vec![(invocation_id, data_point)];
+
+        while let Some((invocation_id, data_point)) = completed.pop() {
+            let completed_ids = Self::remove_in_flight(in_flight, host_id, invocation_id);
+
+            for dependent in Self::release_dependents(waiting_dependents, host_id, &completed_ids, &data_point) {
+                let monitor = match monitors.get(host_id).and_then(|host_monitors| host_monitors.get(&dependent.monitor_id)) {
+                    Some(monitor) => monitor,
+                    None => {
+                        log::debug!("[{}][{}] Ignoring refresh of removed monitor", host_id, dependent.monitor_id);
+
+                        state_update_sender.send(StateUpdateMessage {
+                            host_name: host_id.clone(),
+                            module_spec: ModuleSpecification::monitor(&dependent.monitor_id, "latest"),
+                            invocation_id: dependent.invocation_id,
+                            ..Default::default()
+                        }).unwrap();
+
+                        completed.push((dependent.invocation_id, DataPoint::empty()));
+                        continue;
+                    }
+                };
+                let mut parent_datapoint = DataPoint::empty();
+                parent_datapoint.multivalue = dependent.dependency_data;
+
+                let messages_result = if monitor.is_independent() {
+                    Ok(Vec::new())
+                }
+                else {
+                    get_monitor_connector_messages(&dependent.host, monitor, &parent_datapoint)
+                };
+
+                let messages = match messages_result {
+                    Ok(messages) => messages,
+                    Err(error) => {
+                        log::error!("[{}][{}] Monitor failed: {}", host_id, dependent.monitor_id, error);
+
+                        state_update_sender.send(StateUpdateMessage {
+                            host_name: host_id.clone(),
+                            display_options: monitor.get_display_options(),
+                            module_spec: monitor.get_module_spec(),
+                            errors: vec![error],
+                            invocation_id: dependent.invocation_id,
+                            ..Default::default()
+                        }).unwrap();
+
+                        completed.push((dependent.invocation_id, DataPoint::empty()));
+                        continue;
+                    }
+                };
+
+                request_sender.send(ConnectorRequest {
+                    connector_spec: monitor.get_connector_spec(),
+                    source_id: dependent.monitor_id,
+                    host: dependent.host,
+                    invocation_id: dependent.invocation_id,
+                    response_sender: response_sender.clone(),
+                    request_type: RequestType::MonitorCommand {
+                        parent_datapoint: Some(parent_datapoint),
+                        extension_monitors: dependent.extension_monitors,
+                        commands: messages,
+                        cache_key: monitor.get_cache_key(),
+                    },
+                }).unwrap();
+            }
+        }
+    }
+
+    /// Drops the response of a monitor that no longer exists. The refresh is still completed so it isn't left in progress.
+    #[allow(clippy::too_many_arguments)]
+    fn cancel_refresh(
+        monitors: &HashMap<String, HashMap<String, Monitor>>,
+        in_flight: &Mutex<HashMap<String, HashMap<String, InFlightRefresh>>>,
+        waiting_dependents: &Mutex<Vec<DependentRefresh>>,
+        request_sender: &mpsc::Sender<ConnectorRequest>,
+        state_update_sender: &mpsc::Sender<StateUpdateMessage>,
+        response_sender: &mpsc::Sender<RequestResponse>,
+        host_id: &String,
+        monitor_id: &String,
+        invocation_id: u64) {
+
+        log::debug!("[{}][{}] Ignoring response of removed monitor", host_id, monitor_id);
+
+        // Update without a data point only clears the pending invocation.
+        state_update_sender.send(StateUpdateMessage {
+            host_name: host_id.clone(),
+            module_spec: ModuleSpecification::monitor(monitor_id, "latest"),
+            invocation_id: invocation_id,
+            ..Default::default()
+        }).unwrap();
+
+        Self::complete_refresh(monitors, in_flight, waiting_dependents, request_sender, state_update_sender,
+                               response_sender, host_id, invocation_id, DataPoint::empty());
+    }
+
+    /// Response source is the last extension monitor, so the entry is found by invocation ID.
+    /// Returns the IDs of the base monitors that were removed.
+    fn remove_in_flight(in_flight: &Mutex<HashMap<String, HashMap<String, InFlightRefresh>>>, host_id: &String, invocation_id: u64) -> Vec<String> {
+        let mut removed_ids = Vec::new();
+        if let Some(host_in_flight) = in_flight.lock().unwrap().get_mut(host_id) {
+            host_in_flight.retain(|monitor_id, refresh| {
+                if refresh.invocation_id == invocation_id {
+                    removed_ids.push(monitor_id.clone());
+                }
+                refresh.invocation_id != invocation_id
+            });
+        }
+        removed_ids
+    }
+
+    /// Passes the data point to the dependents waiting for it. Returns the dependents that are no longer waiting for anything.
+    fn release_dependents(waiting_dependents: &Mutex<Vec<DependentRefresh>>,
+                          host_id: &String,
+                          completed_ids: &[String],
+                          data_point: &DataPoint) -> Vec<DependentRefresh> {
+
+        let mut waiting_dependents = waiting_dependents.lock().unwrap();
+
+        for dependent in waiting_dependents.iter_mut().filter(|dependent| &dependent.host.name == host_id) {
+            for completed_id in completed_ids {
+                if dependent.waiting_for.remove(completed_id) {
+                    let mut dependency_data = data_point.clone();
+                    dependency_data.label = completed_id.clone();
+                    dependent.dependency_data.push(dependency_data);
+                }
+            }
+        }
+
+        let (ready, still_waiting): (Vec<_>, Vec<_>) = waiting_dependents.drain(..).partition(|dependent| dependent.waiting_for.is_empty());
+        *waiting_dependents = still_waiting;
+        ready
+    }
+}
+
+/// Refresh that hasn't received a response yet.
+struct InFlightRefresh {
+    invocation_id: u64,
+    started: Instant,
+}
+
+impl InFlightRefresh {
+    fn new(invocation_id: u64) -> Self {
+        InFlightRefresh {
+            invocation_id: invocation_id,
+            started: Instant::now(),
+        }
+    }
+}
+
+/// Refresh of a monitor that depends on other monitors that were still being refreshed.
+struct DependentRefresh {
+    host: Host,
+    monitor_id: String,
+    invocation_id: u64,
+    extension_monitors: Vec<String>,
+    /// IDs of the monitors that haven't completed yet.
+    waiting_for: HashSet<String>,
+    /// Data points of completed dependencies, labeled with the monitor ID.
+    dependency_data: Vec<DataPoint>,
+}
+
+/// Tracks the progress of a refresh operation spanning multiple hosts.
+#[derive(Default, Clone)]
+pub struct RefreshProgress {
+    /// Host name is the key.
+    pub invocation_ids: HashMap<String, Vec<u64>>,
+}
+
+impl RefreshProgress {
+    pub fn total(&self) -> usize {
+        self.invocation_ids.values().map(|ids| ids.len()).sum()
+    }
+
+    /// Returns the number of finished invocations. `pending` contains the invocation IDs still in progress.
+    pub fn completed(&self, pending: &HashSet<u64>) -> usize {
+        self.invocation_ids.values().flatten().filter(|invocation_id| !pending.contains(invocation_id)).count()
+    }
+
+    pub fn is_done(&self, pending: &HashSet<u64>) -> bool {
+        self.completed(pending) == self.total()
+    }
 }
 
 fn get_monitor_connector_messages(host: &Host, monitor: &Monitor, parent_datapoint: &DataPoint) -> Result<Vec<String>, LkError> {
@@ -524,3 +1093,68 @@ fn get_monitor_connector_messages(host: &Host, monitor: &Monitor, parent_datapoi
 
     Ok(all_messages)
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependent(host_id: &str, monitor_id: &str, invocation_id: u64, waiting_for: &[&str]) -> DependentRefresh {
+        DependentRefresh {
+            host: Host::empty(host_id, &[]),
+            monitor_id: monitor_id.to_string(),
+            invocation_id: invocation_id,
+            extension_monitors: Vec::new(),
+            waiting_for: waiting_for.iter().map(|id| id.to_string()).collect(),
+            dependency_data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_remove_in_flight() {
+        let host_id = String::from("host1");
+        let mut host_in_flight = HashMap::new();
+        host_in_flight.insert(String::from("ram"), InFlightRefresh::new(1));
+        host_in_flight.insert(String::from("swap"), InFlightRefresh::new(1));
+        host_in_flight.insert(String::from("uptime"), InFlightRefresh::new(2));
+        let in_flight = Mutex::new(HashMap::from([(host_id.clone(), host_in_flight)]));
+
+        let mut removed_ids = MonitorManager::remove_in_flight(&in_flight, &host_id, 1);
+        removed_ids.sort();
+        assert_eq!(removed_ids, vec![String::from("ram"), String::from("swap")]);
+        assert!(in_flight.lock().unwrap()[&host_id].contains_key("uptime"));
+
+        // Already removed or unknown.
+        assert!(MonitorManager::remove_in_flight(&in_flight, &host_id, 1).is_empty());
+        assert!(MonitorManager::remove_in_flight(&in_flight, &String::from("host2"), 2).is_empty());
+    }
+
+    #[test]
+    fn test_release_dependents() {
+        let host_id = String::from("host1");
+        let waiting_dependents = Mutex::new(vec![
+            dependent("host1", "aggregate", 10, &["cpu", "ram"]),
+            dependent("host1", "cpu-alert", 11, &["cpu"]),
+            dependent("host2", "cpu-alert", 12, &["cpu"]),
+        ]);
+
+        let ready = MonitorManager::release_dependents(&waiting_dependents, &host_id, &[String::from("cpu")], &DataPoint::new("50"));
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].invocation_id, 11);
+        assert_eq!(ready[0].dependency_data[0].label, "cpu");
+        assert_eq!(ready[0].dependency_data[0].value, "50");
+
+        // Dependent with multiple dependencies waits for all of them. Other hosts are not affected.
+        assert_eq!(waiting_dependents.lock().unwrap().len(), 2);
+
+        let ready = MonitorManager::release_dependents(&waiting_dependents, &host_id, &[String::from("ram")], &DataPoint::new("20"));
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].invocation_id, 10);
+        let labels = ready[0].dependency_data.iter().map(|data_point| data_point.label.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["cpu", "ram"]);
+
+        let remaining = waiting_dependents.lock().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].host.name, "host2");
+    }
+}
@@ -0,0 +1,274 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::configuration::{Hosts, MonitorConfig, Preferences};
+use crate::frontend::UIUpdate;
+
+/// How often the schedule is checked for due refreshes.
+const SCHEDULER_TICK: Duration = Duration::from_secs(1);
+/// Upper limit for the backed-off refresh interval of hosts that are down.
+/// Monitors with a longer regular interval keep using it.
+const MAX_BACKOFF_INTERVAL: Duration = Duration::from_secs(300);
+
+
+/// Keeps track of when monitors should be refreshed automatically.
+/// The actual refreshing is done by MonitorManager in the UI thread, so due refreshes are sent through the UI update channel.
+#[derive(Default)]
+pub struct RefreshScheduler {
+    // Host name is the first key, monitor id is the second key.
+    schedule: Arc<Mutex<HashMap<String, HashMap<String, ScheduledRefresh>>>>,
+    /// Exponent for backing off refreshing of hosts that are down. Host name is the key.
+    backoff_levels: HashMap<String, u32>,
+    stop_sender: Option<mpsc::Sender<()>>,
+    scheduler_thread: Option<thread::JoinHandle<()>>,
+}
+
+#[derive(Clone)]
+struct ScheduledRefresh {
+    interval: Duration,
+    next_refresh: Instant,
+}
+
+impl RefreshScheduler {
+    pub fn configure(&mut self, hosts_config: &Hosts, preferences: &Preferences) {
+        self.stop();
+
+        self.backoff_levels.clear();
+        let mut schedule = self.schedule.lock().unwrap();
+        schedule.clear();
+
+        // Names prefixed with _ are reserved for internal use.
+        let host_ids = hosts_config.hosts.keys().filter(|host_id| !host_id.starts_with("_")).collect::<Vec<_>>();
+        let now = Instant::now();
+
+        for (index, host_id) in host_ids.iter().enumerate() {
+            let mut host_schedule = HashMap::new();
+
+            // Disabled monitors are included too since they can be enabled at runtime.
+            // MonitorManager will skip monitors that are not in use.
+            for (monitor_id, monitor_config) in hosts_config.hosts[*host_id].effective.monitors.iter() {
+                let interval = Self::get_interval(monitor_config, preferences);
+                if interval == 0 {
+                    continue;
+                }
+
+                let interval = Duration::from_secs(interval);
+                // Hosts are spread evenly over the interval so that they don't all get refreshed at the same time.
+                let offset = interval.mul_f64((index + 1) as f64 / host_ids.len() as f64);
+
+                host_schedule.insert(monitor_id.clone(), ScheduledRefresh {
+                    interval: interval,
+                    next_refresh: now + offset,
+                });
+            }
+
+            if !host_schedule.is_empty() {
+                schedule.insert(host_id.to_string(), host_schedule);
+            }
+        }
+
+        if !schedule.is_empty() {
+            log::debug!("Scheduled automatic refresh for {} hosts", schedule.len());
+        }
+    }
+
+    /// Starts the timer thread. Due refreshes are sent as `UIUpdate::ScheduledRefresh`.
+    pub fn start(&mut self, update_sender: mpsc::Sender<UIUpdate>) {
+        if self.scheduler_thread.is_some() || self.schedule.lock().unwrap().is_empty() {
+            return;
+        }
+
+        let (stop_sender, stop_receiver) = mpsc::channel::<()>();
+        self.stop_sender = Some(stop_sender);
+        let schedule = self.schedule.clone();
+
+        let thread = thread::spawn(move || {
+            log::debug!("Started refresh scheduler");
+
+            loop {
+                match stop_receiver.recv_timeout(SCHEDULER_TICK) {
+                    Err(mpsc::RecvTimeoutError::Timeout) => (),
+                    _ => {
+                        log::debug!("Gracefully stopping refresh scheduler thread");
+                        return;
+                    }
+                }
+
+                let due_refreshes = Self::take_due_refreshes(&mut schedule.lock().unwrap(), Instant::now());
+                if due_refreshes.is_empty() {
+                    continue;
+                }
+
+                if let Err(error) = update_sender.send(UIUpdate::ScheduledRefresh(due_refreshes)) {
+                    log::error!("Stopped refresh scheduler thread: {}", error);
+                    return;
+                }
+            }
+        });
+
+        self.scheduler_thread = Some(thread);
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(thread) = self.scheduler_thread.take() {
+            if let Some(stop_sender) = self.stop_sender.take() {
+                stop_sender.send(())
+                    .unwrap_or_else(|error| log::error!("Couldn't send exit token to refresh scheduler: {}", error));
+            }
+
+            thread.join().unwrap();
+        }
+    }
+
+    /// Postpones the next refresh of a host that is down. The delay doubles on every call until it reaches `MAX_BACKOFF_INTERVAL`.
+    pub fn back_off(&mut self, host_id: &String) {
+        let level = self.backoff_levels.entry(host_id.clone()).or_insert(0);
+        // Limited to avoid overflows. The interval has reached the maximum long before this anyway.
+        *level = (*level + 1).min(16);
+        let multiplier = 2_u32.pow(*level);
+
+        if let Some(host_schedule) = self.schedule.lock().unwrap().get_mut(host_id) {
+            let now = Instant::now();
+            for scheduled in host_schedule.values_mut() {
+                let delay = scheduled.interval.saturating_mul(multiplier).min(MAX_BACKOFF_INTERVAL.max(scheduled.interval));
+                scheduled.next_refresh = now + delay;
+            }
+        }
+
+        log::debug!("[{}] Host is down, backing off automatic refresh", host_id);
+    }
+
+    /// Returns to the normal refresh interval after the host has recovered.
+    pub fn reset_backoff(&mut self, host_id: &String) {
+        if self.backoff_levels.remove(host_id).is_some() {
+            log::debug!("[{}] Host recovered, resuming normal automatic refresh", host_id);
+        }
+    }
+
+    /// Per-monitor interval takes precedence. Critical monitors can use a separate interval.
+    fn get_interval(monitor_config: &MonitorConfig, preferences: &Preferences) -> u64 {
+        if let Some(interval) = monitor_config.interval {
+            interval
+        }
+        else if monitor_config.is_critical.unwrap_or(false) && preferences.refresh_interval_critical > 0 {
+            preferences.refresh_interval_critical
+        }
+        else {
+            preferences.refresh_interval
+        }
+    }
+
+    /// Returns the monitors that are due for refresh and schedules their next refresh. Host name is the key.
+    fn take_due_refreshes(schedule: &mut HashMap<String, HashMap<String, ScheduledRefresh>>, now: Instant) -> HashMap<String, Vec<String>> {
+        let mut due_refreshes = HashMap::<String, Vec<String>>::new();
+
+        for (host_id, host_schedule) in schedule.iter_mut() {
+            for (monitor_id, scheduled) in host_schedule.iter_mut() {
+                if scheduled.next_refresh <= now {
+                    scheduled.next_refresh = now + scheduled.interval;
+                    due_refreshes.entry(host_id.clone()).or_default().push(monitor_id.clone());
+                }
+            }
+        }
+
+        due_refreshes
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduled(interval: u64, next_refresh: Instant) -> ScheduledRefresh {
+        ScheduledRefresh {
+            interval: Duration::from_secs(interval),
+            next_refresh: next_refresh,
+        }
+    }
+
+    #[test]
+    fn test_take_due_refreshes() {
+        let start = Instant::now();
+        let now = start + Duration::from_secs(100);
+        let mut schedule = HashMap::from([
+            (String::from("host1"), HashMap::from([
+                (String::from("due"), scheduled(60, now)),
+                (String::from("not-due"), scheduled(60, now + Duration::from_secs(1))),
+            ])),
+            (String::from("host2"), HashMap::from([
+                (String::from("overdue"), scheduled(30, start)),
+            ])),
+        ]);
+
+        let due_refreshes = RefreshScheduler::take_due_refreshes(&mut schedule, now);
+        assert_eq!(due_refreshes.len(), 2);
+        assert_eq!(due_refreshes["host1"], vec![String::from("due")]);
+        assert_eq!(due_refreshes["host2"], vec![String::from("overdue")]);
+
+        // Next refresh is counted from now, so missed refreshes aren't caught up on.
+        assert!(schedule["host2"]["overdue"].next_refresh == now + Duration::from_secs(30));
+        assert!(schedule["host1"]["due"].next_refresh == now + Duration::from_secs(60));
+
+        let due_refreshes = RefreshScheduler::take_due_refreshes(&mut schedule, now + Duration::from_secs(1));
+        assert_eq!(due_refreshes.len(), 1);
+        assert_eq!(due_refreshes["host1"], vec![String::from("not-due")]);
+
+        assert!(RefreshScheduler::take_due_refreshes(&mut schedule, now + Duration::from_secs(2)).is_empty());
+    }
+
+    /// Returns the delays from `before` to the next refreshes of the host's monitors.
+    fn backed_off_delays(scheduler: &mut RefreshScheduler, before: Instant) -> HashMap<String, Duration> {
+        scheduler.back_off(&String::from("host1"));
+        scheduler.schedule.lock().unwrap()["host1"].iter()
+            .map(|(monitor_id, scheduled)| (monitor_id.clone(), scheduled.next_refresh.duration_since(before)))
+            .collect()
+    }
+
+    fn assert_about(delay: Duration, expected_secs: u64) {
+        assert!(delay >= Duration::from_secs(expected_secs) && delay < Duration::from_secs(expected_secs + 1),
+                "Expected about {}s, got {:?}", expected_secs, delay);
+    }
+
+    #[test]
+    fn test_back_off() {
+        let now = Instant::now();
+        let mut scheduler = RefreshScheduler::default();
+        scheduler.schedule.lock().unwrap().insert(String::from("host1"), HashMap::from([
+            (String::from("short"), scheduled(60, now)),
+            (String::from("long"), scheduled(600, now)),
+        ]));
+
+        // Doubles every time until the maximum. Longer regular intervals are kept as they are.
+        let delays = backed_off_delays(&mut scheduler, now);
+        assert_about(delays["short"], 120);
+        assert_about(delays["long"], 600);
+
+        let delays = backed_off_delays(&mut scheduler, now);
+        assert_about(delays["short"], 240);
+
+        let delays = backed_off_delays(&mut scheduler, now);
+        assert_about(delays["short"], 300);
+
+        // Level is capped, so this doesn't overflow.
+        for _ in 0..40 {
+            scheduler.back_off(&String::from("host1"));
+        }
+        let delays = backed_off_delays(&mut scheduler, now);
+        assert_about(delays["short"], 300);
+        assert_about(delays["long"], 600);
+
+        scheduler.reset_backoff(&String::from("host1"));
+        assert!(scheduler.backoff_levels.is_empty());
+        let delays = backed_off_delays(&mut scheduler, now);
+        assert_about(delays["short"], 120);
+    }
+}
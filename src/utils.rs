@@ -12,9 +12,18 @@ pub use version_number::VersionNumber;
 pub mod string_validation;
 
 pub mod shell_command;
-pub use shell_command::ShellCommand;
+pub use shell_command::{ShellCommand, shell_quote};
 
 pub mod error_message;
 pub use error_message::ErrorMessage;
 
 pub mod sha256;
+
+pub mod secret;
+pub use secret::Secret;
+
+pub mod invocation_id;
+pub use invocation_id::next_invocation_id;
+
+pub mod units;
+pub use units::{UnitSystem, ByteUnits, TemperatureUnit};
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use chrono::{DateTime, Utc};
 use serde_derive::{Deserialize, Serialize};
 
 use crate::{enums::Criticality, error::LkError};
@@ -11,6 +12,28 @@ use crate::{enums::Criticality, error::LkError};
 pub struct ErrorMessage {
     pub message: String,
     pub criticality: Criticality,
+    /// When the error occurred.
+    #[serde(default = "Utc::now")]
+    pub time: DateTime<Utc>,
+    /// ID of the module the error originated from. Can be empty.
+    #[serde(default)]
+    pub source_id: String,
+}
+
+impl ErrorMessage {
+    pub fn new(criticality: Criticality, message: String) -> Self {
+        ErrorMessage {
+            message: message,
+            criticality: criticality,
+            time: Utc::now(),
+            source_id: String::new(),
+        }
+    }
+
+    pub fn with_source(mut self, source_id: &str) -> Self {
+        self.source_id = source_id.to_string();
+        self
+    }
 }
 
 impl From<LkError> for ErrorMessage {
@@ -18,6 +41,8 @@ impl From<LkError> for ErrorMessage {
         ErrorMessage {
             message: error.to_string(),
             criticality: Criticality::Error,
+            time: Utc::now(),
+            source_id: error.source_id,
         }
     }
 }
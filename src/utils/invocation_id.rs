@@ -0,0 +1,28 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static LATEST_INVOCATION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Returns a new invocation ID. IDs are shared by monitors and commands, so they are unique within the session.
+/// IDs are never less than the current time in microseconds since epoch, so they also keep increasing
+/// across restarts unless the system clock is set backwards. ID 0 is never returned since it means "no invocation".
+/// Values stay below 2^53 (until year 2255), so they are also exact as JavaScript numbers in QML.
+pub fn next_invocation_id() -> u64 {
+    let mut latest = LATEST_INVOCATION_ID.load(Ordering::Relaxed);
+    loop {
+        let next = std::cmp::max(latest.saturating_add(1), current_time_micros());
+        match LATEST_INVOCATION_ID.compare_exchange_weak(latest, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => return next,
+            Err(actual) => latest = actual,
+        }
+    }
+}
+
+fn current_time_micros() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_micros() as u64).unwrap_or(0)
+}
@@ -0,0 +1,142 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::error::LkError;
+use crate::utils::{shell_quote, strip_newline, ShellCommand};
+
+/// Attribute name used for keyring lookups.
+/// Secrets can be stored with e.g. `secret-tool store --label="Lightkeeper" lightkeeper <secret_ref>`.
+const KEYRING_ATTRIBUTE: &str = "lightkeeper";
+const REDACTED: &str = "********";
+
+/// Known secret values that are masked by `redact`.
+static REDACTED_VALUES: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+
+/// Source of a secret, such as a password or a passphrase, in connector settings.
+/// Keyring and command secrets are resolved when needed, so the secret itself is never written to configuration.
+#[derive(Clone, Default)]
+pub enum Secret {
+    #[default]
+    None,
+    /// Explicit value in configuration, e.g. `password`.
+    Value(String),
+    /// Reference to a secret in OS keyring (Secret Service), e.g. `password_secret_ref`.
+    KeyringRef(String),
+    /// Command that outputs the secret, e.g. `password_secret_command`.
+    Command(String),
+}
+
+impl Secret {
+    /// Precedence is: explicit value, keyring reference, secret command.
+    pub fn from_settings(settings: &HashMap<String, String>, setting_name: &str) -> Secret {
+        if let Some(value) = settings.get(setting_name) {
+            Secret::Value(value.clone())
+        }
+        else if let Some(secret_ref) = settings.get(&format!("{}_secret_ref", setting_name)) {
+            Secret::KeyringRef(secret_ref.clone())
+        }
+        else if let Some(secret_command) = settings.get(&format!("{}_secret_command", setting_name)) {
+            Secret::Command(secret_command.clone())
+        }
+        else {
+            Secret::None
+        }
+    }
+
+    pub fn is_set(&self) -> bool {
+        !matches!(self, Secret::None)
+    }
+
+    /// Returns None if the secret is not set. Resolved values are masked in logs (see `redact`).
+    pub fn resolve(&self) -> Result<Option<String>, LkError> {
+        let command = match self {
+            Secret::None => return Ok(None),
+            Secret::Value(value) => {
+                add_redacted_value(value);
+                return Ok(Some(value.clone()));
+            },
+            Secret::KeyringRef(secret_ref) => ShellCommand::new_from(vec!["secret-tool", "lookup", KEYRING_ATTRIBUTE, secret_ref]),
+            Secret::Command(secret_command) => {
+                // Secret commands can contain credentials themselves, e.g. for accessing a vault.
+                let mut command = ShellCommand::new_from(vec!["sh", "-c"]);
+                command.secret_argument(secret_command.clone());
+                command
+            },
+        };
+
+        let output = command.execute()
+            .map_err(|error| LkError::other(format!("Failed to resolve secret: {}", error)))?;
+
+        if !output.status.success() {
+            // Output is not included since it could contain parts of the secret.
+            return Err(LkError::other(format!("Failed to resolve secret. Command exited with {}", output.status)));
+        }
+
+        let secret = strip_newline(&String::from_utf8_lossy(&output.stdout));
+        add_redacted_value(&secret);
+        Ok(Some(secret))
+    }
+}
+
+/// Masks the value in everything passed through `redact` from now on.
+pub fn add_redacted_value(value: &str) {
+    if value.is_empty() {
+        return;
+    }
+
+    let mut redacted_values = REDACTED_VALUES.write().unwrap();
+    if !redacted_values.iter().any(|existing| existing == value) {
+        redacted_values.push(value.to_string());
+        // Longest first so that secrets containing other secrets are fully masked.
+        redacted_values.sort_by(|left, right| right.len().cmp(&left.len()));
+    }
+}
+
+/// Masks known secret values. Should be used for anything logged that could contain secrets, e.g. commands.
+pub fn redact(text: &str) -> String {
+    let mut result = text.to_string();
+    for value in REDACTED_VALUES.read().unwrap().iter() {
+        // Single quotes are escaped in quoted command arguments, so the value wouldn't match as-is.
+        if value.contains('\'') {
+            result = result.replace(&shell_quote(value), REDACTED);
+        }
+        result = result.replace(value, REDACTED);
+    }
+    result
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        add_redacted_value("hunter2-test-redact");
+        assert_eq!(redact("password is hunter2-test-redact."), "password is ********.");
+        assert_eq!(redact("nothing to hide"), "nothing to hide");
+    }
+
+    #[test]
+    fn test_redact_shell_quoted() {
+        let mut command = ShellCommand::new_from(vec!["login", "--password"]);
+        command.secret_argument("it's-test-redact-quoted");
+
+        let redacted = redact(&command.to_string());
+        assert_eq!(redacted, "'login' '--password' ********");
+        assert!(!redacted.contains("test-redact-quoted"));
+    }
+
+    #[test]
+    fn test_redact_resolved_value() {
+        let secret = Secret::Value(String::from("test-redact-resolved"));
+        assert_eq!(secret.resolve().unwrap(), Some(String::from("test-redact-resolved")));
+        assert_eq!(redact("'sudo' test-redact-resolved"), "'sudo' ********");
+    }
+}
@@ -5,6 +5,10 @@
 
 use sha2::{Digest, Sha256};
 
+/// For hashing data incrementally, e.g. while streaming.
+pub use sha2::Sha256 as Hasher;
+pub use sha2::Digest;
+
 pub fn hash(contents: &[u8]) -> String {
     let mut hasher = Sha256::new();
     hasher.update(contents);
@@ -12,3 +16,7 @@ pub fn hash(contents: &[u8]) -> String {
 
     hex::encode(hash)
 }
+
+pub fn finalize(hasher: Hasher) -> String {
+    hex::encode(hasher.finalize())
+}
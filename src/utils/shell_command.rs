@@ -4,13 +4,21 @@
  */
 
 use std::collections::VecDeque;
+use std::io::Write;
 use std::process;
+use std::thread;
+
+use base64::Engine;
+
+use crate::utils::secret;
 
 /// For building command line commands correctly.
 pub struct ShellCommand {
     arguments: VecDeque<String>,
     piped_to: VecDeque<Vec<String>>,
+    stdin: Option<Vec<u8>>,
     pub ignore_stderr: bool,
+    /// Plain sudo is used here. The SSH connector replaces it with its `sudo_command` setting if set.
     pub use_sudo: bool,
 }
 
@@ -19,6 +27,7 @@ impl ShellCommand {
         ShellCommand {
             arguments: VecDeque::new(),
             piped_to: VecDeque::new(),
+            stdin: None,
             ignore_stderr: false,
             use_sudo: false,
         }
@@ -41,6 +50,17 @@ impl ShellCommand {
         self
     }
 
+    /// Argument that contains a secret, e.g. a password. It's masked in logged commands (see `secret::redact`).
+    pub fn secret_argument<IntoString>(&mut self, argument: IntoString) -> &mut Self
+    where
+        IntoString: Into<String>,
+    {
+        let argument = argument.into();
+        secret::add_redacted_value(&argument);
+        self.arguments.push_back(argument);
+        self
+    }
+
     pub fn arguments<IntoString>(&mut self, arguments: Vec<IntoString>) -> &mut Self
     where
         IntoString: Into<String>,
@@ -60,6 +80,14 @@ impl ShellCommand {
         self
     }
 
+    /// Data that is passed to the command through stdin.
+    /// When sent to a remote host as a string, the data is included in the command itself, so it's not suitable for large amounts of data.
+    /// Note that sudo can't then read a password from stdin, so it requires passwordless sudo.
+    pub fn stdin(&mut self, data: Vec<u8>) -> &mut Self {
+        self.stdin = Some(data);
+        self
+    }
+
     pub fn execute(&self) -> std::io::Result<process::Output> {
         if self.arguments.is_empty() {
             return Err(std::io::Error::new(std::io::ErrorKind::Other, "No command specified"));
@@ -68,11 +96,26 @@ impl ShellCommand {
         let command = self.arguments.get(0).unwrap();
         let arguments = self.arguments.iter().skip(1).collect::<Vec<&String>>();
 
-        if arguments.is_empty() {
-            process::Command::new(command).output()
-        }
-        else {
-            process::Command::new(command).args(arguments).output()
+        match &self.stdin {
+            None => process::Command::new(command).args(arguments).output(),
+            Some(data) => {
+                let mut child = process::Command::new(command)
+                    .args(arguments)
+                    .stdin(process::Stdio::piped())
+                    .stdout(process::Stdio::piped())
+                    .stderr(process::Stdio::piped())
+                    .spawn()?;
+
+                // Written from another thread since the command can block on a full stdout pipe before reading all of stdin.
+                // Stdin is closed when dropped so the command will receive EOF.
+                let mut stdin = child.stdin.take().unwrap();
+                let data = data.clone();
+                let writer = thread::spawn(move || stdin.write_all(&data));
+
+                let output = child.wait_with_output()?;
+                writer.join().map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "Writing to stdin failed"))??;
+                Ok(output)
+            }
         }
     }
 
@@ -87,38 +130,96 @@ impl ToString for ShellCommand {
             String::new()
         }
         else {
-            let mut command;
+            let mut arguments = self.arguments.iter().map(|argument| shell_quote(argument)).collect::<Vec<_>>();
             if self.use_sudo {
-                command = process::Command::new("sudo");
-                for argument in self.arguments.iter() {
-                    command.arg(argument);
-                }
+                arguments.insert(0, shell_quote("sudo"));
             }
-            else {
-                command = process::Command::new(&self.arguments[0]);
-                for argument in self.arguments.iter().skip(1) {
-                    command.arg(argument);
-                }
-            };
 
-            let mut command_string = format!("{:?}", command);
+            let mut command_string = arguments.join(" ");
+
+            // Data is base64-encoded so that binary data and special characters survive intact.
+            if let Some(data) = &self.stdin {
+                let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+                command_string = format!("{} {} {} | {} {} | {}",
+                    shell_quote("printf"), shell_quote("%s"), shell_quote(&encoded), shell_quote("base64"), shell_quote("-d"), command_string);
+            }
 
             if self.ignore_stderr {
                 command_string = format!("{} 2>/dev/null", command_string);
             }
 
-            if !self.piped_to.is_empty() {
-                for piped_arguments in self.piped_to.iter() {
-                    let mut piped_command = process::Command::new(&piped_arguments[0]);
-                    for argument in piped_arguments.iter().skip(1) {
-                        piped_command.arg(argument);
-                    }
-
-                    command_string = format!("{} | {:?}", command_string, piped_command);
-                }
+            for piped_arguments in self.piped_to.iter() {
+                let piped_command = piped_arguments.iter().map(|argument| shell_quote(argument)).collect::<Vec<_>>().join(" ");
+                command_string = format!("{} | {}", command_string, piped_command);
             }
 
             command_string
         }
     }
 }
+
+/// Quotes an argument for POSIX shells so that it's always passed as-is, without any expansions.
+/// Single quotes inside the argument are handled by closing the quotes, adding an escaped quote and reopening the quotes.
+pub fn shell_quote(argument: &str) -> String {
+    format!("'{}'", argument.replace('\'', "'\\''"))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("simple"), "'simple'");
+        assert_eq!(shell_quote("with spaces"), "'with spaces'");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote("$(rm -rf /)"), "'$(rm -rf /)'");
+        assert_eq!(shell_quote("`id`; echo \"$HOME\""), "'`id`; echo \"$HOME\"'");
+    }
+
+    #[test]
+    fn test_to_string() {
+        assert_eq!(ShellCommand::new().to_string(), "");
+
+        let command = ShellCommand::new_from(vec!["echo", "it's", "", "$(id)", "a b"]);
+        assert_eq!(command.to_string(), "'echo' 'it'\\''s' '' '$(id)' 'a b'");
+    }
+
+    #[test]
+    fn test_to_string_use_sudo() {
+        let mut command = ShellCommand::new_from(vec!["ls", "-l"]);
+        command.use_sudo = true;
+        assert_eq!(command.to_string(), "'sudo' 'ls' '-l'");
+    }
+
+    #[test]
+    fn test_to_string_ignore_stderr_and_pipe_to() {
+        let mut command = ShellCommand::new_from(vec!["cat", "/etc/hosts"]);
+        command.pipe_to(vec!["grep", "local host"]);
+        assert_eq!(command.to_string(), "'cat' '/etc/hosts' | 'grep' 'local host'");
+
+        command.ignore_stderr = true;
+        command.pipe_to(vec!["wc", "-l"]);
+        assert_eq!(command.to_string(), "'cat' '/etc/hosts' 2>/dev/null | 'grep' 'local host' | 'wc' '-l'");
+    }
+
+    #[test]
+    fn test_to_string_stdin() {
+        let mut command = ShellCommand::new_from(vec!["tee", "/tmp/file"]);
+        command.use_sudo = true;
+        command.stdin(b"hello".to_vec());
+        assert_eq!(command.to_string(), "'printf' '%s' 'aGVsbG8=' | 'base64' '-d' | 'sudo' 'tee' '/tmp/file'");
+    }
+
+    #[test]
+    fn test_execute_stdin() {
+        let mut command = ShellCommand::new_from(vec!["cat"]);
+        command.stdin(b"it's $(data)".to_vec());
+
+        let output = command.execute().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"it's $(data)");
+    }
+}
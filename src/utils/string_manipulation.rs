@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use super::units::{self, ByteUnits};
+
 pub fn strip_newline<Stringable: ToString>(input: &Stringable) -> String {
     let input = input.to_string();
     input.strip_suffix("\r\n").or(input.strip_suffix('\n')).unwrap_or(&input).to_string()
@@ -38,3 +40,105 @@ pub fn remove_quotes<Stringable: ToString>(input: &Stringable) -> String {
         .unwrap_or(&input)
         .to_string()
 }
+
+
+const BYTE_UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+const SI_BYTE_UNITS: [&str; 6] = ["B", "kB", "MB", "GB", "TB", "PB"];
+
+/// Formats byte count using the configured unit system, e.g. `1.5 GiB` or `1.6 GB`.
+pub fn format_bytes(bytes: u64) -> String {
+    let (divisor, units) = match units::get_unit_system().byte_units {
+        ByteUnits::Binary => (1024.0, BYTE_UNITS),
+        ByteUnits::Si => (1000.0, SI_BYTE_UNITS),
+    };
+
+    let mut value = bytes as f64;
+    let mut unit_index = 0;
+
+    while value >= divisor && unit_index < units.len() - 1 {
+        value /= divisor;
+        unit_index += 1;
+    }
+
+    if unit_index == 0 {
+        format!("{} {}", bytes, units[0])
+    }
+    else {
+        format!("{:.1} {}", value, units[unit_index])
+    }
+}
+
+/// Parses sizes such as `512`, `10G` or `1.5 GiB` to bytes. Units are always binary (powers of 1024) and case-insensitive.
+/// SI units such as `GB` are rejected since they would be ambiguous.
+pub fn parse_bytes(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let unit_start = input.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(input.len());
+    let (number, unit) = input.split_at(unit_start);
+
+    let number = number.parse::<f64>().ok()?;
+    let exponent = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kib" => 1,
+        "m" | "mib" => 2,
+        "g" | "gib" => 3,
+        "t" | "tib" => 4,
+        "p" | "pib" => 5,
+        _ => return None,
+    };
+
+    Some((number * 1024_f64.powi(exponent)) as u64)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::units::UnitSystem;
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(parse_bytes("512"), Some(512));
+        assert_eq!(parse_bytes(" 2k "), Some(2048));
+        assert_eq!(parse_bytes("10G"), Some(10 * 1024 * 1024 * 1024));
+        assert_eq!(parse_bytes("1.5 GiB"), Some(1610612736));
+        assert_eq!(parse_bytes("1.5 gib"), Some(1610612736));
+        assert_eq!(parse_bytes("3 B"), Some(3));
+        assert_eq!(parse_bytes("1P"), Some(1024_u64.pow(5)));
+
+        // Ambiguous SI units.
+        assert_eq!(parse_bytes("80GB"), None);
+        assert_eq!(parse_bytes("1 kB"), None);
+
+        assert_eq!(parse_bytes(""), None);
+        assert_eq!(parse_bytes("GiB"), None);
+        assert_eq!(parse_bytes("10 XiB"), None);
+        assert_eq!(parse_bytes("-1G"), None);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        // Unit system is global, so both variants are tested here.
+        set_byte_units(ByteUnits::Binary);
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(1610612736), "1.5 GiB");
+        assert_eq!(format_bytes(u64::MAX), "16384.0 PiB");
+
+        set_byte_units(ByteUnits::Si);
+        assert_eq!(format_bytes(999), "999 B");
+        assert_eq!(format_bytes(1000), "1.0 kB");
+        assert_eq!(format_bytes(1_600_000_000), "1.6 GB");
+        assert_eq!(format_bytes(1610612736), "1.6 GB");
+
+        set_byte_units(ByteUnits::Binary);
+    }
+
+    fn set_byte_units(byte_units: ByteUnits) {
+        units::set_unit_system(UnitSystem {
+            byte_units: byte_units,
+            ..units::get_unit_system()
+        });
+    }
+}
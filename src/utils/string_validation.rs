@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use crate::error::LkError;
+
 const DECIMAL_SEPARATOR: char = '.';
 
 pub fn is_alphanumeric(string: &str) -> bool {
@@ -18,6 +20,11 @@ pub fn begins_with_dash(string: &str) -> bool {
     string.chars().next().unwrap_or(' ') == '-'
 }
 
+// Absolute or relative time as accepted by e.g. journalctl and docker (e.g. "2025-01-01 12:00:00", "-1h", "now").
+pub fn is_time_expression(string: &str) -> bool {
+    is_alphanumeric_with(string, " -:+.")
+}
+
 pub fn is_numeric(string: &str) -> bool {
     string.parse::<f64>().is_ok()
 }
@@ -39,3 +46,11 @@ pub fn is_numeric_with_unit(string: &str, valid_units: &[String]) -> bool {
     let unit_string = unit_chars.trim().to_string();
     !unit_string.is_empty() && valid_units.contains(&unit_string)
 }
+
+// Count parameter, e.g. page size. Empty value means the default. Anything else has to be a non-negative integer.
+pub fn parse_count(value: Option<&String>, default: u32) -> Result<u32, LkError> {
+    match value {
+        Some(value) if !value.is_empty() => value.parse::<u32>().map_err(|_| LkError::invalid_parameter("Invalid number", value)),
+        _ => Ok(default),
+    }
+}
@@ -0,0 +1,57 @@
+/*
+ * SPDX-FileCopyrightText: Copyright (C) 2025 kalaksi@users.noreply.github.com
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::sync::RwLock;
+
+use serde_derive::{Deserialize, Serialize};
+
+/// Set on startup from preferences. Monitors use it when formatting values in `process_response`.
+static UNIT_SYSTEM: RwLock<UnitSystem> = RwLock::new(UnitSystem {
+    byte_units: ByteUnits::Binary,
+    temperature_unit: TemperatureUnit::Celsius,
+});
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ByteUnits {
+    /// Powers of 1024, e.g. MiB.
+    #[default]
+    Binary,
+    /// Powers of 1000, e.g. MB.
+    Si,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct UnitSystem {
+    #[serde(default)]
+    pub byte_units: ByteUnits,
+    #[serde(default)]
+    pub temperature_unit: TemperatureUnit,
+}
+
+pub fn set_unit_system(unit_system: UnitSystem) {
+    *UNIT_SYSTEM.write().unwrap() = unit_system;
+}
+
+pub fn get_unit_system() -> UnitSystem {
+    *UNIT_SYSTEM.read().unwrap()
+}
+
+/// Converts a temperature in Celsius to the configured unit. Returns the converted value and unit symbol.
+pub fn convert_temperature(celsius: f32) -> (f32, &'static str) {
+    match get_unit_system().temperature_unit {
+        TemperatureUnit::Celsius => (celsius, "°C"),
+        TemperatureUnit::Fahrenheit => (celsius * 9.0 / 5.0 + 32.0, "°F"),
+    }
+}